@@ -0,0 +1,657 @@
+//! Syntax tree for TypeScript declaration files.
+//!
+//! Every node carries the byte [`Span`] it was parsed from. The tree stays close to the
+//! surface syntax: parenthesized types are dropped, but nothing else is desugared.
+
+use crate::span::Span;
+
+/// A parsed `.d.ts` file, or the body of a namespace or module block.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Module {
+    pub items: Vec<Item>,
+    /// The `/// <reference ... />` directives at the top of a file. Always empty for blocks.
+    pub references: Vec<Reference>,
+    pub span: Span,
+}
+
+impl Module {
+    /// Whether the file is an ES module rather than a script, which TypeScript decides by
+    /// the presence of top-level imports and exports.
+    pub fn is_es_module(&self) -> bool {
+        self.items.iter().any(|item| {
+            item.export
+                || matches!(
+                    item.kind,
+                    ItemKind::Import(_) | ItemKind::Export(_) | ItemKind::ImportAlias(_)
+                )
+        })
+    }
+}
+
+/// A triple-slash directive such as `/// <reference path="./other.d.ts" />`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reference {
+    pub kind: ReferenceKind,
+    pub value: String,
+    pub span: Span,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ReferenceKind {
+    /// `path="..."`: another file, relative to this one.
+    Path,
+    /// `types="..."`: the declarations of a package.
+    Types,
+    /// `lib="..."`: one of TypeScript's built-in libraries, such as `dom`.
+    Lib,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ident {
+    pub name: String,
+    pub span: Span,
+}
+
+/// A possibly qualified name such as `Foo.Bar.Baz`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EntityName {
+    pub parts: Vec<Ident>,
+    pub span: Span,
+}
+
+impl EntityName {
+    /// The last component of the name.
+    pub fn last(&self) -> &Ident {
+        self.parts.last().expect("entity names are never empty")
+    }
+
+    /// The name joined back together with dots.
+    pub fn to_dotted(&self) -> String {
+        let names: Vec<&str> = self.parts.iter().map(|p| p.name.as_str()).collect();
+        names.join(".")
+    }
+}
+
+/// A top-level statement or a statement inside a namespace body.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Item {
+    pub kind: ItemKind,
+    /// Whether the item was written with `export`.
+    pub export: bool,
+    /// Whether the item was written with `export default`.
+    pub default: bool,
+    /// Whether the item was written with `declare`.
+    pub declare: bool,
+    pub doc: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ItemKind {
+    Interface(InterfaceDecl),
+    TypeAlias(TypeAliasDecl),
+    Class(ClassDecl),
+    Function(FunctionDecl),
+    Variable(VariableDecl),
+    Enum(EnumDecl),
+    Namespace(NamespaceDecl),
+    Module(ModuleDecl),
+    /// `declare global { ... }`, declarations that a module adds to the global scope.
+    Global(Module),
+    Import(ImportDecl),
+    ImportAlias(ImportAliasDecl),
+    Export(ExportDecl),
+}
+
+impl ItemKind {
+    /// The name the item declares, if it declares a single name.
+    pub fn name(&self) -> Option<&Ident> {
+        match self {
+            ItemKind::Interface(d) => Some(&d.name),
+            ItemKind::TypeAlias(d) => Some(&d.name),
+            ItemKind::Class(d) => d.name.as_ref(),
+            ItemKind::Function(d) => d.name.as_ref(),
+            ItemKind::Enum(d) => Some(&d.name),
+            ItemKind::Namespace(d) => d.name.parts.first(),
+            ItemKind::ImportAlias(d) => Some(&d.name),
+            ItemKind::Variable(_)
+            | ItemKind::Module(_)
+            | ItemKind::Global(_)
+            | ItemKind::Import(_)
+            | ItemKind::Export(_) => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeParam {
+    pub name: Ident,
+    pub constraint: Option<Type>,
+    pub default: Option<Type>,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct InterfaceDecl {
+    pub name: Ident,
+    pub type_params: Vec<TypeParam>,
+    pub extends: Vec<TypeRef>,
+    pub members: Vec<Member>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeAliasDecl {
+    pub name: Ident,
+    pub type_params: Vec<TypeParam>,
+    pub ty: Type,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClassDecl {
+    /// `None` only for `export default class { ... }`.
+    pub name: Option<Ident>,
+    pub is_abstract: bool,
+    pub type_params: Vec<TypeParam>,
+    pub extends: Option<TypeRef>,
+    pub implements: Vec<TypeRef>,
+    pub members: Vec<ClassMember>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FunctionDecl {
+    /// `None` only for `export default function (...)`.
+    pub name: Option<Ident>,
+    pub sig: Signature,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VariableKind {
+    Var,
+    Let,
+    Const,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct VariableDecl {
+    pub kind: VariableKind,
+    pub declarators: Vec<VariableDeclarator>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct VariableDeclarator {
+    pub name: Ident,
+    pub ty: Option<Type>,
+    pub init: Option<Expr>,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnumDecl {
+    pub name: Ident,
+    pub is_const: bool,
+    pub members: Vec<EnumMember>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnumMember {
+    pub name: PropName,
+    pub init: Option<Expr>,
+    pub doc: Option<String>,
+    pub span: Span,
+}
+
+/// `namespace A.B.C { ... }` (or the legacy `module A.B.C { ... }`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct NamespaceDecl {
+    pub name: EntityName,
+    pub body: Module,
+}
+
+/// `declare module "name" { ... }`. The body is absent for shorthand ambient modules
+/// (`declare module "name";`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModuleDecl {
+    pub name: String,
+    pub name_span: Span,
+    pub body: Option<Module>,
+}
+
+/// `import X, { a, b as c } from "m"`, `import * as ns from "m"`, `import "m"` or
+/// `import ns = require("m")`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportDecl {
+    /// Whether the import was written `import type`.
+    pub type_only: bool,
+    /// The local name of the default export.
+    pub default: Option<Ident>,
+    /// The local name of the whole module.
+    pub namespace: Option<Ident>,
+    pub named: Vec<Specifier>,
+    pub module: String,
+    pub module_span: Span,
+}
+
+/// `import X = A.B.C`, a local name for a namespace member.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportAliasDecl {
+    pub name: Ident,
+    pub target: EntityName,
+}
+
+/// `a` or `a as b` in an import or export list.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Specifier {
+    pub name: Ident,
+    pub alias: Option<Ident>,
+    /// Whether the specifier was written `type a`.
+    pub type_only: bool,
+}
+
+impl Specifier {
+    /// The name the specifier introduces: the alias if there is one.
+    pub fn local(&self) -> &Ident {
+        self.alias.as_ref().unwrap_or(&self.name)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExportDecl {
+    /// `export * from "m"` or `export * as ns from "m"`.
+    All {
+        alias: Option<Ident>,
+        module: String,
+        module_span: Span,
+    },
+    /// `export { a, b as c }`, or `export { a, b as c } from "m"`.
+    Named {
+        specifiers: Vec<Specifier>,
+        module: Option<(String, Span)>,
+    },
+    /// `export default name;`
+    Default(Ident),
+    /// `export = name;`, which makes the module the value `name` refers to, in the CommonJS
+    /// fashion.
+    Assign(EntityName),
+    /// `export as namespace Name;`, the global a UMD library defines when it is loaded by a
+    /// script rather than imported.
+    AsNamespace(Ident),
+}
+
+/// The name of a property, method or enum member.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PropName {
+    Ident(Ident),
+    Str(String, Span),
+    Num(String, Span),
+    /// A computed name such as `[Symbol.iterator]`.
+    Computed(Box<Expr>),
+    Private(Ident),
+}
+
+impl PropName {
+    /// The property name as a JavaScript string, if it is statically known.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            PropName::Ident(i) | PropName::Private(i) => Some(&i.name),
+            PropName::Str(s, _) | PropName::Num(s, _) => Some(s),
+            PropName::Computed(_) => None,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            PropName::Ident(i) | PropName::Private(i) => i.span,
+            PropName::Str(_, span) | PropName::Num(_, span) => *span,
+            PropName::Computed(e) => e.span,
+        }
+    }
+}
+
+/// A member of an interface or object literal type.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Member {
+    pub kind: MemberKind,
+    pub doc: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MemberKind {
+    Property(PropertySig),
+    Method(MethodSig),
+    /// A call signature, `(x: number): string`.
+    Call(Signature),
+    /// A construct signature, `new (x: number): Foo`.
+    Construct(Signature),
+    Index(IndexSig),
+    Getter(GetterSig),
+    Setter(SetterSig),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PropertySig {
+    pub name: PropName,
+    pub optional: bool,
+    pub readonly: bool,
+    pub ty: Option<Type>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MethodSig {
+    pub name: PropName,
+    pub optional: bool,
+    pub sig: Signature,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndexSig {
+    pub readonly: bool,
+    pub params: Vec<Param>,
+    pub ty: Option<Type>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GetterSig {
+    pub name: PropName,
+    pub ty: Option<Type>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SetterSig {
+    pub name: PropName,
+    pub param: Param,
+}
+
+/// Type parameters, parameters and return type of a function-like declaration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Signature {
+    pub type_params: Vec<TypeParam>,
+    pub params: Vec<Param>,
+    pub ret: Option<Type>,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Param {
+    pub name: ParamName,
+    pub optional: bool,
+    pub rest: bool,
+    pub ty: Option<Type>,
+    /// Set for constructor parameter properties such as `constructor(public x: number)`.
+    pub accessibility: Option<Accessibility>,
+    pub readonly: bool,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParamName {
+    Ident(Ident),
+    /// A destructuring pattern. Only its source text is kept since it carries no type
+    /// information of its own.
+    Pattern(String, Span),
+}
+
+impl ParamName {
+    /// A usable name for the parameter. Patterns are named `arg`.
+    pub fn name(&self) -> &str {
+        match self {
+            ParamName::Ident(i) => &i.name,
+            ParamName::Pattern(..) => "arg",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Accessibility {
+    Public,
+    Protected,
+    Private,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClassMember {
+    pub kind: ClassMemberKind,
+    pub is_static: bool,
+    pub is_abstract: bool,
+    pub readonly: bool,
+    pub accessibility: Option<Accessibility>,
+    pub doc: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ClassMemberKind {
+    Constructor(Signature),
+    Property(PropertySig),
+    Method(MethodSig),
+    Index(IndexSig),
+    Getter(GetterSig),
+    Setter(SetterSig),
+    /// A `static { }` block. Only legal in implementation files, but harmless to accept.
+    StaticBlock,
+}
+
+/// A reference to a named type, e.g. `Array<string>` or `Foo.Bar`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeRef {
+    pub name: EntityName,
+    pub type_args: Vec<Type>,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Type {
+    pub kind: TypeKind,
+    pub span: Span,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum KeywordType {
+    Any,
+    Unknown,
+    Never,
+    Void,
+    Undefined,
+    Null,
+    String,
+    Number,
+    Boolean,
+    BigInt,
+    Symbol,
+    Object,
+    This,
+}
+
+impl KeywordType {
+    pub fn from_name(name: &str) -> Option<KeywordType> {
+        Some(match name {
+            "any" => KeywordType::Any,
+            "unknown" => KeywordType::Unknown,
+            "never" => KeywordType::Never,
+            "void" => KeywordType::Void,
+            "undefined" => KeywordType::Undefined,
+            "null" => KeywordType::Null,
+            "string" => KeywordType::String,
+            "number" => KeywordType::Number,
+            "boolean" => KeywordType::Boolean,
+            "bigint" => KeywordType::BigInt,
+            "symbol" => KeywordType::Symbol,
+            "object" => KeywordType::Object,
+            "this" => KeywordType::This,
+            _ => return None,
+        })
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            KeywordType::Any => "any",
+            KeywordType::Unknown => "unknown",
+            KeywordType::Never => "never",
+            KeywordType::Void => "void",
+            KeywordType::Undefined => "undefined",
+            KeywordType::Null => "null",
+            KeywordType::String => "string",
+            KeywordType::Number => "number",
+            KeywordType::Boolean => "boolean",
+            KeywordType::BigInt => "bigint",
+            KeywordType::Symbol => "symbol",
+            KeywordType::Object => "object",
+            KeywordType::This => "this",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LiteralType {
+    Str(String),
+    /// A number literal type; negative literals keep their `-` sign in the text.
+    Num(String),
+    BigInt(String),
+    Bool(bool),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TypeOperator {
+    Keyof,
+    Unique,
+    Readonly,
+}
+
+/// A `+` or `-` modifier on `readonly` or `?` in a mapped type.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MappedModifier {
+    Add,
+    Remove,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TypeKind {
+    Keyword(KeywordType),
+    Literal(LiteralType),
+    Reference(TypeRef),
+    /// `T[]`
+    Array(Box<Type>),
+    Tuple(Vec<TupleElement>),
+    Union(Vec<Type>),
+    Intersection(Vec<Type>),
+    Function(Box<FunctionType>),
+    Constructor(Box<FunctionType>),
+    /// An object literal type, `{ a: string; b(): void }`.
+    Object(Vec<Member>),
+    /// `typeof x.y`
+    Query(EntityName),
+    Operator(TypeOperator, Box<Type>),
+    /// `T[K]`
+    Indexed {
+        object: Box<Type>,
+        index: Box<Type>,
+    },
+    Mapped(Box<MappedType>),
+    Conditional(Box<ConditionalType>),
+    /// `infer U` or `infer U extends C`
+    Infer {
+        name: Ident,
+        constraint: Option<Box<Type>>,
+    },
+    /// A template literal type; `quasis` has one more element than `types`.
+    Template {
+        quasis: Vec<String>,
+        types: Vec<Type>,
+    },
+    /// A type predicate return type: `x is T`, `asserts x is T` or `asserts x`.
+    Predicate {
+        asserts: bool,
+        param: Ident,
+        ty: Option<Box<Type>>,
+    },
+    /// `import("module").Name<Args>`
+    Import {
+        module: String,
+        qualifier: Option<EntityName>,
+        type_args: Vec<Type>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TupleElement {
+    pub name: Option<Ident>,
+    pub ty: Type,
+    pub optional: bool,
+    pub rest: bool,
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FunctionType {
+    pub is_abstract: bool,
+    pub sig: Signature,
+}
+
+/// `{ readonly [K in C as N]?: T }`
+#[derive(Clone, Debug, PartialEq)]
+pub struct MappedType {
+    pub readonly: Option<MappedModifier>,
+    pub param: Ident,
+    pub constraint: Type,
+    pub name_type: Option<Type>,
+    pub optional: Option<MappedModifier>,
+    pub ty: Option<Type>,
+}
+
+/// `check extends extends_ty ? true_ty : false_ty`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConditionalType {
+    pub check: Type,
+    pub extends: Type,
+    pub true_ty: Type,
+    pub false_ty: Type,
+}
+
+/// The small expression language that can appear in declaration files: enum member
+/// initializers, `const` initializers and computed property names.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum UnaryOp {
+    Neg,
+    Plus,
+    BitNot,
+    Not,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Exp,
+    Shl,
+    Shr,
+    UShr,
+    BitAnd,
+    BitOr,
+    BitXor,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExprKind {
+    Num(String),
+    BigInt(String),
+    Str(String),
+    Bool(bool),
+    Null,
+    /// A template literal without substitutions.
+    Template(String),
+    Ident(Ident),
+    Member(Box<Expr>, Ident),
+    /// `a["b"]`
+    Index(Box<Expr>, Box<Expr>),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+}
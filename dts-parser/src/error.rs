@@ -0,0 +1,38 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::span::{line_col, Span};
+
+/// A lexing or parsing error, located by its byte span.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub span: Span,
+}
+
+impl ParseError {
+    pub fn new(message: impl Into<String>, span: Span) -> ParseError {
+        ParseError {
+            message: message.into(),
+            span,
+        }
+    }
+
+    /// Formats the error as `file:line:col: message`.
+    pub fn display_with_source(&self, file_name: &str, src: &str) -> String {
+        format!(
+            "{}:{}: {}",
+            file_name,
+            line_col(src, self.span.start),
+            self.message
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at {:?}", self.message, self.span)
+    }
+}
+
+impl Error for ParseError {}
@@ -0,0 +1,401 @@
+//! Tokenizer for TypeScript declaration files.
+//!
+//! Keywords are not distinguished from identifiers here: almost every TypeScript keyword is
+//! contextual in declaration files (`type`, `declare`, `namespace`, ...), so the parser decides
+//! by looking at the identifier text.
+
+use crate::error::ParseError;
+use crate::span::Span;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenKind {
+    /// An identifier or keyword.
+    Ident(String),
+    /// A `#name` private class member name.
+    PrivateName(String),
+    /// A string literal, with escapes already decoded.
+    Str(String),
+    /// A numeric literal, as written in the source.
+    Num(String),
+    /// A bigint literal such as `10n`, without the `n` suffix.
+    BigInt(String),
+    /// A template literal. `quasis` has one more element than `substitutions`.
+    Template {
+        quasis: Vec<String>,
+        substitutions: Vec<Span>,
+    },
+    /// Punctuation. Operators that could be ambiguous inside types (`>>`, `>=`, `<<`, `**`)
+    /// are always lexed as single characters; see [`Token::joined_to_next`].
+    Punct(&'static str),
+    Eof,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+    /// Whether a line break separates this token from the previous one. Needed for automatic
+    /// semicolon insertion.
+    pub newline_before: bool,
+    /// The text of a `/** ... */` comment directly preceding this token, if any.
+    pub doc: Option<String>,
+}
+
+impl Token {
+    pub fn is_ident(&self, name: &str) -> bool {
+        matches!(&self.kind, TokenKind::Ident(n) if n == name)
+    }
+
+    /// Whether the token is an identifier or a string, which can both name an export.
+    pub fn is_ident_like(&self) -> bool {
+        matches!(self.kind, TokenKind::Ident(_) | TokenKind::Str(_))
+    }
+
+    pub fn is_punct(&self, p: &str) -> bool {
+        matches!(&self.kind, TokenKind::Punct(q) if *q == p)
+    }
+
+    /// Whether `next` starts exactly where this token ends, e.g. the two halves of `>>`.
+    pub fn joined_to_next(&self, next: &Token) -> bool {
+        self.span.end == next.span.start
+    }
+}
+
+const PUNCTS: &[&str] = &[
+    "...", "=>", "{", "}", "(", ")", "[", "]", ";", ",", "<", ">", ".", ":", "?", "=", "|", "&",
+    "+", "-", "*", "/", "%", "^", "~", "!", "@",
+];
+
+pub struct Lexer<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(src: &'a str) -> Lexer<'a> {
+        Lexer { src, pos: 0 }
+    }
+
+    /// Lexes a sub-range of the source, e.g. a template literal substitution. Spans stay
+    /// relative to the full source.
+    pub fn with_range(src: &'a str, span: Span) -> Lexer<'a> {
+        Lexer {
+            src: &src[..span.end],
+            pos: span.start,
+        }
+    }
+
+    /// Tokenizes the whole input. The returned vector always ends with an `Eof` token.
+    pub fn tokenize(mut self) -> Result<Vec<Token>, ParseError> {
+        let mut tokens = Vec::new();
+        loop {
+            let tok = self.next_token()?;
+            let eof = tok.kind == TokenKind::Eof;
+            tokens.push(tok);
+            if eof {
+                return Ok(tokens);
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn peek_at(&self, n: usize) -> Option<char> {
+        self.src[self.pos..].chars().nth(n)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn error(&self, start: usize, message: impl Into<String>) -> ParseError {
+        ParseError::new(
+            message,
+            Span::new(start, self.pos.max(start + 1).min(self.src.len())),
+        )
+    }
+
+    /// Skips whitespace and comments, returning whether a newline was seen and the last doc
+    /// comment encountered.
+    fn skip_trivia(&mut self) -> Result<(bool, Option<String>), ParseError> {
+        let mut newline = false;
+        let mut doc = None;
+        loop {
+            match self.peek() {
+                Some('\n') | Some('\u{2028}') | Some('\u{2029}') => {
+                    newline = true;
+                    self.bump();
+                }
+                Some(c) if c.is_whitespace() || c == '\u{feff}' => {
+                    self.bump();
+                }
+                Some('/') if self.peek_at(1) == Some('/') => {
+                    while let Some(c) = self.peek() {
+                        if c == '\n' {
+                            break;
+                        }
+                        self.bump();
+                    }
+                }
+                Some('/') if self.peek_at(1) == Some('*') => {
+                    let start = self.pos;
+                    self.pos += 2;
+                    let end = match self.src[self.pos..].find("*/") {
+                        Some(i) => self.pos + i,
+                        None => {
+                            self.pos = self.src.len();
+                            return Err(self.error(start, "unterminated block comment"));
+                        }
+                    };
+                    let body = &self.src[self.pos..end];
+                    if body.contains('\n') {
+                        newline = true;
+                    }
+                    if body.starts_with('*') && !body.starts_with("**") {
+                        doc = Some(clean_doc_comment(&body[1..]));
+                    } else {
+                        doc = None;
+                    }
+                    self.pos = end + 2;
+                }
+                _ => return Ok((newline, doc)),
+            }
+        }
+    }
+
+    pub fn next_token(&mut self) -> Result<Token, ParseError> {
+        let (newline_before, doc) = self.skip_trivia()?;
+        let start = self.pos;
+        let kind = match self.peek() {
+            None => TokenKind::Eof,
+            Some(c) if is_ident_start(c) => TokenKind::Ident(self.ident()),
+            Some('#') if self.peek_at(1).is_some_and(is_ident_start) => {
+                self.bump();
+                TokenKind::PrivateName(self.ident())
+            }
+            Some(c) if c.is_ascii_digit() => self.number(start)?,
+            Some('.') if self.peek_at(1).is_some_and(|c| c.is_ascii_digit()) => {
+                self.number(start)?
+            }
+            Some(q @ ('"' | '\'')) => {
+                self.bump();
+                TokenKind::Str(self.string_body(start, q)?)
+            }
+            Some('`') => self.template(start)?,
+            Some(_) => {
+                let rest = &self.src[self.pos..];
+                match PUNCTS.iter().find(|p| rest.starts_with(**p)) {
+                    Some(p) => {
+                        self.pos += p.len();
+                        TokenKind::Punct(p)
+                    }
+                    None => {
+                        self.bump();
+                        return Err(self.error(start, "unexpected character"));
+                    }
+                }
+            }
+        };
+        Ok(Token {
+            kind,
+            span: Span::new(start, self.pos),
+            newline_before,
+            doc,
+        })
+    }
+
+    fn ident(&mut self) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(is_ident_continue) {
+            self.bump();
+        }
+        self.src[start..self.pos].to_string()
+    }
+
+    fn number(&mut self, start: usize) -> Result<TokenKind, ParseError> {
+        let radix_prefix = self.peek() == Some('0')
+            && matches!(self.peek_at(1), Some('x' | 'X' | 'o' | 'O' | 'b' | 'B'));
+        if radix_prefix {
+            self.pos += 2;
+            while self
+                .peek()
+                .is_some_and(|c| c.is_ascii_hexdigit() || c == '_')
+            {
+                self.bump();
+            }
+        } else {
+            while self.peek().is_some_and(|c| c.is_ascii_digit() || c == '_') {
+                self.bump();
+            }
+            if self.peek() == Some('.') && self.peek_at(1) != Some('.') {
+                self.bump();
+                while self.peek().is_some_and(|c| c.is_ascii_digit() || c == '_') {
+                    self.bump();
+                }
+            }
+            if matches!(self.peek(), Some('e' | 'E')) {
+                self.bump();
+                if matches!(self.peek(), Some('+' | '-')) {
+                    self.bump();
+                }
+                while self.peek().is_some_and(|c| c.is_ascii_digit() || c == '_') {
+                    self.bump();
+                }
+            }
+        }
+        let text = self.src[start..self.pos].to_string();
+        if self.peek() == Some('n') {
+            self.bump();
+            return Ok(TokenKind::BigInt(text));
+        }
+        if self.peek().is_some_and(is_ident_start) {
+            self.bump();
+            return Err(self.error(start, "identifier directly after number"));
+        }
+        Ok(TokenKind::Num(text))
+    }
+
+    fn escape(&mut self, start: usize, out: &mut String) -> Result<(), ParseError> {
+        let c = match self.bump() {
+            Some(c) => c,
+            None => return Err(self.error(start, "unterminated string literal")),
+        };
+        match c {
+            'n' => out.push('\n'),
+            't' => out.push('\t'),
+            'r' => out.push('\r'),
+            'b' => out.push('\u{8}'),
+            'f' => out.push('\u{c}'),
+            'v' => out.push('\u{b}'),
+            '0' if !self.peek().is_some_and(|c| c.is_ascii_digit()) => out.push('\0'),
+            '\r' => {
+                if self.peek() == Some('\n') {
+                    self.bump();
+                }
+            }
+            '\n' | '\u{2028}' | '\u{2029}' => {}
+            'x' => {
+                let code = self.hex_digits(start, 2)?;
+                out.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+            }
+            'u' => {
+                let code = if self.peek() == Some('{') {
+                    self.bump();
+                    let digits_start = self.pos;
+                    while self.peek().is_some_and(|c| c.is_ascii_hexdigit()) {
+                        self.bump();
+                    }
+                    let code = u32::from_str_radix(&self.src[digits_start..self.pos], 16)
+                        .map_err(|_| self.error(start, "invalid unicode escape"))?;
+                    if self.bump() != Some('}') {
+                        return Err(self.error(start, "invalid unicode escape"));
+                    }
+                    code
+                } else {
+                    self.hex_digits(start, 4)?
+                };
+                out.push(char::from_u32(code).unwrap_or('\u{fffd}'));
+            }
+            c => out.push(c),
+        }
+        Ok(())
+    }
+
+    fn hex_digits(&mut self, start: usize, n: usize) -> Result<u32, ParseError> {
+        let digits_start = self.pos;
+        for _ in 0..n {
+            if !self.peek().is_some_and(|c| c.is_ascii_hexdigit()) {
+                return Err(self.error(start, "invalid escape sequence"));
+            }
+            self.bump();
+        }
+        Ok(u32::from_str_radix(&self.src[digits_start..self.pos], 16).unwrap_or(0xfffd))
+    }
+
+    fn string_body(&mut self, start: usize, quote: char) -> Result<String, ParseError> {
+        let mut out = String::new();
+        loop {
+            match self.bump() {
+                None | Some('\n') => return Err(self.error(start, "unterminated string literal")),
+                Some('\\') => self.escape(start, &mut out)?,
+                Some(c) if c == quote => return Ok(out),
+                Some(c) => out.push(c),
+            }
+        }
+    }
+
+    fn template(&mut self, start: usize) -> Result<TokenKind, ParseError> {
+        self.bump();
+        let mut quasis = Vec::new();
+        let mut substitutions = Vec::new();
+        let mut current = String::new();
+        loop {
+            match self.bump() {
+                None => return Err(self.error(start, "unterminated template literal")),
+                Some('`') => {
+                    quasis.push(current);
+                    return Ok(TokenKind::Template {
+                        quasis,
+                        substitutions,
+                    });
+                }
+                Some('\\') => self.escape(start, &mut current)?,
+                Some('$') if self.peek() == Some('{') => {
+                    self.bump();
+                    quasis.push(std::mem::take(&mut current));
+                    let sub_start = self.pos;
+                    let mut depth = 0usize;
+                    loop {
+                        let tok = self.next_token()?;
+                        match tok.kind {
+                            TokenKind::Eof => {
+                                return Err(self.error(start, "unterminated template literal"))
+                            }
+                            TokenKind::Punct("{") => depth += 1,
+                            TokenKind::Punct("}") if depth == 0 => {
+                                substitutions.push(Span::new(sub_start, tok.span.start));
+                                break;
+                            }
+                            TokenKind::Punct("}") => depth -= 1,
+                            _ => {}
+                        }
+                    }
+                }
+                Some(c) => current.push(c),
+            }
+        }
+    }
+}
+
+/// Strips the leading `*` decoration from each line of a `/** ... */` comment body.
+fn clean_doc_comment(body: &str) -> String {
+    let lines: Vec<&str> = body
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            let line = line.strip_prefix('*').unwrap_or(line);
+            line.strip_prefix(' ').unwrap_or(line).trim_end()
+        })
+        .collect();
+    let first = lines
+        .iter()
+        .position(|l| !l.is_empty())
+        .unwrap_or(lines.len());
+    let last = lines
+        .iter()
+        .rposition(|l| !l.is_empty())
+        .map_or(first, |i| i + 1);
+    lines[first..last].join("\n")
+}
+
+pub fn is_ident_start(c: char) -> bool {
+    c == '$' || c == '_' || c.is_alphabetic()
+}
+
+pub fn is_ident_continue(c: char) -> bool {
+    c == '$' || c == '_' || c == '\u{200c}' || c == '\u{200d}' || c.is_alphanumeric()
+}
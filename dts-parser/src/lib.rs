@@ -0,0 +1,20 @@
+//! A lexer and parser for TypeScript declaration (`.d.ts`) files.
+//!
+//! ```
+//! let module = dts_parser::parse("declare function foo(a: number): string;").unwrap();
+//! assert_eq!(module.items.len(), 1);
+//! ```
+
+pub mod ast;
+mod error;
+pub mod lexer;
+pub mod parser;
+mod span;
+
+pub use crate::error::ParseError;
+pub use crate::span::{line_col, LineCol, Span};
+
+/// Parses the source text of a declaration file.
+pub fn parse(src: &str) -> Result<ast::Module, ParseError> {
+    parser::Parser::new(src)?.parse_module()
+}
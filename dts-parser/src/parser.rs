@@ -0,0 +1,2224 @@
+//! Recursive-descent parser for declaration files.
+//!
+//! The parser works on a fully tokenized input, so speculative parsing (needed to tell a
+//! parenthesized type from a function type, for instance) is just a matter of saving and
+//! restoring the token position.
+
+use crate::ast::*;
+use crate::error::ParseError;
+use crate::lexer::{Lexer, Token, TokenKind};
+use crate::span::Span;
+
+type PResult<T> = Result<T, ParseError>;
+
+pub struct Parser<'a> {
+    src: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(src: &'a str) -> PResult<Parser<'a>> {
+        Ok(Parser {
+            src,
+            tokens: Lexer::new(src).tokenize()?,
+            pos: 0,
+        })
+    }
+
+    /// A parser over a sub-range of the source, such as a template literal substitution.
+    fn for_range(src: &'a str, span: Span) -> PResult<Parser<'a>> {
+        Ok(Parser {
+            src,
+            tokens: Lexer::with_range(src, span).tokenize()?,
+            pos: 0,
+        })
+    }
+
+    // ----- token helpers -----
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    fn peek_nth(&self, n: usize) -> &Token {
+        let idx = (self.pos + n).min(self.tokens.len() - 1);
+        &self.tokens[idx]
+    }
+
+    fn bump(&mut self) -> Token {
+        let tok = self.tokens[self.pos].clone();
+        if tok.kind != TokenKind::Eof {
+            self.pos += 1;
+        }
+        tok
+    }
+
+    fn at_eof(&self) -> bool {
+        self.peek().kind == TokenKind::Eof
+    }
+
+    fn at_punct(&self, p: &str) -> bool {
+        self.peek().is_punct(p)
+    }
+
+    fn at_ident(&self, name: &str) -> bool {
+        self.peek().is_ident(name)
+    }
+
+    fn eat_punct(&mut self, p: &str) -> bool {
+        if self.at_punct(p) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_ident(&mut self, name: &str) -> bool {
+        if self.at_ident(name) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_punct(&mut self, p: &str) -> PResult<Span> {
+        if self.at_punct(p) {
+            Ok(self.bump().span)
+        } else {
+            Err(self.unexpected(&format!("`{}`", p)))
+        }
+    }
+
+    fn expect_ident_kw(&mut self, name: &str) -> PResult<Span> {
+        if self.at_ident(name) {
+            Ok(self.bump().span)
+        } else {
+            Err(self.unexpected(&format!("`{}`", name)))
+        }
+    }
+
+    /// The end of the most recently consumed token.
+    fn prev_end(&self) -> usize {
+        if self.pos == 0 {
+            0
+        } else {
+            self.tokens[self.pos - 1].span.end
+        }
+    }
+
+    fn span_from(&self, start: usize) -> Span {
+        Span::new(start, self.prev_end().max(start))
+    }
+
+    fn unexpected(&self, expected: &str) -> ParseError {
+        let tok = self.peek();
+        let found = match &tok.kind {
+            TokenKind::Eof => "end of file".to_string(),
+            _ => format!("`{}`", tok.span.text(self.src)),
+        };
+        ParseError::new(format!("expected {}, found {}", expected, found), tok.span)
+    }
+
+    fn ident(&mut self) -> PResult<Ident> {
+        match &self.peek().kind {
+            TokenKind::Ident(name) => {
+                let ident = Ident {
+                    name: name.clone(),
+                    span: self.peek().span,
+                };
+                self.bump();
+                Ok(ident)
+            }
+            _ => Err(self.unexpected("identifier")),
+        }
+    }
+
+    fn at_any_ident(&self) -> bool {
+        matches!(self.peek().kind, TokenKind::Ident(_))
+    }
+
+    /// Consumes a statement terminator, applying automatic semicolon insertion.
+    fn semicolon(&mut self) -> PResult<()> {
+        if self.eat_punct(";") || self.at_punct("}") || self.at_eof() || self.peek().newline_before
+        {
+            Ok(())
+        } else {
+            Err(self.unexpected("`;`"))
+        }
+    }
+
+    // ----- items -----
+
+    pub fn parse_module(&mut self) -> PResult<Module> {
+        let start = self.peek().span.start;
+        let references = parse_references(&self.src[..start]);
+        let mut items = Vec::new();
+        while !self.at_eof() {
+            if let Some(item) = self.parse_item()? {
+                items.push(item);
+            }
+        }
+        Ok(Module {
+            items,
+            references,
+            span: Span::new(start, self.src.len()),
+        })
+    }
+
+    /// Parses `{ items }`, the body of a namespace, ambient module or `declare global`.
+    fn parse_block(&mut self) -> PResult<Module> {
+        let start = self.expect_punct("{")?.start;
+        let mut items = Vec::new();
+        while !self.at_punct("}") {
+            if self.at_eof() {
+                return Err(self.unexpected("`}`"));
+            }
+            if let Some(item) = self.parse_item()? {
+                items.push(item);
+            }
+        }
+        self.bump();
+        Ok(Module {
+            items,
+            references: Vec::new(),
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_item(&mut self) -> PResult<Option<Item>> {
+        if self.eat_punct(";") {
+            return Ok(None);
+        }
+        let start = self.peek().span.start;
+        let doc = self.peek().doc.clone();
+        let mut export = false;
+        let mut default = false;
+        let mut declare = false;
+        if self.at_ident("export") {
+            self.bump();
+            export = true;
+            if let Some(kind) = self.parse_export()? {
+                let default = matches!(kind, ItemKind::Export(ExportDecl::Default(_)));
+                return Ok(Some(Item {
+                    kind,
+                    export,
+                    default,
+                    declare,
+                    doc,
+                    span: self.span_from(start),
+                }));
+            }
+            if self.eat_ident("default") {
+                default = true;
+            }
+        }
+        if self.at_ident("declare") && !self.peek_nth(1).newline_before {
+            self.bump();
+            declare = true;
+        }
+        let kind = self.parse_item_kind(default)?;
+        Ok(Some(Item {
+            kind,
+            export,
+            default,
+            declare,
+            doc,
+            span: self.span_from(start),
+        }))
+    }
+
+    fn parse_item_kind(&mut self, default: bool) -> PResult<ItemKind> {
+        let tok = self.peek().clone();
+        let kw = match &tok.kind {
+            TokenKind::Ident(name) => name.as_str(),
+            _ => return Err(self.unexpected("a declaration")),
+        };
+        match kw {
+            "interface" => {
+                self.bump();
+                Ok(ItemKind::Interface(self.parse_interface()?))
+            }
+            "type" if matches!(self.peek_nth(1).kind, TokenKind::Ident(_)) => {
+                self.bump();
+                Ok(ItemKind::TypeAlias(self.parse_type_alias()?))
+            }
+            "abstract" if self.peek_nth(1).is_ident("class") => {
+                self.bump();
+                self.bump();
+                Ok(ItemKind::Class(self.parse_class(true, default)?))
+            }
+            "class" => {
+                self.bump();
+                Ok(ItemKind::Class(self.parse_class(false, default)?))
+            }
+            "async" if self.peek_nth(1).is_ident("function") => {
+                self.bump();
+                self.bump();
+                Ok(ItemKind::Function(self.parse_function(default)?))
+            }
+            "function" => {
+                self.bump();
+                Ok(ItemKind::Function(self.parse_function(default)?))
+            }
+            "const" if self.peek_nth(1).is_ident("enum") => {
+                self.bump();
+                self.bump();
+                Ok(ItemKind::Enum(self.parse_enum(true)?))
+            }
+            "enum" => {
+                self.bump();
+                Ok(ItemKind::Enum(self.parse_enum(false)?))
+            }
+            "var" | "let" | "const" => {
+                let kind = match kw {
+                    "var" => VariableKind::Var,
+                    "let" => VariableKind::Let,
+                    _ => VariableKind::Const,
+                };
+                self.bump();
+                Ok(ItemKind::Variable(self.parse_variable(kind)?))
+            }
+            "namespace" | "module" => {
+                self.bump();
+                if let TokenKind::Str(name) = &self.peek().kind {
+                    let name = name.clone();
+                    let name_span = self.bump().span;
+                    let body = if self.at_punct("{") {
+                        Some(self.parse_block()?)
+                    } else {
+                        self.semicolon()?;
+                        None
+                    };
+                    Ok(ItemKind::Module(ModuleDecl {
+                        name,
+                        name_span,
+                        body,
+                    }))
+                } else {
+                    let name = self.parse_entity_name()?;
+                    let body = self.parse_block()?;
+                    Ok(ItemKind::Namespace(NamespaceDecl { name, body }))
+                }
+            }
+            "global" if self.peek_nth(1).is_punct("{") => {
+                self.bump();
+                Ok(ItemKind::Global(self.parse_block()?))
+            }
+            "import" => {
+                self.bump();
+                self.parse_import()
+            }
+            _ => Err(self.unexpected("a declaration")),
+        }
+    }
+
+    // ----- imports and exports -----
+
+    /// Parses the rest of an `import` statement, after the keyword.
+    fn parse_import(&mut self) -> PResult<ItemKind> {
+        // `type` is a modifier unless it is the imported name, as in `import type from "m"`.
+        let type_only = self.at_ident("type")
+            && !self.peek_nth(1).is_ident("from")
+            && !self.peek_nth(1).is_punct(",")
+            && !self.peek_nth(1).is_punct("=");
+        if type_only {
+            self.bump();
+        }
+        let mut decl = ImportDecl {
+            type_only,
+            default: None,
+            namespace: None,
+            named: Vec::new(),
+            module: String::new(),
+            module_span: Span::default(),
+        };
+        if self.at_any_ident() && self.peek_nth(1).is_punct("=") {
+            let name = self.ident()?;
+            self.bump();
+            if self.at_ident("require") && self.peek_nth(1).is_punct("(") {
+                self.bump();
+                self.bump();
+                (decl.module, decl.module_span) = self.module_specifier()?;
+                self.expect_punct(")")?;
+                self.semicolon()?;
+                decl.namespace = Some(name);
+                return Ok(ItemKind::Import(decl));
+            }
+            let target = self.parse_entity_name()?;
+            self.semicolon()?;
+            return Ok(ItemKind::ImportAlias(ImportAliasDecl { name, target }));
+        }
+        if !matches!(self.peek().kind, TokenKind::Str(_)) {
+            if self.at_any_ident() {
+                decl.default = Some(self.ident()?);
+                if !self.eat_punct(",") {
+                    self.expect_ident_kw("from")?;
+                }
+            }
+            if self.eat_punct("*") {
+                self.expect_ident_kw("as")?;
+                decl.namespace = Some(self.ident()?);
+            } else if self.at_punct("{") {
+                decl.named = self.parse_specifiers()?;
+            }
+            if decl.namespace.is_some() || !decl.named.is_empty() || self.at_ident("from") {
+                self.expect_ident_kw("from")?;
+            }
+        }
+        (decl.module, decl.module_span) = self.module_specifier()?;
+        self.skip_import_attributes()?;
+        self.semicolon()?;
+        Ok(ItemKind::Import(decl))
+    }
+
+    /// Parses an export declaration after the `export` keyword, or returns `None` if the
+    /// keyword exports an ordinary declaration instead.
+    fn parse_export(&mut self) -> PResult<Option<ItemKind>> {
+        let type_only = self.at_ident("type")
+            && (self.peek_nth(1).is_punct("{") || self.peek_nth(1).is_punct("*"));
+        if type_only {
+            self.bump();
+        }
+        let decl = if self.eat_punct("*") {
+            let alias = if self.eat_ident("as") {
+                Some(self.ident()?)
+            } else {
+                None
+            };
+            self.expect_ident_kw("from")?;
+            let (module, module_span) = self.module_specifier()?;
+            ExportDecl::All {
+                alias,
+                module,
+                module_span,
+            }
+        } else if self.at_punct("{") {
+            let mut specifiers = self.parse_specifiers()?;
+            if type_only {
+                for spec in &mut specifiers {
+                    spec.type_only = true;
+                }
+            }
+            let module = if self.eat_ident("from") {
+                Some(self.module_specifier()?)
+            } else {
+                None
+            };
+            ExportDecl::Named { specifiers, module }
+        } else if self.at_ident("default")
+            && matches!(self.peek_nth(1).kind, TokenKind::Ident(_))
+            && self.at_terminator(2)
+        {
+            self.bump();
+            ExportDecl::Default(self.ident()?)
+        } else if self.eat_punct("=") {
+            ExportDecl::Assign(self.parse_entity_name()?)
+        } else if self.at_ident("as") && self.peek_nth(1).is_ident("namespace") {
+            self.bump();
+            self.bump();
+            ExportDecl::AsNamespace(self.ident()?)
+        } else {
+            return Ok(None);
+        };
+        self.skip_import_attributes()?;
+        self.semicolon()?;
+        Ok(Some(ItemKind::Export(decl)))
+    }
+
+    /// Whether the token `n` ahead ends a statement.
+    fn at_terminator(&self, n: usize) -> bool {
+        let tok = self.peek_nth(n);
+        tok.kind == TokenKind::Eof || tok.newline_before || tok.is_punct(";") || tok.is_punct("}")
+    }
+
+    /// Parses `{ a, type b, c as d }`.
+    fn parse_specifiers(&mut self) -> PResult<Vec<Specifier>> {
+        self.expect_punct("{")?;
+        let mut specifiers = Vec::new();
+        while !self.at_punct("}") {
+            let type_only = self.at_ident("type")
+                && self.peek_nth(1).is_ident_like()
+                && !self.peek_nth(1).is_ident("as");
+            if type_only {
+                self.bump();
+            }
+            let name = self.specifier_name()?;
+            let alias = if self.eat_ident("as") {
+                Some(self.specifier_name()?)
+            } else {
+                None
+            };
+            specifiers.push(Specifier {
+                name,
+                alias,
+                type_only,
+            });
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+        self.expect_punct("}")?;
+        Ok(specifiers)
+    }
+
+    /// An identifier, or a string naming an export that isn't one.
+    fn specifier_name(&mut self) -> PResult<Ident> {
+        if let TokenKind::Str(name) = &self.peek().kind {
+            let name = name.clone();
+            let span = self.bump().span;
+            return Ok(Ident { name, span });
+        }
+        self.ident()
+    }
+
+    fn module_specifier(&mut self) -> PResult<(String, Span)> {
+        match &self.peek().kind {
+            TokenKind::Str(module) => {
+                let module = module.clone();
+                Ok((module, self.bump().span))
+            }
+            _ => Err(self.unexpected("module specifier")),
+        }
+    }
+
+    /// Skips `with { type: "json" }` (or the older `assert { ... }`) after a module specifier.
+    fn skip_import_attributes(&mut self) -> PResult<()> {
+        if (self.at_ident("with") || self.at_ident("assert"))
+            && !self.peek().newline_before
+            && self.peek_nth(1).is_punct("{")
+        {
+            self.bump();
+            self.bump();
+            while !self.eat_punct("}") {
+                if self.at_eof() {
+                    return Err(self.unexpected("`}`"));
+                }
+                self.bump();
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_interface(&mut self) -> PResult<InterfaceDecl> {
+        let name = self.ident()?;
+        let type_params = self.parse_opt_type_params()?;
+        let mut extends = Vec::new();
+        if self.eat_ident("extends") {
+            loop {
+                extends.push(self.parse_type_ref()?);
+                if !self.eat_punct(",") {
+                    break;
+                }
+            }
+        }
+        let members = self.parse_type_members()?;
+        Ok(InterfaceDecl {
+            name,
+            type_params,
+            extends,
+            members,
+        })
+    }
+
+    fn parse_type_alias(&mut self) -> PResult<TypeAliasDecl> {
+        let name = self.ident()?;
+        let type_params = self.parse_opt_type_params()?;
+        self.expect_punct("=")?;
+        let ty = self.parse_type()?;
+        self.semicolon()?;
+        Ok(TypeAliasDecl {
+            name,
+            type_params,
+            ty,
+        })
+    }
+
+    fn parse_function(&mut self, default: bool) -> PResult<FunctionDecl> {
+        self.eat_punct("*");
+        let name = if default && (self.at_punct("(") || self.at_punct("<")) {
+            None
+        } else {
+            Some(self.ident()?)
+        };
+        let sig = self.parse_signature(":")?;
+        if self.at_punct("{") {
+            self.skip_balanced()?;
+        } else {
+            self.semicolon()?;
+        }
+        Ok(FunctionDecl { name, sig })
+    }
+
+    fn parse_enum(&mut self, is_const: bool) -> PResult<EnumDecl> {
+        let name = self.ident()?;
+        self.expect_punct("{")?;
+        let mut members = Vec::new();
+        while !self.at_punct("}") {
+            let start = self.peek().span.start;
+            let doc = self.peek().doc.clone();
+            let name = self.parse_prop_name()?;
+            let init = if self.eat_punct("=") {
+                Some(self.parse_expr()?)
+            } else {
+                None
+            };
+            members.push(EnumMember {
+                name,
+                init,
+                doc,
+                span: self.span_from(start),
+            });
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+        self.expect_punct("}")?;
+        Ok(EnumDecl {
+            name,
+            is_const,
+            members,
+        })
+    }
+
+    fn parse_variable(&mut self, kind: VariableKind) -> PResult<VariableDecl> {
+        let mut declarators = Vec::new();
+        loop {
+            let start = self.peek().span.start;
+            let name = self.ident()?;
+            self.eat_punct("!");
+            let ty = if self.eat_punct(":") {
+                Some(self.parse_type()?)
+            } else {
+                None
+            };
+            let init = if self.eat_punct("=") {
+                Some(self.parse_expr()?)
+            } else {
+                None
+            };
+            declarators.push(VariableDeclarator {
+                name,
+                ty,
+                init,
+                span: self.span_from(start),
+            });
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+        self.semicolon()?;
+        Ok(VariableDecl { kind, declarators })
+    }
+
+    fn parse_class(&mut self, is_abstract: bool, default: bool) -> PResult<ClassDecl> {
+        let name = if default && !self.at_any_ident()
+            || self.at_ident("extends")
+            || self.at_ident("implements")
+        {
+            None
+        } else {
+            Some(self.ident()?)
+        };
+        let type_params = self.parse_opt_type_params()?;
+        let extends = if self.eat_ident("extends") {
+            Some(self.parse_type_ref()?)
+        } else {
+            None
+        };
+        let mut implements = Vec::new();
+        if self.eat_ident("implements") {
+            loop {
+                implements.push(self.parse_type_ref()?);
+                if !self.eat_punct(",") {
+                    break;
+                }
+            }
+        }
+        self.expect_punct("{")?;
+        let mut members = Vec::new();
+        while !self.at_punct("}") {
+            if self.at_eof() {
+                return Err(self.unexpected("`}`"));
+            }
+            if self.eat_punct(";") {
+                continue;
+            }
+            members.push(self.parse_class_member()?);
+        }
+        self.bump();
+        Ok(ClassDecl {
+            name,
+            is_abstract,
+            type_params,
+            extends,
+            implements,
+            members,
+        })
+    }
+
+    /// Whether the current identifier is used as a modifier, i.e. is followed on the same
+    /// line by something that can start a member name.
+    fn at_modifier(&self) -> bool {
+        let next = self.peek_nth(1);
+        if next.newline_before {
+            return false;
+        }
+        matches!(
+            next.kind,
+            TokenKind::Ident(_) | TokenKind::Str(_) | TokenKind::Num(_) | TokenKind::PrivateName(_)
+        ) || next.is_punct("[")
+            || next.is_punct("{")
+            || next.is_punct("*")
+            || next.is_punct("...")
+    }
+
+    fn parse_class_member(&mut self) -> PResult<ClassMember> {
+        let start = self.peek().span.start;
+        let doc = self.peek().doc.clone();
+        let mut member = ClassMember {
+            kind: ClassMemberKind::StaticBlock,
+            is_static: false,
+            is_abstract: false,
+            readonly: false,
+            accessibility: None,
+            doc,
+            span: Span::default(),
+        };
+        loop {
+            let modifier = match &self.peek().kind {
+                TokenKind::Ident(name) if self.at_modifier() => name.clone(),
+                _ => break,
+            };
+            match modifier.as_str() {
+                "static" => member.is_static = true,
+                "abstract" => member.is_abstract = true,
+                "readonly" => member.readonly = true,
+                "public" => member.accessibility = Some(Accessibility::Public),
+                "protected" => member.accessibility = Some(Accessibility::Protected),
+                "private" => member.accessibility = Some(Accessibility::Private),
+                "declare" | "override" | "accessor" | "async" => {}
+                _ => break,
+            }
+            self.bump();
+        }
+        if member.is_static && self.at_punct("{") {
+            self.skip_balanced()?;
+            member.span = self.span_from(start);
+            return Ok(member);
+        }
+        member.kind = if self.at_ident("constructor")
+            && (self.peek_nth(1).is_punct("(") || self.peek_nth(1).is_punct("<"))
+        {
+            self.bump();
+            let sig = self.parse_signature(":")?;
+            self.skip_body_or_semicolon()?;
+            ClassMemberKind::Constructor(sig)
+        } else if self.at_index_signature() {
+            let sig = self.parse_index_sig(member.readonly)?;
+            self.semicolon()?;
+            ClassMemberKind::Index(sig)
+        } else if let Some(getter) = self.at_accessor() {
+            self.bump();
+            let name = self.parse_prop_name()?;
+            let sig = self.parse_signature(":")?;
+            self.skip_body_or_semicolon()?;
+            accessor_kind(getter, name, sig).into_class_member()
+        } else {
+            self.eat_punct("*");
+            let name = self.parse_prop_name()?;
+            let optional = self.eat_punct("?");
+            self.eat_punct("!");
+            if self.at_punct("(") || self.at_punct("<") {
+                let sig = self.parse_signature(":")?;
+                self.skip_body_or_semicolon()?;
+                ClassMemberKind::Method(MethodSig {
+                    name,
+                    optional,
+                    sig,
+                })
+            } else {
+                let ty = if self.eat_punct(":") {
+                    Some(self.parse_type()?)
+                } else {
+                    None
+                };
+                if self.eat_punct("=") {
+                    self.parse_expr()?;
+                }
+                self.semicolon()?;
+                ClassMemberKind::Property(PropertySig {
+                    name,
+                    optional,
+                    readonly: member.readonly,
+                    ty,
+                })
+            }
+        };
+        member.span = self.span_from(start);
+        Ok(member)
+    }
+
+    fn skip_body_or_semicolon(&mut self) -> PResult<()> {
+        if self.at_punct("{") {
+            self.skip_balanced()
+        } else {
+            self.semicolon()
+        }
+    }
+
+    /// Skips a bracketed token group, e.g. a function body or a destructuring pattern.
+    fn skip_balanced(&mut self) -> PResult<()> {
+        let mut depth = 0usize;
+        loop {
+            let tok = self.bump();
+            match tok.kind {
+                TokenKind::Punct("{") | TokenKind::Punct("[") | TokenKind::Punct("(") => depth += 1,
+                TokenKind::Punct("}") | TokenKind::Punct("]") | TokenKind::Punct(")") => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                TokenKind::Eof => {
+                    return Err(ParseError::new("unbalanced brackets", tok.span));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// If the current token is a `get`/`set` accessor keyword, returns whether it is `get`.
+    fn at_accessor(&self) -> Option<bool> {
+        let getter = if self.at_ident("get") {
+            true
+        } else if self.at_ident("set") {
+            false
+        } else {
+            return None;
+        };
+        let next = self.peek_nth(1);
+        let starts_name = matches!(
+            next.kind,
+            TokenKind::Ident(_) | TokenKind::Str(_) | TokenKind::Num(_) | TokenKind::PrivateName(_)
+        ) || next.is_punct("[");
+        if starts_name && !next.newline_before {
+            Some(getter)
+        } else {
+            None
+        }
+    }
+
+    // ----- members -----
+
+    /// Parses `{ members }` of an interface or object literal type.
+    fn parse_type_members(&mut self) -> PResult<Vec<Member>> {
+        self.expect_punct("{")?;
+        let mut members = Vec::new();
+        while !self.at_punct("}") {
+            if self.at_eof() {
+                return Err(self.unexpected("`}`"));
+            }
+            members.push(self.parse_member()?);
+            if !self.eat_punct(";")
+                && !self.eat_punct(",")
+                && !self.at_punct("}")
+                && !self.peek().newline_before
+            {
+                return Err(self.unexpected("`;`"));
+            }
+        }
+        self.bump();
+        Ok(members)
+    }
+
+    fn at_index_signature(&self) -> bool {
+        self.at_punct("[")
+            && matches!(self.peek_nth(1).kind, TokenKind::Ident(_))
+            && (self.peek_nth(2).is_punct(":") || self.peek_nth(2).is_punct(","))
+    }
+
+    fn parse_index_sig(&mut self, readonly: bool) -> PResult<IndexSig> {
+        self.expect_punct("[")?;
+        let params = self.parse_param_list("]")?;
+        let ty = if self.eat_punct(":") {
+            Some(self.parse_type()?)
+        } else {
+            None
+        };
+        Ok(IndexSig {
+            readonly,
+            params,
+            ty,
+        })
+    }
+
+    fn parse_member(&mut self) -> PResult<Member> {
+        let start = self.peek().span.start;
+        let doc = self.peek().doc.clone();
+        let kind = if self.at_punct("(") || self.at_punct("<") {
+            MemberKind::Call(self.parse_signature(":")?)
+        } else if self.at_ident("new")
+            && (self.peek_nth(1).is_punct("(") || self.peek_nth(1).is_punct("<"))
+        {
+            self.bump();
+            MemberKind::Construct(self.parse_signature(":")?)
+        } else {
+            let readonly = self.at_ident("readonly") && self.at_modifier();
+            if readonly {
+                self.bump();
+            }
+            if self.at_index_signature() {
+                MemberKind::Index(self.parse_index_sig(readonly)?)
+            } else if let Some(getter) = self.at_accessor() {
+                self.bump();
+                let name = self.parse_prop_name()?;
+                let sig = self.parse_signature(":")?;
+                accessor_kind(getter, name, sig).into_member()
+            } else {
+                let name = self.parse_prop_name()?;
+                let optional = self.eat_punct("?");
+                if self.at_punct("(") || self.at_punct("<") {
+                    MemberKind::Method(MethodSig {
+                        name,
+                        optional,
+                        sig: self.parse_signature(":")?,
+                    })
+                } else {
+                    let ty = if self.eat_punct(":") {
+                        Some(self.parse_type()?)
+                    } else {
+                        None
+                    };
+                    MemberKind::Property(PropertySig {
+                        name,
+                        optional,
+                        readonly,
+                        ty,
+                    })
+                }
+            }
+        };
+        Ok(Member {
+            kind,
+            doc,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_prop_name(&mut self) -> PResult<PropName> {
+        let tok = self.peek().clone();
+        let name = match tok.kind {
+            TokenKind::Ident(name) => PropName::Ident(Ident {
+                name,
+                span: tok.span,
+            }),
+            TokenKind::PrivateName(name) => PropName::Private(Ident {
+                name,
+                span: tok.span,
+            }),
+            TokenKind::Str(s) => PropName::Str(s, tok.span),
+            TokenKind::Num(n) => PropName::Num(n, tok.span),
+            TokenKind::Punct("[") => {
+                self.bump();
+                let expr = self.parse_expr()?;
+                self.expect_punct("]")?;
+                return Ok(PropName::Computed(Box::new(expr)));
+            }
+            _ => return Err(self.unexpected("property name")),
+        };
+        self.bump();
+        Ok(name)
+    }
+
+    // ----- signatures -----
+
+    /// Parses `<T>(params) ret_sep Ret`. The return type is optional when `ret_sep` is `:`.
+    fn parse_signature(&mut self, ret_sep: &str) -> PResult<Signature> {
+        let start = self.peek().span.start;
+        let type_params = self.parse_opt_type_params()?;
+        self.expect_punct("(")?;
+        let params = self.parse_param_list(")")?;
+        let ret = if ret_sep == "=>" {
+            self.expect_punct("=>")?;
+            Some(self.parse_return_type()?)
+        } else if self.eat_punct(ret_sep) {
+            Some(self.parse_return_type()?)
+        } else {
+            None
+        };
+        Ok(Signature {
+            type_params,
+            params,
+            ret,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parses parameters up to and including the `close` bracket.
+    fn parse_param_list(&mut self, close: &str) -> PResult<Vec<Param>> {
+        let mut params = Vec::new();
+        while !self.at_punct(close) {
+            params.push(self.parse_param()?);
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+        self.expect_punct(close)?;
+        Ok(params)
+    }
+
+    fn parse_param(&mut self) -> PResult<Param> {
+        let start = self.peek().span.start;
+        let mut accessibility = None;
+        let mut readonly = false;
+        loop {
+            let next = self.peek_nth(1);
+            let followed_by_name = matches!(next.kind, TokenKind::Ident(_))
+                || next.is_punct("{")
+                || next.is_punct("[")
+                || next.is_punct("...");
+            if !followed_by_name {
+                break;
+            }
+            if self.at_ident("public") {
+                accessibility = Some(Accessibility::Public);
+            } else if self.at_ident("protected") {
+                accessibility = Some(Accessibility::Protected);
+            } else if self.at_ident("private") {
+                accessibility = Some(Accessibility::Private);
+            } else if self.at_ident("readonly") {
+                readonly = true;
+            } else if !self.at_ident("override") {
+                break;
+            }
+            self.bump();
+        }
+        let rest = self.eat_punct("...");
+        let name = if self.at_punct("{") || self.at_punct("[") {
+            let pat_start = self.peek().span.start;
+            self.skip_balanced()?;
+            let span = self.span_from(pat_start);
+            ParamName::Pattern(span.text(self.src).to_string(), span)
+        } else {
+            ParamName::Ident(self.ident()?)
+        };
+        let optional = self.eat_punct("?");
+        let ty = if self.eat_punct(":") {
+            Some(self.parse_type()?)
+        } else {
+            None
+        };
+        if self.eat_punct("=") {
+            self.parse_expr()?;
+        }
+        Ok(Param {
+            name,
+            optional,
+            rest,
+            ty,
+            accessibility,
+            readonly,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_opt_type_params(&mut self) -> PResult<Vec<TypeParam>> {
+        let mut params = Vec::new();
+        if !self.eat_punct("<") {
+            return Ok(params);
+        }
+        while !self.at_punct(">") {
+            let start = self.peek().span.start;
+            while (self.at_ident("const") || self.at_ident("in") || self.at_ident("out"))
+                && matches!(self.peek_nth(1).kind, TokenKind::Ident(_))
+            {
+                self.bump();
+            }
+            let name = self.ident()?;
+            let constraint = if self.eat_ident("extends") {
+                Some(self.parse_type()?)
+            } else {
+                None
+            };
+            let default = if self.eat_punct("=") {
+                Some(self.parse_type()?)
+            } else {
+                None
+            };
+            params.push(TypeParam {
+                name,
+                constraint,
+                default,
+                span: self.span_from(start),
+            });
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+        self.expect_punct(">")?;
+        Ok(params)
+    }
+
+    fn parse_opt_type_args(&mut self) -> PResult<Vec<Type>> {
+        let mut args = Vec::new();
+        if !self.at_punct("<") || self.peek().newline_before {
+            return Ok(args);
+        }
+        self.bump();
+        while !self.at_punct(">") {
+            args.push(self.parse_type()?);
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+        self.expect_punct(">")?;
+        Ok(args)
+    }
+
+    fn parse_entity_name(&mut self) -> PResult<EntityName> {
+        let first = self.ident()?;
+        let start = first.span.start;
+        let mut parts = vec![first];
+        while self.at_punct(".") && matches!(self.peek_nth(1).kind, TokenKind::Ident(_)) {
+            self.bump();
+            parts.push(self.ident()?);
+        }
+        Ok(EntityName {
+            parts,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_type_ref(&mut self) -> PResult<TypeRef> {
+        let start = self.peek().span.start;
+        let name = self.parse_entity_name()?;
+        let type_args = self.parse_opt_type_args()?;
+        Ok(TypeRef {
+            name,
+            type_args,
+            span: self.span_from(start),
+        })
+    }
+
+    // ----- types -----
+
+    pub fn parse_type(&mut self) -> PResult<Type> {
+        self.parse_type_inner(true)
+    }
+
+    fn parse_type_inner(&mut self, allow_conditional: bool) -> PResult<Type> {
+        if self.at_function_type() {
+            return self.parse_function_type(false);
+        }
+        if self.at_ident("new") {
+            return self.parse_function_type(false);
+        }
+        if self.at_ident("abstract") && self.peek_nth(1).is_ident("new") {
+            self.bump();
+            return self.parse_function_type(true);
+        }
+        let start = self.peek().span.start;
+        let check = self.parse_union_type()?;
+        if !allow_conditional || !self.at_ident("extends") || self.peek().newline_before {
+            return Ok(check);
+        }
+        self.bump();
+        let extends = self.parse_type_inner(false)?;
+        self.expect_punct("?")?;
+        let true_ty = self.parse_type()?;
+        self.expect_punct(":")?;
+        let false_ty = self.parse_type()?;
+        Ok(Type {
+            kind: TypeKind::Conditional(Box::new(ConditionalType {
+                check,
+                extends,
+                true_ty,
+                false_ty,
+            })),
+            span: self.span_from(start),
+        })
+    }
+
+    /// Decides whether a `(` or `<` starts a function type rather than a parenthesized type.
+    fn at_function_type(&self) -> bool {
+        if self.at_punct("<") {
+            return true;
+        }
+        if !self.at_punct("(") {
+            return false;
+        }
+        let next = self.peek_nth(1);
+        if next.is_punct(")") || next.is_punct("...") {
+            return true;
+        }
+        // Skip over what would be the first parameter's name or pattern.
+        let mut idx = self.pos + 1;
+        match &self.tokens[idx].kind {
+            TokenKind::Ident(name) => {
+                if matches!(
+                    name.as_str(),
+                    "public" | "private" | "protected" | "readonly"
+                ) && matches!(self.peek_nth(2).kind, TokenKind::Ident(_))
+                {
+                    idx += 1;
+                }
+                idx += 1;
+            }
+            TokenKind::Punct("{") | TokenKind::Punct("[") => {
+                let mut depth = 0usize;
+                loop {
+                    match self.tokens[idx].kind {
+                        TokenKind::Punct("{") | TokenKind::Punct("[") | TokenKind::Punct("(") => {
+                            depth += 1
+                        }
+                        TokenKind::Punct("}") | TokenKind::Punct("]") | TokenKind::Punct(")") => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        TokenKind::Eof => return false,
+                        _ => {}
+                    }
+                    idx += 1;
+                }
+                idx += 1;
+            }
+            _ => return false,
+        }
+        let after = &self.tokens[idx.min(self.tokens.len() - 1)];
+        if after.is_punct(":") || after.is_punct(",") || after.is_punct("?") || after.is_punct("=")
+        {
+            return true;
+        }
+        after.is_punct(")") && self.tokens[(idx + 1).min(self.tokens.len() - 1)].is_punct("=>")
+    }
+
+    fn parse_function_type(&mut self, is_abstract: bool) -> PResult<Type> {
+        let start = self.peek().span.start;
+        let constructor = self.eat_ident("new");
+        let sig = self.parse_signature("=>")?;
+        let func = Box::new(FunctionType { is_abstract, sig });
+        Ok(Type {
+            kind: if constructor {
+                TypeKind::Constructor(func)
+            } else {
+                TypeKind::Function(func)
+            },
+            span: self.span_from(start),
+        })
+    }
+
+    /// Parses a return type, which may be a type predicate.
+    fn parse_return_type(&mut self) -> PResult<Type> {
+        let start = self.peek().span.start;
+        let asserts = self.at_ident("asserts")
+            && matches!(self.peek_nth(1).kind, TokenKind::Ident(_))
+            && !self.peek_nth(1).newline_before;
+        if asserts {
+            self.bump();
+        }
+        let is_predicate = asserts
+            || (self.at_any_ident()
+                && self.peek_nth(1).is_ident("is")
+                && !self.peek_nth(1).newline_before);
+        if !is_predicate {
+            return self.parse_type();
+        }
+        let param = self.ident()?;
+        let ty = if self.eat_ident("is") {
+            Some(Box::new(self.parse_type()?))
+        } else {
+            None
+        };
+        Ok(Type {
+            kind: TypeKind::Predicate { asserts, param, ty },
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_union_type(&mut self) -> PResult<Type> {
+        let start = self.peek().span.start;
+        let leading = self.eat_punct("|");
+        let first = self.parse_intersection_type()?;
+        if !self.at_punct("|") {
+            return Ok(if leading {
+                Type {
+                    span: self.span_from(start),
+                    ..first
+                }
+            } else {
+                first
+            });
+        }
+        let mut types = vec![first];
+        while self.eat_punct("|") {
+            types.push(self.parse_intersection_type()?);
+        }
+        Ok(Type {
+            kind: TypeKind::Union(types),
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_intersection_type(&mut self) -> PResult<Type> {
+        let start = self.peek().span.start;
+        self.eat_punct("&");
+        let first = self.parse_operator_type()?;
+        if !self.at_punct("&") {
+            return Ok(first);
+        }
+        let mut types = vec![first];
+        while self.eat_punct("&") {
+            types.push(self.parse_operator_type()?);
+        }
+        Ok(Type {
+            kind: TypeKind::Intersection(types),
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_operator_type(&mut self) -> PResult<Type> {
+        let start = self.peek().span.start;
+        let op = if self.at_ident("keyof") {
+            Some(TypeOperator::Keyof)
+        } else if self.at_ident("unique") {
+            Some(TypeOperator::Unique)
+        } else if self.at_ident("readonly") {
+            Some(TypeOperator::Readonly)
+        } else {
+            None
+        };
+        if let Some(op) = op {
+            if self.starts_type(self.peek_nth(1)) {
+                self.bump();
+                let operand = self.parse_operator_type()?;
+                return Ok(Type {
+                    kind: TypeKind::Operator(op, Box::new(operand)),
+                    span: self.span_from(start),
+                });
+            }
+        }
+        if self.at_ident("infer") && matches!(self.peek_nth(1).kind, TokenKind::Ident(_)) {
+            self.bump();
+            let name = self.ident()?;
+            let constraint = self.parse_infer_constraint()?;
+            return Ok(Type {
+                kind: TypeKind::Infer { name, constraint },
+                span: self.span_from(start),
+            });
+        }
+        self.parse_postfix_type()
+    }
+
+    /// `infer U extends C` is ambiguous with a conditional type whose check type is `infer U`.
+    /// Like `tsc`, treat the `extends` as a constraint unless a `?` follows it.
+    fn parse_infer_constraint(&mut self) -> PResult<Option<Box<Type>>> {
+        if !self.at_ident("extends") {
+            return Ok(None);
+        }
+        let saved = self.pos;
+        self.bump();
+        match self.parse_type_inner(false) {
+            Ok(ty) if !self.at_punct("?") => Ok(Some(Box::new(ty))),
+            _ => {
+                self.pos = saved;
+                Ok(None)
+            }
+        }
+    }
+
+    fn starts_type(&self, tok: &Token) -> bool {
+        match &tok.kind {
+            TokenKind::Ident(_)
+            | TokenKind::Str(_)
+            | TokenKind::Num(_)
+            | TokenKind::BigInt(_)
+            | TokenKind::Template { .. } => true,
+            TokenKind::Punct(p) => matches!(*p, "(" | "[" | "{" | "<" | "-" | "|" | "&"),
+            _ => false,
+        }
+    }
+
+    fn parse_postfix_type(&mut self) -> PResult<Type> {
+        let start = self.peek().span.start;
+        let mut ty = self.parse_primary_type()?;
+        while self.at_punct("[") && !self.peek().newline_before {
+            self.bump();
+            if self.eat_punct("]") {
+                ty = Type {
+                    kind: TypeKind::Array(Box::new(ty)),
+                    span: self.span_from(start),
+                };
+            } else {
+                let index = self.parse_type()?;
+                self.expect_punct("]")?;
+                ty = Type {
+                    kind: TypeKind::Indexed {
+                        object: Box::new(ty),
+                        index: Box::new(index),
+                    },
+                    span: self.span_from(start),
+                };
+            }
+        }
+        Ok(ty)
+    }
+
+    fn parse_primary_type(&mut self) -> PResult<Type> {
+        let tok = self.peek().clone();
+        let start = tok.span.start;
+        let kind = match tok.kind {
+            TokenKind::Punct("(") => {
+                self.bump();
+                let ty = self.parse_type()?;
+                self.expect_punct(")")?;
+                return Ok(ty);
+            }
+            TokenKind::Punct("[") => self.parse_tuple_type()?,
+            TokenKind::Punct("{") => {
+                if self.at_mapped_type() {
+                    self.parse_mapped_type()?
+                } else {
+                    TypeKind::Object(self.parse_type_members()?)
+                }
+            }
+            TokenKind::Punct("-") => {
+                self.bump();
+                match self.bump().kind {
+                    TokenKind::Num(n) => TypeKind::Literal(LiteralType::Num(format!("-{}", n))),
+                    TokenKind::BigInt(n) => {
+                        TypeKind::Literal(LiteralType::BigInt(format!("-{}", n)))
+                    }
+                    _ => {
+                        self.pos -= 1;
+                        return Err(self.unexpected("number literal"));
+                    }
+                }
+            }
+            TokenKind::Str(s) => {
+                self.bump();
+                TypeKind::Literal(LiteralType::Str(s))
+            }
+            TokenKind::Num(n) => {
+                self.bump();
+                TypeKind::Literal(LiteralType::Num(n))
+            }
+            TokenKind::BigInt(n) => {
+                self.bump();
+                TypeKind::Literal(LiteralType::BigInt(n))
+            }
+            TokenKind::Template {
+                quasis,
+                substitutions,
+            } => {
+                self.bump();
+                let mut types = Vec::new();
+                for span in substitutions {
+                    let mut sub = Parser::for_range(self.src, span)?;
+                    types.push(sub.parse_type()?);
+                    if !sub.at_eof() {
+                        return Err(sub.unexpected("`}`"));
+                    }
+                }
+                TypeKind::Template { quasis, types }
+            }
+            TokenKind::Ident(name) => match name.as_str() {
+                "true" | "false" => {
+                    self.bump();
+                    TypeKind::Literal(LiteralType::Bool(name == "true"))
+                }
+                "typeof" => {
+                    self.bump();
+                    if self.at_ident("import") {
+                        self.parse_import_type()?
+                    } else {
+                        let name = self.parse_entity_name()?;
+                        self.parse_opt_type_args()?;
+                        TypeKind::Query(name)
+                    }
+                }
+                "import" if self.peek_nth(1).is_punct("(") => self.parse_import_type()?,
+                _ => match KeywordType::from_name(&name) {
+                    Some(kw) if !self.peek_nth(1).is_punct(".") => {
+                        self.bump();
+                        TypeKind::Keyword(kw)
+                    }
+                    _ => TypeKind::Reference(self.parse_type_ref()?),
+                },
+            },
+            _ => return Err(self.unexpected("type")),
+        };
+        Ok(Type {
+            kind,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_import_type(&mut self) -> PResult<TypeKind> {
+        self.expect_ident_kw("import")?;
+        self.expect_punct("(")?;
+        let module = match self.bump().kind {
+            TokenKind::Str(s) => s,
+            _ => {
+                self.pos -= 1;
+                return Err(self.unexpected("module specifier"));
+            }
+        };
+        self.expect_punct(")")?;
+        let qualifier = if self.eat_punct(".") {
+            Some(self.parse_entity_name()?)
+        } else {
+            None
+        };
+        let type_args = self.parse_opt_type_args()?;
+        Ok(TypeKind::Import {
+            module,
+            qualifier,
+            type_args,
+        })
+    }
+
+    fn parse_tuple_type(&mut self) -> PResult<TypeKind> {
+        self.expect_punct("[")?;
+        let mut elements = Vec::new();
+        while !self.at_punct("]") {
+            let start = self.peek().span.start;
+            let rest = self.eat_punct("...");
+            let labeled = matches!(self.peek().kind, TokenKind::Ident(_))
+                && (self.peek_nth(1).is_punct(":")
+                    || self.peek_nth(1).is_punct("?") && self.peek_nth(2).is_punct(":"));
+            let (name, mut optional) = if labeled {
+                let name = self.ident()?;
+                let optional = self.eat_punct("?");
+                self.expect_punct(":")?;
+                (Some(name), optional)
+            } else {
+                (None, false)
+            };
+            let rest = rest || self.eat_punct("...");
+            let ty = self.parse_type()?;
+            if self.eat_punct("?") {
+                optional = true;
+            }
+            elements.push(TupleElement {
+                name,
+                ty,
+                optional,
+                rest,
+                span: self.span_from(start),
+            });
+            if !self.eat_punct(",") {
+                break;
+            }
+        }
+        self.expect_punct("]")?;
+        Ok(TypeKind::Tuple(elements))
+    }
+
+    fn at_mapped_type(&self) -> bool {
+        let mut n = 1;
+        if self.peek_nth(n).is_punct("+") || self.peek_nth(n).is_punct("-") {
+            n += 1;
+        }
+        if self.peek_nth(n).is_ident("readonly") {
+            n += 1;
+        }
+        self.peek_nth(n).is_punct("[")
+            && matches!(self.peek_nth(n + 1).kind, TokenKind::Ident(_))
+            && self.peek_nth(n + 2).is_ident("in")
+    }
+
+    fn parse_mapped_modifier(&mut self) -> Option<MappedModifier> {
+        if self.eat_punct("+") {
+            Some(MappedModifier::Add)
+        } else if self.eat_punct("-") {
+            Some(MappedModifier::Remove)
+        } else {
+            None
+        }
+    }
+
+    fn parse_mapped_type(&mut self) -> PResult<TypeKind> {
+        self.expect_punct("{")?;
+        let sign = self.parse_mapped_modifier();
+        let readonly = if self.eat_ident("readonly") {
+            Some(sign.unwrap_or(MappedModifier::Add))
+        } else {
+            None
+        };
+        self.expect_punct("[")?;
+        let param = self.ident()?;
+        self.expect_ident_kw("in")?;
+        let constraint = self.parse_type()?;
+        let name_type = if self.eat_ident("as") {
+            Some(self.parse_type()?)
+        } else {
+            None
+        };
+        self.expect_punct("]")?;
+        let sign = self.parse_mapped_modifier();
+        let optional = if self.eat_punct("?") {
+            Some(sign.unwrap_or(MappedModifier::Add))
+        } else {
+            None
+        };
+        let ty = if self.eat_punct(":") {
+            Some(self.parse_type()?)
+        } else {
+            None
+        };
+        self.eat_punct(";");
+        self.eat_punct(",");
+        self.expect_punct("}")?;
+        Ok(TypeKind::Mapped(Box::new(MappedType {
+            readonly,
+            param,
+            constraint,
+            name_type,
+            optional,
+            ty,
+        })))
+    }
+
+    // ----- expressions -----
+
+    pub fn parse_expr(&mut self) -> PResult<Expr> {
+        self.parse_binary_expr(0)
+    }
+
+    /// Returns the binary operator at the current position, its precedence and its length in
+    /// tokens. Multi-character shift operators are assembled from adjacent `<`/`>` tokens.
+    fn peek_binary_op(&self) -> Option<(BinaryOp, u8, usize)> {
+        let tok = self.peek();
+        let p = match &tok.kind {
+            TokenKind::Punct(p) => *p,
+            _ => return None,
+        };
+        let joined = |n: usize, q: &str| {
+            let mut prev = tok;
+            for i in 1..=n {
+                let next = self.peek_nth(i);
+                if !next.is_punct(q) || !prev.joined_to_next(next) {
+                    return false;
+                }
+                prev = next;
+            }
+            true
+        };
+        Some(match p {
+            "|" => (BinaryOp::BitOr, 1, 1),
+            "^" => (BinaryOp::BitXor, 2, 1),
+            "&" => (BinaryOp::BitAnd, 3, 1),
+            "<" if joined(1, "<") => (BinaryOp::Shl, 4, 2),
+            ">" if joined(2, ">") => (BinaryOp::UShr, 4, 3),
+            ">" if joined(1, ">") => (BinaryOp::Shr, 4, 2),
+            "+" => (BinaryOp::Add, 5, 1),
+            "-" => (BinaryOp::Sub, 5, 1),
+            "*" if joined(1, "*") => (BinaryOp::Exp, 7, 2),
+            "*" => (BinaryOp::Mul, 6, 1),
+            "/" => (BinaryOp::Div, 6, 1),
+            "%" => (BinaryOp::Rem, 6, 1),
+            _ => return None,
+        })
+    }
+
+    fn parse_binary_expr(&mut self, min_prec: u8) -> PResult<Expr> {
+        let start = self.peek().span.start;
+        let mut lhs = self.parse_unary_expr()?;
+        while let Some((op, prec, len)) = self.peek_binary_op() {
+            if prec < min_prec {
+                break;
+            }
+            for _ in 0..len {
+                self.bump();
+            }
+            // `**` is right-associative, everything else is left-associative.
+            let next_min = if op == BinaryOp::Exp { prec } else { prec + 1 };
+            let rhs = self.parse_binary_expr(next_min)?;
+            lhs = Expr {
+                kind: ExprKind::Binary(op, Box::new(lhs), Box::new(rhs)),
+                span: self.span_from(start),
+            };
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary_expr(&mut self) -> PResult<Expr> {
+        let start = self.peek().span.start;
+        let op = match &self.peek().kind {
+            TokenKind::Punct("-") => Some(UnaryOp::Neg),
+            TokenKind::Punct("+") => Some(UnaryOp::Plus),
+            TokenKind::Punct("~") => Some(UnaryOp::BitNot),
+            TokenKind::Punct("!") => Some(UnaryOp::Not),
+            _ => None,
+        };
+        if let Some(op) = op {
+            self.bump();
+            let operand = self.parse_unary_expr()?;
+            return Ok(Expr {
+                kind: ExprKind::Unary(op, Box::new(operand)),
+                span: self.span_from(start),
+            });
+        }
+        let mut expr = self.parse_primary_expr()?;
+        loop {
+            if self.at_punct(".") {
+                self.bump();
+                let name = self.ident()?;
+                expr = Expr {
+                    kind: ExprKind::Member(Box::new(expr), name),
+                    span: self.span_from(start),
+                };
+            } else if self.at_punct("[") && !self.peek().newline_before {
+                self.bump();
+                let index = self.parse_expr()?;
+                self.expect_punct("]")?;
+                expr = Expr {
+                    kind: ExprKind::Index(Box::new(expr), Box::new(index)),
+                    span: self.span_from(start),
+                };
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn parse_primary_expr(&mut self) -> PResult<Expr> {
+        let tok = self.peek().clone();
+        let kind = match tok.kind {
+            TokenKind::Num(n) => ExprKind::Num(n),
+            TokenKind::BigInt(n) => ExprKind::BigInt(n),
+            TokenKind::Str(s) => ExprKind::Str(s),
+            TokenKind::Template {
+                mut quasis,
+                substitutions,
+            } if substitutions.is_empty() => ExprKind::Template(quasis.remove(0)),
+            TokenKind::Ident(name) => match name.as_str() {
+                "true" => ExprKind::Bool(true),
+                "false" => ExprKind::Bool(false),
+                "null" => ExprKind::Null,
+                _ => ExprKind::Ident(Ident {
+                    name,
+                    span: tok.span,
+                }),
+            },
+            TokenKind::Punct("(") => {
+                self.bump();
+                let expr = self.parse_expr()?;
+                self.expect_punct(")")?;
+                return Ok(expr);
+            }
+            _ => return Err(self.unexpected("expression")),
+        };
+        self.bump();
+        Ok(Expr {
+            kind,
+            span: tok.span,
+        })
+    }
+}
+
+/// A parsed `get`/`set` accessor, before it is placed into an interface or class member.
+enum Accessor {
+    Get(GetterSig),
+    Set(SetterSig),
+}
+
+fn accessor_kind(getter: bool, name: PropName, sig: Signature) -> Accessor {
+    if getter {
+        Accessor::Get(GetterSig { name, ty: sig.ret })
+    } else {
+        let param = sig.params.into_iter().next().unwrap_or(Param {
+            name: ParamName::Ident(Ident {
+                name: "value".to_string(),
+                span: sig.span,
+            }),
+            optional: false,
+            rest: false,
+            ty: None,
+            accessibility: None,
+            readonly: false,
+            span: sig.span,
+        });
+        Accessor::Set(SetterSig { name, param })
+    }
+}
+
+impl Accessor {
+    fn into_member(self) -> MemberKind {
+        match self {
+            Accessor::Get(g) => MemberKind::Getter(g),
+            Accessor::Set(s) => MemberKind::Setter(s),
+        }
+    }
+
+    fn into_class_member(self) -> ClassMemberKind {
+        match self {
+            Accessor::Get(g) => ClassMemberKind::Getter(g),
+            Accessor::Set(s) => ClassMemberKind::Setter(s),
+        }
+    }
+}
+
+/// Parses the `/// <reference ... />` directives in the comments before the first statement.
+fn parse_references(leading: &str) -> Vec<Reference> {
+    let mut references = Vec::new();
+    let mut offset = 0;
+    for line in leading.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let Some(directive) = line.trim().strip_prefix("///") else {
+            continue;
+        };
+        let Some(attrs) = directive.trim_start().strip_prefix("<reference") else {
+            continue;
+        };
+        for (name, kind) in [
+            ("path", ReferenceKind::Path),
+            ("types", ReferenceKind::Types),
+            ("lib", ReferenceKind::Lib),
+        ] {
+            if let Some(value) = reference_attr(attrs, name) {
+                let span = Span::new(start, start + line.trim_end().len());
+                references.push(Reference { kind, value, span });
+                break;
+            }
+        }
+    }
+    references
+}
+
+/// The value of attribute `name="..."` in a reference directive.
+fn reference_attr(attrs: &str, name: &str) -> Option<String> {
+    let mut rest = attrs;
+    while let Some(i) = rest.find(name) {
+        let before = rest[..i].chars().next_back();
+        let after = rest[i + name.len()..].trim_start();
+        rest = &rest[i + name.len()..];
+        if before.is_some_and(|c| !c.is_whitespace()) {
+            continue;
+        }
+        let Some(value) = after.strip_prefix('=') else {
+            continue;
+        };
+        let value = value.trim_start();
+        let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let value = &value[1..];
+        return value.find(quote).map(|end| value[..end].to_string());
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(src: &str) -> Module {
+        Parser::new(src).unwrap().parse_module().unwrap()
+    }
+
+    fn parse_type(src: &str) -> Type {
+        Parser::new(src).unwrap().parse_type().unwrap()
+    }
+
+    fn only_item(src: &str) -> Item {
+        let mut module = parse(src);
+        assert_eq!(module.items.len(), 1, "{:?}", module.items);
+        module.items.remove(0)
+    }
+
+    fn names(types: &[Type], src: &str) -> Vec<String> {
+        types.iter().map(|t| t.span.text(src).to_string()).collect()
+    }
+
+    #[test]
+    fn function_declaration() {
+        let src = "/** Adds. */\ndeclare function add<T extends number = 1>(a: T, b?: number, ...rest: T[]): T;";
+        let item = only_item(src);
+        assert!(item.declare);
+        assert_eq!(item.doc.as_deref(), Some("Adds."));
+        let ItemKind::Function(FunctionDecl {
+            name: Some(name),
+            sig,
+        }) = item.kind
+        else {
+            panic!("not a function: {:?}", item.kind);
+        };
+        assert_eq!(name.name, "add");
+        let [param] = sig.type_params.as_slice() else {
+            panic!("{:?}", sig.type_params);
+        };
+        assert_eq!(param.name.name, "T");
+        assert_eq!(param.constraint.as_ref().unwrap().span.text(src), "number");
+        assert_eq!(param.default.as_ref().unwrap().span.text(src), "1");
+        let params: Vec<(&str, bool, bool)> = sig
+            .params
+            .iter()
+            .map(|p| (p.name.name(), p.optional, p.rest))
+            .collect();
+        assert_eq!(
+            params,
+            [
+                ("a", false, false),
+                ("b", true, false),
+                ("rest", false, true)
+            ]
+        );
+        assert_eq!(sig.ret.unwrap().span.text(src), "T");
+    }
+
+    #[test]
+    fn interface_members() {
+        let src = "interface Foo<T> extends Bar, Baz<T> {\n\
+                   readonly a: string;\n\
+                   b?(x: number): void;\n\
+                   (x: number): string;\n\
+                   new (x: number): Foo<T>;\n\
+                   [key: string]: any;\n\
+                   get c(): number;\n\
+                   set c(value: number);\n\
+                   \"quoted-name\": boolean;\n\
+                   }";
+        let ItemKind::Interface(decl) = only_item(src).kind else {
+            panic!("not an interface");
+        };
+        assert_eq!(decl.name.name, "Foo");
+        let extends: Vec<String> = decl.extends.iter().map(|e| e.name.to_dotted()).collect();
+        assert_eq!(extends, ["Bar", "Baz"]);
+        assert_eq!(decl.extends[1].type_args.len(), 1);
+        let kinds: Vec<&MemberKind> = decl.members.iter().map(|m| &m.kind).collect();
+        assert!(matches!(
+            kinds[0],
+            MemberKind::Property(PropertySig {
+                readonly: true,
+                optional: false,
+                ..
+            })
+        ));
+        assert!(matches!(
+            kinds[1],
+            MemberKind::Method(MethodSig { optional: true, .. })
+        ));
+        assert!(matches!(kinds[2], MemberKind::Call(_)));
+        assert!(matches!(kinds[3], MemberKind::Construct(_)));
+        assert!(matches!(kinds[4], MemberKind::Index(_)));
+        assert!(matches!(kinds[5], MemberKind::Getter(_)));
+        assert!(matches!(kinds[6], MemberKind::Setter(_)));
+        let MemberKind::Property(prop) = kinds[7] else {
+            panic!("{:?}", kinds[7]);
+        };
+        assert_eq!(prop.name.as_str(), Some("quoted-name"));
+    }
+
+    #[test]
+    fn class_members() {
+        let src = "export declare abstract class Foo<T> extends Base<T> implements A, B {\n\
+                   constructor(public x: number);\n\
+                   static create(): Foo<string>;\n\
+                   protected abstract run(): void;\n\
+                   private readonly id;\n\
+                   }";
+        let item = only_item(src);
+        assert!(item.export && item.declare);
+        let ItemKind::Class(decl) = item.kind else {
+            panic!("not a class");
+        };
+        assert!(decl.is_abstract);
+        assert_eq!(decl.extends.unwrap().span.text(src), "Base<T>");
+        assert_eq!(decl.implements.len(), 2);
+        let [ctor, create, run, id] = decl.members.as_slice() else {
+            panic!("{:?}", decl.members);
+        };
+        let ClassMemberKind::Constructor(sig) = &ctor.kind else {
+            panic!("{:?}", ctor.kind);
+        };
+        assert_eq!(sig.params[0].accessibility, Some(Accessibility::Public));
+        assert!(create.is_static);
+        assert!(run.is_abstract);
+        assert_eq!(run.accessibility, Some(Accessibility::Protected));
+        assert!(id.readonly);
+        let ClassMemberKind::Property(prop) = &id.kind else {
+            panic!("{:?}", id.kind);
+        };
+        assert!(prop.ty.is_none());
+    }
+
+    #[test]
+    fn namespaces_and_modules() {
+        let module = parse(
+            "declare namespace A.B { const x: number; }\n\
+             declare module \"pkg\" { export function f(): void; }\n\
+             declare module \"shorthand\";\n\
+             declare global { interface Window {} }",
+        );
+        let kinds: Vec<&ItemKind> = module.items.iter().map(|i| &i.kind).collect();
+        let ItemKind::Namespace(ns) = kinds[0] else {
+            panic!("{:?}", kinds[0]);
+        };
+        assert_eq!(ns.name.to_dotted(), "A.B");
+        assert_eq!(ns.body.items.len(), 1);
+        let ItemKind::Module(m) = kinds[1] else {
+            panic!("{:?}", kinds[1]);
+        };
+        assert_eq!(m.name, "pkg");
+        assert!(m.body.as_ref().unwrap().items[0].export);
+        assert!(matches!(
+            kinds[2],
+            ItemKind::Module(ModuleDecl { body: None, .. })
+        ));
+        assert!(matches!(kinds[3], ItemKind::Global(_)));
+        assert!(!module.is_es_module());
+    }
+
+    #[test]
+    fn imports_and_exports() {
+        let module = parse(
+            "import Def, { a, type b as c } from \"m\";\n\
+             import * as ns from \"n\";\n\
+             import X = A.B;\n\
+             export * as all from \"o\";\n\
+             export { a as d };\n\
+             export = Def;\n\
+             export as namespace Lib;",
+        );
+        assert!(module.is_es_module());
+        let ItemKind::Import(import) = &module.items[0].kind else {
+            panic!("{:?}", module.items[0]);
+        };
+        assert_eq!(import.default.as_ref().unwrap().name, "Def");
+        let named: Vec<(&str, bool)> = import
+            .named
+            .iter()
+            .map(|s| (s.local().name.as_str(), s.type_only))
+            .collect();
+        assert_eq!(named, [("a", false), ("c", true)]);
+        assert_eq!(import.module, "m");
+        let ItemKind::Import(import) = &module.items[1].kind else {
+            panic!("{:?}", module.items[1]);
+        };
+        assert_eq!(import.namespace.as_ref().unwrap().name, "ns");
+        assert!(matches!(module.items[2].kind, ItemKind::ImportAlias(_)));
+        let exports: Vec<&ExportDecl> = module.items[3..]
+            .iter()
+            .map(|i| match &i.kind {
+                ItemKind::Export(e) => e,
+                kind => panic!("{:?}", kind),
+            })
+            .collect();
+        assert!(matches!(exports[0], ExportDecl::All { alias: Some(_), .. }));
+        assert!(matches!(exports[1], ExportDecl::Named { module: None, .. }));
+        assert!(matches!(exports[2], ExportDecl::Assign(name) if name.to_dotted() == "Def"));
+        assert!(matches!(exports[3], ExportDecl::AsNamespace(name) if name.name == "Lib"));
+    }
+
+    #[test]
+    fn enums_and_variables() {
+        let module = parse(
+            "declare const enum E { A = 1, B = A << 2, \"C\" = \"c\" }\n\
+             declare let x: number, y: string;\n\
+             declare const VERSION = \"1.0\";",
+        );
+        let ItemKind::Enum(e) = &module.items[0].kind else {
+            panic!("{:?}", module.items[0]);
+        };
+        assert!(e.is_const);
+        let names: Vec<Option<&str>> = e.members.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, [Some("A"), Some("B"), Some("C")]);
+        assert!(matches!(
+            e.members[1].init.as_ref().unwrap().kind,
+            ExprKind::Binary(BinaryOp::Shl, ..)
+        ));
+        let ItemKind::Variable(decl) = &module.items[1].kind else {
+            panic!("{:?}", module.items[1]);
+        };
+        assert_eq!(decl.kind, VariableKind::Let);
+        assert_eq!(decl.declarators.len(), 2);
+        let ItemKind::Variable(decl) = &module.items[2].kind else {
+            panic!("{:?}", module.items[2]);
+        };
+        assert!(matches!(
+            &decl.declarators[0].init.as_ref().unwrap().kind,
+            ExprKind::Str(s) if s == "1.0"
+        ));
+    }
+
+    #[test]
+    fn unions_and_intersections() {
+        let src = "| A | B & C | (D | E)[]";
+        let TypeKind::Union(types) = parse_type(src).kind else {
+            panic!("not a union");
+        };
+        assert_eq!(names(&types, src), ["A", "B & C", "(D | E)[]"]);
+        assert!(matches!(types[1].kind, TypeKind::Intersection(_)));
+        let TypeKind::Array(elem) = &types[2].kind else {
+            panic!("{:?}", types[2]);
+        };
+        // Parentheses are dropped.
+        assert!(matches!(elem.kind, TypeKind::Union(_)));
+    }
+
+    #[test]
+    fn function_and_constructor_types() {
+        let TypeKind::Function(f) = parse_type("<T>(x: T, ...rest: any[]) => void").kind else {
+            panic!("not a function type");
+        };
+        assert_eq!(f.sig.type_params.len(), 1);
+        assert!(f.sig.params[1].rest);
+        let TypeKind::Constructor(c) = parse_type("abstract new () => Foo").kind else {
+            panic!("not a constructor type");
+        };
+        assert!(c.is_abstract);
+    }
+
+    #[test]
+    fn tuples() {
+        let src = "[a: string, b?: number, ...rest: boolean[]]";
+        let TypeKind::Tuple(elems) = parse_type(src).kind else {
+            panic!("not a tuple");
+        };
+        let elems: Vec<(&str, bool, bool)> = elems
+            .iter()
+            .map(|e| (e.name.as_ref().unwrap().name.as_str(), e.optional, e.rest))
+            .collect();
+        assert_eq!(
+            elems,
+            [
+                ("a", false, false),
+                ("b", true, false),
+                ("rest", false, true)
+            ]
+        );
+        let TypeKind::Tuple(elems) = parse_type("[string, number?]").kind else {
+            panic!("not a tuple");
+        };
+        assert!(elems[0].name.is_none() && elems[1].optional);
+    }
+
+    #[test]
+    fn computed_types() {
+        let src = "T extends (infer U)[] ? U : never";
+        let TypeKind::Conditional(c) = parse_type(src).kind else {
+            panic!("not a conditional type");
+        };
+        assert_eq!(c.check.span.text(src), "T");
+        let TypeKind::Array(elem) = &c.extends.kind else {
+            panic!("{:?}", c.extends);
+        };
+        assert!(matches!(&elem.kind, TypeKind::Infer { name, .. } if name.name == "U"));
+
+        let src = "{ -readonly [K in keyof T as `get${K}`]+?: T[K] }";
+        let TypeKind::Mapped(m) = parse_type(src).kind else {
+            panic!("not a mapped type");
+        };
+        assert_eq!(m.readonly, Some(MappedModifier::Remove));
+        assert_eq!(m.optional, Some(MappedModifier::Add));
+        assert!(matches!(
+            m.constraint.kind,
+            TypeKind::Operator(TypeOperator::Keyof, _)
+        ));
+        let TypeKind::Template { quasis, types } = &m.name_type.as_ref().unwrap().kind else {
+            panic!("{:?}", m.name_type);
+        };
+        assert_eq!(quasis, &["get", ""]);
+        assert_eq!(types.len(), 1);
+        assert!(matches!(
+            m.ty.as_ref().unwrap().kind,
+            TypeKind::Indexed { .. }
+        ));
+
+        assert!(matches!(
+            parse_type("typeof a.b").kind,
+            TypeKind::Query(name) if name.to_dotted() == "a.b"
+        ));
+        assert!(matches!(
+            parse_type("import(\"m\").A<string>").kind,
+            TypeKind::Import { module, qualifier: Some(_), type_args } if module == "m" && type_args.len() == 1
+        ));
+    }
+
+    #[test]
+    fn literal_and_keyword_types() {
+        let src = "-1 | \"s\" | true | 10n | null | undefined | unique symbol";
+        let TypeKind::Union(types) = parse_type(src).kind else {
+            panic!("not a union");
+        };
+        let kinds: Vec<&TypeKind> = types.iter().map(|t| &t.kind).collect();
+        assert_eq!(
+            kinds[0],
+            &TypeKind::Literal(LiteralType::Num("-1".to_string()))
+        );
+        assert_eq!(
+            kinds[1],
+            &TypeKind::Literal(LiteralType::Str("s".to_string()))
+        );
+        assert_eq!(kinds[2], &TypeKind::Literal(LiteralType::Bool(true)));
+        assert!(matches!(
+            kinds[3],
+            TypeKind::Literal(LiteralType::BigInt(_))
+        ));
+        assert_eq!(kinds[4], &TypeKind::Keyword(KeywordType::Null));
+        assert_eq!(kinds[5], &TypeKind::Keyword(KeywordType::Undefined));
+        assert!(matches!(
+            kinds[6],
+            TypeKind::Operator(TypeOperator::Unique, _)
+        ));
+    }
+
+    #[test]
+    fn type_predicates() {
+        let module = parse(
+            "declare function isString(x: unknown): x is string;\n\
+             declare function check(x: unknown): asserts x;",
+        );
+        let predicates: Vec<(bool, &str, bool)> = module
+            .items
+            .iter()
+            .map(|item| match &item.kind {
+                ItemKind::Function(f) => match &f.sig.ret.as_ref().unwrap().kind {
+                    TypeKind::Predicate { asserts, param, ty } => {
+                        (*asserts, param.name.as_str(), ty.is_some())
+                    }
+                    kind => panic!("{:?}", kind),
+                },
+                kind => panic!("{:?}", kind),
+            })
+            .collect();
+        assert_eq!(predicates, [(false, "x", true), (true, "x", false)]);
+    }
+
+    #[test]
+    fn reference_directives() {
+        let module = parse(
+            "/// <reference path=\"./other.d.ts\" />\n\
+             /// <reference types='node' />\n\
+             declare const x: number;",
+        );
+        let references: Vec<(ReferenceKind, &str)> = module
+            .references
+            .iter()
+            .map(|r| (r.kind, r.value.as_str()))
+            .collect();
+        assert_eq!(
+            references,
+            [
+                (ReferenceKind::Path, "./other.d.ts"),
+                (ReferenceKind::Types, "node")
+            ]
+        );
+    }
+
+    #[test]
+    fn errors_are_located() {
+        let src = "declare function f(: number): void;";
+        let err = Parser::new(src).unwrap().parse_module().unwrap_err();
+        assert_eq!(err.span.text(src), ":");
+        assert_eq!(
+            err.display_with_source("a.d.ts", src),
+            format!("a.d.ts:1:20: {}", err.message)
+        );
+    }
+}
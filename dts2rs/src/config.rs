@@ -0,0 +1,281 @@
+//! User configuration, read from a TOML file.
+//!
+//! ```toml
+//! # Pin or override the Rust names of functions, methods and accessors.
+//! [names]
+//! "createElement(string)" = "create_element"
+//! "Document.createElement(string, ElementCreationOptions)" = "create_element_with_options"
+//! "Foo.constructor(number)" = "from_f64"
+//! "get Foo.size" = "len"
+//!
+//! # Bind generic types as typed wrappers (the default) or erase their type parameters.
+//! generics = "typed"
+//!
+//! # Import ES module declarations from this module instead of the global scope.
+//! module = "my-package"
+//!
+//! # Bind UMD libraries through the global they define, for loading them with a script tag.
+//! umd = "global"
+//!
+//! # Also bind functions returning promises as `async fn`.
+//! promises = "async"
+//!
+//! # Copy arrays to and from `Vec` and tuples to and from Rust tuples.
+//! arrays = "vec"
+//!
+//! # Copy typed arrays and buffers to and from Rust slices and `Vec`s, with parameters that
+//! # JavaScript writes to taken as `&mut [T]`.
+//! typed_arrays = "slice"
+//! mutable_slices = ["readInto.buffer", "Crypto.getRandomValues.array"]
+//!
+//! # Copy records such as `Record<string, number>` to and from `HashMap`s.
+//! records = "map"
+//!
+//! # Bind global types to these Rust types instead of the js-sys or web-sys ones.
+//! [globals]
+//! Buffer = "node_sys::Buffer"
+//! ResizeObserver = "web_sys::ResizeObserver"
+//!
+//! # Choose how unions are lowered, for all of them or per declaration.
+//! union_strategy = "auto"
+//!
+//! [unions]
+//! Shape = "js-value"
+//! "createElement.options" = "enum"
+//!
+//! # Name object literal types instead of naming them after where they are written.
+//! [type_names]
+//! "init.opts" = "InitOptions"
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Rust names to use instead of the generated ones, keyed by a function's signature key.
+    ///
+    /// Keys have the form `name(types)` for free functions, `Class.name(types)` for methods,
+    /// `Class.constructor(types)` for constructors and `get Class.prop` / `set Class.prop`
+    /// for accessors. Static members are prefixed with `static`, as in
+    /// `static Class.name(types)` or `get static Class.prop`. The accessors of an index
+    /// signature are `get Class[string]`, `set Class[string]` and `delete Class[string]`, or
+    /// `[number]` for numeric keys. `types` is the comma-separated
+    /// list of parameter types as written in the declaration file, with `?` after optional
+    /// and `...` before rest parameters. A key without the parenthesized list applies to a
+    /// function that is not overloaded.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub names: BTreeMap<String, String>,
+    /// The ES module that declarations in ES module files, those with top-level imports or
+    /// exports, are imported from, as in `#[wasm_bindgen(module = "my-package")]`. Without
+    /// it they are looked up in the global scope, like the declarations of script files.
+    /// Every file of the project is imported from the same module, which is usually the
+    /// package that the entry file declares.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+    /// How UMD libraries, whose files declare `export as namespace Name`, are bound.
+    #[serde(skip_serializing_if = "UmdMode::is_module")]
+    pub umd: UmdMode,
+    /// How functions and methods returning `Promise<T>` are bound.
+    #[serde(skip_serializing_if = "PromiseMode::is_raw")]
+    pub promises: PromiseMode,
+    /// How arrays and tuples are bound.
+    #[serde(skip_serializing_if = "ArrayMode::is_array")]
+    pub arrays: ArrayMode,
+    /// How typed arrays such as `Uint8Array`, `ArrayBuffer` and `ArrayBufferView` are bound.
+    #[serde(skip_serializing_if = "TypedArrayMode::is_js_sys")]
+    pub typed_arrays: TypedArrayMode,
+    /// Typed array parameters taken as `&mut [T]` with [`TypedArrayMode::Slice`], keyed as
+    /// for [`unions`](Config::unions): `readInto.buffer` for parameter `buffer` of function
+    /// `readInto`.
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    pub mutable_slices: BTreeSet<String>,
+    /// How records, `Record<string, T>` and object types with just an index signature, are
+    /// bound.
+    #[serde(skip_serializing_if = "RecordMode::is_typed")]
+    pub records: RecordMode,
+    /// Rust types for global TypeScript types that the project doesn't declare, keyed by the
+    /// TypeScript name, such as `Buffer` or `Intl.Collator`. These add to and override the
+    /// built-in mapping of standard library types to `js-sys` and DOM types to `web-sys`.
+    /// A `web_sys::` path enables the `web-sys` feature of that name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub globals: BTreeMap<String, String>,
+    /// How generic interfaces, classes and functions are bound.
+    #[serde(skip_serializing_if = "GenericsMode::is_typed")]
+    pub generics: GenericsMode,
+    /// How unions are lowered where [`unions`](Config::unions) doesn't say otherwise.
+    #[serde(skip_serializing_if = "UnionStrategy::is_auto")]
+    pub union_strategy: UnionStrategy,
+    /// Union strategies for individual declarations.
+    ///
+    /// Keys are type alias names, or the dotted path to where the union is written:
+    /// `foo.mode` for the `mode` parameter of function `foo`, `Foo.bar` for property `bar`
+    /// of `Foo`, `Foo.bar.mode` for a parameter of method `bar`, `Foo.constructor.mode` for a
+    /// constructor parameter and `foo.return` for a return type. A key also applies to all
+    /// unions nested inside the declaration it names.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub unions: BTreeMap<String, UnionStrategy>,
+    /// Rust names for object literal types, keyed as for [`unions`](Config::unions) by where
+    /// the literal is written, to use instead of the name made from that key.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub type_names: BTreeMap<String, String>,
+}
+
+/// How type parameters such as the `T` of `interface Box<T>` are bound.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GenericsMode {
+    /// Generic types get a phantom-typed wrapper, `Box<T: JsCast>`, around their untyped
+    /// binding, with accessors and methods casting to and from `T`. Generic functions get a
+    /// wrapper of the same kind.
+    #[default]
+    Typed,
+    /// Type parameters are bound as `JsValue`.
+    Erased,
+}
+
+impl GenericsMode {
+    fn is_typed(&self) -> bool {
+        *self == GenericsMode::Typed
+    }
+}
+
+/// Where the declarations of a UMD library, which is both an ES module and a global, are
+/// imported from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UmdMode {
+    /// From the library's module, as for any other ES module.
+    #[default]
+    Module,
+    /// From the global that `export as namespace Name` names, as in
+    /// `#[wasm_bindgen(js_namespace = Name)]`, for libraries loaded by a `<script>` tag.
+    Global,
+}
+
+impl UmdMode {
+    fn is_module(&self) -> bool {
+        *self == UmdMode::Module
+    }
+}
+
+/// How functions returning `Promise<T>` are bound.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PromiseMode {
+    /// As returning `js_sys::Promise`.
+    #[default]
+    Raw,
+    /// As `async fn foo(...) -> Result<T, JsValue>`, awaiting the promise with
+    /// `wasm-bindgen-futures` and casting its value to `T`, next to the binding returning
+    /// `js_sys::Promise`, which is renamed to `foo_promise`.
+    Async,
+}
+
+impl PromiseMode {
+    fn is_raw(&self) -> bool {
+        *self == PromiseMode::Raw
+    }
+}
+
+/// How array types such as `string[]`, `ReadonlyArray<Foo>` or `Array<number>`, and tuple
+/// types such as `[string, number]`, are bound.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArrayMode {
+    /// As `js_sys::Array`.
+    #[default]
+    Array,
+    /// Arrays as `Vec<T>` and tuples as Rust tuples, copied across the boundary.
+    Vec,
+    /// Arrays as `JsArray<T>`, a typed view of the JavaScript array, and tuples as Rust
+    /// tuples.
+    Typed,
+}
+
+impl ArrayMode {
+    fn is_array(&self) -> bool {
+        *self == ArrayMode::Array
+    }
+}
+
+/// How typed arrays, `ArrayBuffer` and the types any of them can be passed as, such as
+/// `ArrayBufferView` and `BufferSource`, are bound.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TypedArrayMode {
+    /// As the js-sys types, such as `js_sys::Uint8Array`.
+    #[default]
+    JsSys,
+    /// Copied from slices of their element type, such as `&[f32]` for `Float32Array` and
+    /// `&[u8]` for `ArrayBuffer`, and returned as a `Vec`.
+    Slice,
+}
+
+impl TypedArrayMode {
+    fn is_js_sys(&self) -> bool {
+        *self == TypedArrayMode::JsSys
+    }
+}
+
+/// How records such as `Record<string, number>` or `{ [name: string]: Foo }` are bound.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RecordMode {
+    /// As `JsRecord<T>`, a typed view of the JavaScript object with `get`, `set`, `keys` and
+    /// `entries`.
+    #[default]
+    Typed,
+    /// Copied to and from `HashMap<String, T>`.
+    Map,
+}
+
+impl RecordMode {
+    fn is_typed(&self) -> bool {
+        *self == RecordMode::Typed
+    }
+}
+
+/// How a union type such as `string | number | Foo` is lowered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum UnionStrategy {
+    /// `string-enum` for unions of string literals, `enum` for unions named by a type alias
+    /// and `js-value` for all others.
+    #[default]
+    Auto,
+    /// A `#[wasm_bindgen]` enum with a variant per member, converting to and from `JsValue`.
+    /// Unions that aren't named by a type alias are named after where they are written.
+    Enum,
+    /// A `#[wasm_bindgen]` string enum for unions of string literals, `JsValue` otherwise.
+    StringEnum,
+    /// Plain `JsValue`.
+    JsValue,
+}
+
+impl UnionStrategy {
+    fn is_auto(&self) -> bool {
+        *self == UnionStrategy::Auto
+    }
+}
+
+impl Config {
+    pub fn from_toml(src: &str) -> Result<Config, toml::de::Error> {
+        toml::from_str(src)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Config, Error> {
+        let src = fs::read_to_string(path).map_err(|e| Error::Io(path.to_path_buf(), e))?;
+        Config::from_toml(&src).map_err(|e| Error::Config(path.to_path_buf(), e.to_string()))
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("configs are always serializable")
+    }
+}
@@ -0,0 +1,86 @@
+//! Warnings about declarations that could not be bound faithfully.
+
+use std::fmt;
+
+use dts_parser::{line_col, LineCol, Span};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// The file and position the diagnostic refers to, if it refers to source text.
+    pub location: Option<(String, LineCol)>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        match &self.location {
+            Some((file, pos)) => write!(f, "{}: {}:{}: {}", severity, file, pos, self.message),
+            None => write!(f, "{}: {}", severity, self.message),
+        }
+    }
+}
+
+/// Source text of a declaration file, kept around to locate diagnostics.
+#[derive(Clone, Debug)]
+pub struct SourceFile {
+    pub name: String,
+    pub src: String,
+}
+
+impl SourceFile {
+    pub fn new(name: impl Into<String>, src: impl Into<String>) -> SourceFile {
+        SourceFile {
+            name: name.into(),
+            src: src.into(),
+        }
+    }
+}
+
+/// Collects diagnostics while generating bindings.
+#[derive(Debug, Default)]
+pub struct Diagnostics {
+    pub list: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub fn warn(&mut self, file: &SourceFile, span: Span, message: impl Into<String>) {
+        self.push(Severity::Warning, file, span, message.into());
+    }
+
+    pub fn error(&mut self, file: &SourceFile, span: Span, message: impl Into<String>) {
+        self.push(Severity::Error, file, span, message.into());
+    }
+
+    /// Adds a diagnostic unless the same one has been reported at the same position, as
+    /// types evaluated more than once would report it.
+    fn push(&mut self, severity: Severity, file: &SourceFile, span: Span, message: String) {
+        let diagnostic = Diagnostic {
+            severity,
+            message,
+            location: Some((file.name.clone(), line_col(&file.src, span.start))),
+        };
+        if !self.list.contains(&diagnostic) {
+            self.list.push(diagnostic);
+        }
+    }
+
+    /// A diagnostic that isn't tied to a position in a source file.
+    pub fn warn_global(&mut self, message: impl Into<String>) {
+        self.list.push(Diagnostic {
+            severity: Severity::Warning,
+            message: message.into(),
+            location: None,
+        });
+    }
+}
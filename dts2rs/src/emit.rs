@@ -0,0 +1,1500 @@
+//! Rendering of the binding model as Rust source code.
+
+use std::collections::HashMap;
+
+use crate::ir::{
+    Entries, Enum, ExtensionTrait, ExternItem, Function, FunctionKind, GenericKind, GenericType,
+    IndexOp, Module, RustType, Static, TypeDecl, TypeParam, TypedArray, TypedArrayKind,
+    VariantKind,
+};
+use crate::names;
+
+/// A string buffer that keeps track of indentation.
+struct Writer {
+    out: String,
+    indent: usize,
+}
+
+impl Writer {
+    fn line(&mut self, line: &str) {
+        if !line.is_empty() {
+            for _ in 0..self.indent {
+                self.out.push_str("    ");
+            }
+            self.out.push_str(line);
+        }
+        self.out.push('\n');
+    }
+
+    fn open(&mut self, line: &str) {
+        self.line(line);
+        self.indent += 1;
+    }
+
+    fn close(&mut self, line: &str) {
+        self.indent -= 1;
+        self.line(line);
+    }
+
+    fn doc(&mut self, doc: Option<&str>) {
+        if let Some(doc) = doc {
+            for line in doc.lines() {
+                if line.is_empty() {
+                    self.line("///");
+                } else {
+                    self.line(&format!("/// {}", line));
+                }
+            }
+        }
+    }
+}
+
+/// Renders a whole generated file.
+pub fn emit(module: &Module) -> String {
+    let mut w = Writer {
+        out: String::new(),
+        indent: 0,
+    };
+    w.line("// Generated by dts2rs. Do not edit by hand.");
+    w.line("");
+    w.line("use wasm_bindgen::prelude::*;");
+    let mut generics = Vec::new();
+    collect_generics(module, &mut generics);
+    emit_module_body(&mut w, module, "", &generics);
+    w.out
+}
+
+fn collect_generics(module: &Module, out: &mut Vec<GenericType>) {
+    out.extend(module.generics.iter().cloned());
+    for child in &module.modules {
+        collect_generics(child, out);
+    }
+}
+
+/// Renders the items of `module`, whose path is `prefix`, and its submodules. `generics` are
+/// the typed wrappers of the whole file, which may be used from any module.
+fn emit_module_body(w: &mut Writer, module: &Module, prefix: &str, all_generics: &[GenericType]) {
+    // Paths are relative to the root module; items of this module are named directly.
+    for r in &module.reexports {
+        let path = relative_path(&r.path, prefix);
+        match &r.alias {
+            Some(alias) => w.line(&format!("pub use {} as {};", path, alias)),
+            None => w.line(&format!("pub use {};", path)),
+        }
+    }
+    // Submodules are localized when they are rendered.
+    let module = &module.map_paths(&|p| localize(p, prefix));
+    let generics: Vec<GenericType> = all_generics
+        .iter()
+        .map(|g| g.map_paths(&|p| localize(p, prefix)))
+        .collect();
+    // Members of types whose JavaScript name differs from the Rust one need `js_class`.
+    let js_classes: HashMap<&str, &str> = module
+        .externs
+        .iter()
+        .filter_map(|item| match item {
+            ExternItem::Type(t) if t.js_name != t.rust_name => {
+                Some((t.rust_name.as_str(), t.js_name.as_str()))
+            }
+            _ => None,
+        })
+        .collect();
+    if !module.externs.is_empty() {
+        let mut attrs = Vec::new();
+        if let Some(js_module) = &module.js_module {
+            attrs.push(format!("module = {:?}", js_module));
+        }
+        match module.js_namespace.as_slice() {
+            [] => {}
+            [name] if names::is_rust_ident(name) => attrs.push(format!("js_namespace = {}", name)),
+            path => attrs.push(format!("js_namespace = {:?}", path)),
+        }
+        w.line("");
+        w.line(&attrs_line(&attrs).unwrap_or_else(|| "#[wasm_bindgen]".to_string()));
+        w.open("extern \"C\" {");
+        for (i, item) in module.externs.iter().enumerate() {
+            if i > 0 {
+                w.line("");
+            }
+            match item {
+                ExternItem::Type(t) => emit_type(w, t),
+                ExternItem::Function(f) => emit_function(w, f, &js_classes, &generics),
+                ExternItem::Static(s) => emit_static(w, s, &generics),
+            }
+        }
+        w.close("}");
+    }
+    for e in &module.enums {
+        w.line("");
+        emit_enum(w, e);
+    }
+
+    // Functions with generic signatures and calls of function objects are called through
+    // wrappers, and functions returning promises through async ones: as methods of the typed wrapper of their type,
+    // in an inherent impl of a non-generic type, or as free functions.
+    let wrapped: Vec<&Function> = module
+        .externs
+        .iter()
+        .filter_map(|item| match item {
+            ExternItem::Function(f) if is_wrapped(f, &generics) || f.async_name.is_some() => {
+                Some(f)
+            }
+            _ => None,
+        })
+        .collect();
+    for g in &module.generics {
+        w.line("");
+        let members: Vec<&Function> = wrapped
+            .iter()
+            .copied()
+            .filter(|f| f.owner() == Some(&g.erased))
+            .collect();
+        emit_generic_type(w, g, &members, &generics);
+    }
+    let mut owners: Vec<&str> = Vec::new();
+    for f in &wrapped {
+        if let Some(owner) = f.owner() {
+            if !module.generics.iter().any(|g| g.erased == owner) && !owners.contains(&owner) {
+                owners.push(owner);
+            }
+        }
+    }
+    for owner in owners {
+        w.line("");
+        w.open(&format!("impl {} {{", owner));
+        let members = wrapped.iter().filter(|f| f.owner() == Some(owner));
+        for (i, f) in members.enumerate() {
+            if i > 0 {
+                w.line("");
+            }
+            emit_wrappers(w, f, &generics);
+        }
+        w.close("}");
+    }
+    for f in wrapped.iter().filter(|f| f.owner().is_none()) {
+        w.line("");
+        emit_wrappers(w, f, &generics);
+    }
+
+    for e in &module.entries {
+        emit_entries(w, e, module, &generics);
+    }
+
+    for t in &module.traits {
+        w.line("");
+        let members: Vec<&Function> = module
+            .externs
+            .iter()
+            .filter_map(|item| match item {
+                ExternItem::Function(f) if f.has_receiver() && f.owner() == Some(&t.binding) => {
+                    Some(f)
+                }
+                _ => None,
+            })
+            .collect();
+        emit_extension_trait(w, t, &members);
+    }
+
+    for child in &module.modules {
+        w.line("");
+        w.doc(child.doc.as_deref());
+        // Namespaces declaring only types that aren't bound, such as aliases.
+        if is_empty(child) {
+            w.line(&format!("pub mod {} {{}}", child.name));
+            continue;
+        }
+        w.open(&format!("pub mod {} {{", child.name));
+        if uses_parent(child) {
+            w.line("use super::*;");
+        }
+        emit_module_body(
+            w,
+            child,
+            &format!("{}{}::", prefix, child.name),
+            all_generics,
+        );
+        w.close("}");
+    }
+}
+
+/// Whether `module` or one of its submodules has items, which may name those of the parent
+/// module. Re-exports name them by path.
+fn uses_parent(module: &Module) -> bool {
+    !module.externs.is_empty()
+        || !module.enums.is_empty()
+        || !module.generics.is_empty()
+        || !module.traits.is_empty()
+        || !module.entries.is_empty()
+        || module.modules.iter().any(uses_parent)
+}
+
+/// Whether `module` and its submodules have neither items nor re-exports.
+fn is_empty(module: &Module) -> bool {
+    module.reexports.is_empty() && !uses_parent(module) && module.modules.iter().all(is_empty)
+}
+
+/// Renders `#[wasm_bindgen(...)]` if there is anything to put in it.
+fn attrs_line(attrs: &[String]) -> Option<String> {
+    if attrs.is_empty() {
+        None
+    } else {
+        Some(format!("#[wasm_bindgen({})]", attrs.join(", ")))
+    }
+}
+
+fn emit_type(w: &mut Writer, t: &TypeDecl) {
+    w.doc(t.doc.as_deref());
+    let mut attrs: Vec<String> = t
+        .extends
+        .iter()
+        .map(|e| format!("extends = {}", e))
+        .collect();
+    if t.js_name != t.rust_name {
+        attrs.push(format!("js_name = {}", names::js_name_attr(&t.js_name)));
+    }
+    if let Some(is_type_of) = &t.is_type_of {
+        attrs.push(format!("is_type_of = {}", is_type_of));
+    }
+    if let Some(line) = attrs_line(&attrs) {
+        w.line(&line);
+    }
+    // A type is only `Eq` if its ancestors are.
+    if t.extends.iter().any(|e| NOT_EQ.contains(&e.as_str())) {
+        w.line("#[derive(Debug, Clone, PartialEq)]");
+    } else {
+        w.line("#[derive(Debug, Clone, PartialEq, Eq)]");
+    }
+    w.line(&format!("pub type {};", t.rust_name));
+}
+
+/// The `js-sys` types whose type parameters default to `JsValue`, which isn't `Eq`.
+const NOT_EQ: &[&str] = &[
+    "js_sys::Array",
+    "js_sys::ArrayTuple",
+    "js_sys::AsyncGenerator",
+    "js_sys::AsyncIterator",
+    "js_sys::Generator",
+    "js_sys::Iterator",
+    "js_sys::IteratorNext",
+    "js_sys::Map",
+    "js_sys::Promise",
+    "js_sys::PromiseState",
+    "js_sys::PropertyDescriptor",
+    "js_sys::Set",
+    "js_sys::WeakMap",
+];
+
+fn emit_function(
+    w: &mut Writer,
+    f: &Function,
+    js_classes: &HashMap<&str, &str>,
+    generics: &[GenericType],
+) {
+    w.doc(f.doc.as_deref());
+    let mut attrs = Vec::new();
+    let mut params = Vec::new();
+    let js_name = names::js_name_attr(&f.js_name);
+    let (vis, rust_name) = if is_private(f, generics) {
+        ("", private_name(f))
+    } else {
+        ("pub ", f.rust_name.clone())
+    };
+    // wasm-bindgen infers accessor names from `foo` and `set_foo`, spell out anything else.
+    let getter = || {
+        if f.js_name == rust_name {
+            "getter".to_string()
+        } else {
+            format!("getter = {}", js_name)
+        }
+    };
+    let setter = || {
+        if format!("set_{}", f.js_name) == rust_name {
+            "setter".to_string()
+        } else {
+            format!("setter = {}", js_name)
+        }
+    };
+    let renamed = f.js_name != rust_name;
+    let class = match &f.kind {
+        FunctionKind::Free => {
+            if renamed {
+                attrs.push(format!("js_name = {}", js_name));
+            }
+            None
+        }
+        FunctionKind::Method { this, structural }
+        | FunctionKind::Getter { this, structural }
+        | FunctionKind::Setter { this, structural } => {
+            attrs.push("method".to_string());
+            if *structural {
+                attrs.push("structural".to_string());
+            }
+            match &f.kind {
+                FunctionKind::Getter { .. } => attrs.push(getter()),
+                FunctionKind::Setter { .. } => attrs.push(setter()),
+                _ if renamed => attrs.push(format!("js_name = {}", js_name)),
+                _ => {}
+            }
+            params.push(format!("this: &{}", this));
+            Some(this)
+        }
+        FunctionKind::Index { this, op } => {
+            attrs.push("method".to_string());
+            attrs.push("structural".to_string());
+            attrs.push(
+                match op {
+                    IndexOp::Get => "indexing_getter",
+                    IndexOp::Set => "indexing_setter",
+                    IndexOp::Delete => "indexing_deleter",
+                }
+                .to_string(),
+            );
+            params.push(format!("this: &{}", this));
+            Some(this)
+        }
+        FunctionKind::Call { this } => {
+            attrs.push("method".to_string());
+            attrs.push("structural".to_string());
+            attrs.push("js_name = call".to_string());
+            params.push(format!("this: &{}", this));
+            params.push("this_arg: &JsValue".to_string());
+            Some(this)
+        }
+        FunctionKind::Constructor { class } => {
+            attrs.push("constructor".to_string());
+            Some(class)
+        }
+        FunctionKind::StaticMethod { class }
+        | FunctionKind::StaticGetter { class }
+        | FunctionKind::StaticSetter { class } => {
+            attrs.push(format!("static_method_of = {}", class));
+            match &f.kind {
+                FunctionKind::StaticGetter { .. } => attrs.push(getter()),
+                FunctionKind::StaticSetter { .. } => attrs.push(setter()),
+                _ if renamed => attrs.push(format!("js_name = {}", js_name)),
+                _ => {}
+            }
+            Some(class)
+        }
+    };
+    if let Some(js_class) = class.and_then(|c| js_classes.get(c.as_str())) {
+        attrs.push(format!("js_class = {:?}", js_class));
+    }
+    if f.variadic {
+        attrs.push("variadic".to_string());
+    }
+    if let Some(line) = attrs_line(&attrs) {
+        w.line(&line);
+    }
+    params.extend(
+        f.params
+            .iter()
+            .map(|p| format!("{}: {}", p.name, p.ty.erased(generics).param())),
+    );
+    let ret = match &f.ret {
+        Some(ty) => format!(" -> {}", ty.erased(generics).owned()),
+        None => String::new(),
+    };
+    w.line(&format!(
+        "{}fn {}({}){};",
+        vis,
+        rust_name,
+        params.join(", "),
+        ret
+    ));
+}
+
+/// Whether the binding of a function is hidden behind its wrapper: the untyped binding of a
+/// generic function, and the call of a function object. Members of generic types stay public
+/// on the untyped binding of the type.
+fn is_private(f: &Function, generics: &[GenericType]) -> bool {
+    is_wrapped(f, generics)
+        && !f
+            .owner()
+            .is_some_and(|owner| generics.iter().any(|g| g.erased == owner))
+}
+
+/// Whether the function is called through a wrapper of the same name.
+fn is_wrapped(f: &Function, generics: &[GenericType]) -> bool {
+    f.is_generic(generics) || matches!(f.kind, FunctionKind::Call { .. })
+}
+
+fn private_name(f: &Function) -> String {
+    format!("__{}", f.rust_name)
+}
+
+fn emit_static(w: &mut Writer, s: &Static, generics: &[GenericType]) {
+    w.doc(s.doc.as_deref());
+    let mut attrs = vec!["thread_local_v2".to_string()];
+    if s.js_name != s.rust_name {
+        attrs.push(format!("js_name = {}", names::js_name_attr(&s.js_name)));
+    }
+    w.line(&attrs_line(&attrs).unwrap());
+    w.line(&format!(
+        "pub static {}: {};",
+        s.rust_name,
+        s.ty.erased(generics).owned()
+    ));
+}
+
+/// Renders an enum with its conversions. wasm-bindgen provides `From<Enum> for JsValue`;
+/// conversion from `JsValue` and from the types of the variants is added here.
+fn emit_enum(w: &mut Writer, e: &Enum) {
+    let name = &e.rust_name;
+    w.doc(e.doc.as_deref());
+    w.line("#[wasm_bindgen]");
+    if e.is_string_enum() || e.is_int_enum() {
+        w.line("#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]");
+    } else {
+        w.line("#[derive(Debug, Clone, PartialEq)]");
+    }
+    w.open(&format!("pub enum {} {{", name));
+    for v in &e.variants {
+        w.doc(v.doc.as_deref());
+        match &v.kind {
+            VariantKind::Int(value) => w.line(&format!("{} = {},", v.name, value)),
+            VariantKind::Str(value) => w.line(&format!("{} = {:?},", v.name, value)),
+            VariantKind::Value(ty) => w.line(&format!("{}({}),", v.name, ty.owned())),
+        }
+    }
+    w.close("}");
+
+    if !e.aliases.is_empty() {
+        w.line("");
+        w.open(&format!("impl {} {{", name));
+        for (i, alias) in e.aliases.iter().enumerate() {
+            if i > 0 && alias.doc.is_some() {
+                w.line("");
+            }
+            w.doc(alias.doc.as_deref());
+            w.line(&format!(
+                "pub const {}: {} = {}::{};",
+                alias.name, name, name, alias.variant
+            ));
+        }
+        w.close("}");
+    }
+
+    w.line("");
+    w.open(&format!("impl TryFrom<JsValue> for {} {{", name));
+    w.line("type Error = JsValue;");
+    w.line("");
+    w.open(&format!(
+        "fn try_from(value: JsValue) -> Result<{}, JsValue> {{",
+        name
+    ));
+    if e.is_string_enum() {
+        w.line(&format!("{}::from_js_value(&value).ok_or(value)", name));
+    } else {
+        w.line(&format!(
+            "<{} as wasm_bindgen::convert::TryFromJsValue>::try_from_js_value(value)",
+            name
+        ));
+    }
+    w.close("}");
+    w.close("}");
+
+    for v in &e.variants {
+        // `From<JsValue>` would conflict with the `TryFrom<JsValue>` above.
+        let VariantKind::Value(ty) = &v.kind else {
+            continue;
+        };
+        if *ty == RustType::JsValue {
+            continue;
+        }
+        w.line("");
+        w.open(&format!("impl From<{}> for {} {{", ty.owned(), name));
+        w.open(&format!("fn from(value: {}) -> {} {{", ty.owned(), name));
+        w.line(&format!("{}::{}(value)", name, v.name));
+        w.close("}");
+        w.close("}");
+    }
+}
+
+/// Renders `<T: JsCast, U: JsCast>`, with defaults if `defaults` is set.
+fn type_params(params: &[TypeParam], defaults: bool) -> String {
+    bounded_type_params(params, defaults, "JsCast")
+}
+
+/// Renders type parameters `params` bounded by `bound`, with defaults if `defaults` is set.
+/// Only `JsCast` parameters default to `JsValue`.
+fn bounded_type_params(params: &[TypeParam], defaults: bool, bound: &str) -> String {
+    if params.is_empty() {
+        return String::new();
+    }
+    let params: Vec<String> = params
+        .iter()
+        .map(|p| match &p.default {
+            _ if !defaults => format!("{}: {}", p.name, bound),
+            Some(default) => format!("{}: {} = {}", p.name, bound, default.owned()),
+            None if bound == "JsCast" => format!("{}: JsCast = JsValue", p.name),
+            None => format!("{}: {}", p.name, bound),
+        })
+        .collect();
+    format!("<{}>", params.join(", "))
+}
+
+/// Renders a generic type's phantom-typed wrapper, with the conversions and trait
+/// implementations that make it usable like an imported type.
+fn emit_generic_type(
+    w: &mut Writer,
+    g: &GenericType,
+    members: &[&Function],
+    generics: &[GenericType],
+) {
+    let name = &g.rust_name;
+    let erased = &g.erased;
+    let bound = match g.kind {
+        GenericKind::PrimitiveRecord => JS_PRIMITIVE,
+        _ => "JsCast",
+    };
+    let decl = bounded_type_params(&g.type_params, true, bound);
+    let bounds = bounded_type_params(&g.type_params, false, bound);
+    let args: Vec<&str> = g.type_params.iter().map(|p| p.name.as_str()).collect();
+    let this = format!("{}<{}>", name, args.join(", "));
+    let impl_for = |trait_name: &str| format!("impl{} {} for {} {{", bounds, trait_name, this);
+
+    if g.kind == GenericKind::PrimitiveRecord {
+        emit_js_primitive(w);
+        w.line("");
+    }
+    w.doc(g.doc.as_deref());
+    w.line("#[repr(transparent)]");
+    w.open(&format!("pub struct {}{} {{", name, decl));
+    w.line(&format!("erased: {},", erased));
+    w.line(&format!(
+        "ty: std::marker::PhantomData<({},)>,",
+        args.join(", ")
+    ));
+    w.close("}");
+
+    w.line("");
+    w.open(&format!("impl{} {} {{", bounds, this));
+    w.line(&format!(
+        "/// Views an untyped `{}` as a typed one, without checking.",
+        name
+    ));
+    w.open(&format!(
+        "pub fn from_erased(erased: {}) -> {} {{",
+        erased, this
+    ));
+    w.open(&format!("{} {{", name));
+    w.line("erased,");
+    w.line("ty: std::marker::PhantomData,");
+    w.close("}");
+    w.close("}");
+    w.line("");
+    w.open(&format!("pub fn erased(&self) -> &{} {{", erased));
+    w.line("&self.erased");
+    w.close("}");
+    w.line("");
+    w.open(&format!("pub fn into_erased(self) -> {} {{", erased));
+    w.line("self.erased");
+    w.close("}");
+    match g.kind {
+        GenericKind::Declared => {}
+        GenericKind::Array => emit_array_accessors(w, &this),
+        GenericKind::Record => emit_record_accessors(w, &this),
+        GenericKind::PrimitiveRecord => emit_primitive_record_accessors(w, &this),
+    }
+    let (statics, members): (Vec<&Function>, Vec<&Function>) = members.iter().partition(|f| {
+        matches!(
+            f.kind,
+            FunctionKind::StaticMethod { .. }
+                | FunctionKind::StaticGetter { .. }
+                | FunctionKind::StaticSetter { .. }
+        )
+    });
+    for f in &members {
+        w.line("");
+        emit_wrappers(w, f, generics);
+    }
+    w.close("}");
+
+    // Static members can't use the type's parameters, so they go on its default
+    // instantiation, where calls don't need to name one.
+    if !statics.is_empty() {
+        w.line("");
+        w.open(&format!("impl {} {{", name));
+        for (i, f) in statics.into_iter().enumerate() {
+            if i > 0 {
+                w.line("");
+            }
+            emit_wrappers(w, f, generics);
+        }
+        w.close("}");
+    }
+
+    if g.kind != GenericKind::Declared {
+        w.line("");
+        w.open(&impl_for("Default"));
+        w.open(&format!("fn default() -> {} {{", this));
+        w.line(&format!("{}::new()", name));
+        w.close("}");
+        w.close("}");
+    }
+
+    w.line("");
+    w.open(&impl_for("std::ops::Deref"));
+    w.line(&format!("type Target = {};", erased));
+    w.line("");
+    w.open(&format!("fn deref(&self) -> &{} {{", erased));
+    w.line("&self.erased");
+    w.close("}");
+    w.close("}");
+
+    w.line("");
+    w.open(&impl_for("Clone"));
+    w.open(&format!("fn clone(&self) -> {} {{", this));
+    w.line(&format!("{}::from_erased(self.erased.clone())", name));
+    w.close("}");
+    w.close("}");
+
+    w.line("");
+    w.open(&impl_for("std::fmt::Debug"));
+    w.open("fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {");
+    w.line("self.erased.fmt(f)");
+    w.close("}");
+    w.close("}");
+
+    w.line("");
+    w.open(&impl_for("PartialEq"));
+    w.open(&format!("fn eq(&self, other: &{}) -> bool {{", this));
+    w.line("self.erased == other.erased");
+    w.close("}");
+    w.close("}");
+
+    w.line("");
+    w.line(&format!("impl{} Eq for {} {{}}", bounds, this));
+
+    w.line("");
+    w.open(&impl_for("AsRef<JsValue>"));
+    w.open("fn as_ref(&self) -> &JsValue {");
+    w.line("self.erased.as_ref()");
+    w.close("}");
+    w.close("}");
+
+    w.line("");
+    w.open(&format!("impl{} From<{}> for JsValue {{", bounds, this));
+    w.open(&format!("fn from(value: {}) -> JsValue {{", this));
+    w.line("value.erased.into()");
+    w.close("}");
+    w.close("}");
+
+    w.line("");
+    w.open(&impl_for("JsCast"));
+    w.open("fn instanceof(val: &JsValue) -> bool {");
+    // The js-sys types of views have type parameters, whose defaults only apply to types.
+    let erased_ty = match g.kind {
+        GenericKind::Declared => erased.clone(),
+        _ => format!("<{}>", erased),
+    };
+    w.line(&format!("{}::instanceof(val)", erased_ty));
+    w.close("}");
+    w.line("");
+    w.open("fn is_type_of(val: &JsValue) -> bool {");
+    w.line(&format!("{}::is_type_of(val)", erased_ty));
+    w.close("}");
+    w.line("");
+    w.open(&format!(
+        "fn unchecked_from_js(val: JsValue) -> {} {{",
+        this
+    ));
+    w.line(&format!(
+        "{}::from_erased({}::unchecked_from_js(val))",
+        name, erased
+    ));
+    w.close("}");
+    w.line("");
+    w.open(&format!(
+        "fn unchecked_from_js_ref(val: &JsValue) -> &{} {{",
+        this
+    ));
+    w.line(&format!(
+        "// SAFETY: `{}` is a transparent wrapper around `{}`.",
+        name, erased
+    ));
+    w.line(&format!(
+        "unsafe {{ &*({}::unchecked_from_js_ref(val) as *const {} as *const {}) }}",
+        erased, erased, this
+    ));
+    w.close("}");
+    w.close("}");
+}
+
+/// Renders the typed accessors of `JsArray<T>`.
+fn emit_array_accessors(w: &mut Writer, this: &str) {
+    w.line("");
+    w.line("/// An empty array.");
+    w.open(&format!("pub fn new() -> {} {{", this));
+    w.line("Self::from_erased(js_sys::Array::new())");
+    w.close("}");
+    w.line("");
+    w.open("pub fn len(&self) -> u32 {");
+    w.line("self.erased.length()");
+    w.close("}");
+    w.line("");
+    w.open("pub fn is_empty(&self) -> bool {");
+    w.line("self.erased.length() == 0");
+    w.close("}");
+    w.line("");
+    w.line("/// The element at `index`, or `None` past the end.");
+    w.open("pub fn get(&self, index: u32) -> Option<T> {");
+    w.line("(index < self.len()).then(|| self.erased.get(index).unchecked_into())");
+    w.close("}");
+    w.line("");
+    w.open("pub fn set(&self, index: u32, value: &T) {");
+    w.line("self.erased.set(index, value.as_ref().clone());");
+    w.close("}");
+    w.line("");
+    w.line("/// Appends `value`, returning the new length.");
+    w.open("pub fn push(&self, value: &T) -> u32 {");
+    w.line("self.erased.push(value.as_ref())");
+    w.close("}");
+    w.line("");
+    w.open("pub fn iter(&self) -> impl Iterator<Item = T> + '_ {");
+    w.line("self.erased.iter().map(JsCast::unchecked_into)");
+    w.close("}");
+    w.line("");
+    w.open("pub fn to_vec(&self) -> Vec<T> {");
+    w.line("self.iter().collect()");
+    w.close("}");
+}
+
+/// Renders the typed accessors of `JsRecord<T>`.
+fn emit_record_accessors(w: &mut Writer, this: &str) {
+    w.line("");
+    w.line("/// An empty record.");
+    w.open(&format!("pub fn new() -> {} {{", this));
+    w.line("Self::from_erased(js_sys::Object::new())");
+    w.close("}");
+    w.line("");
+    w.line("/// The value of `key`, or `None` if it is undefined.");
+    w.open("pub fn get(&self, key: &str) -> Option<T> {");
+    w.line("Some(js_sys::Reflect::get(&self.erased, &JsValue::from_str(key)).unwrap_throw()).filter(|v| !v.is_undefined()).map(JsCast::unchecked_into)");
+    w.close("}");
+    w.line("");
+    w.open("pub fn set(&self, key: &str, value: &T) {");
+    w.line("js_sys::Reflect::set(&self.erased, &JsValue::from_str(key), value.as_ref()).unwrap_throw();");
+    w.close("}");
+    w.line("");
+    w.open("pub fn delete(&self, key: &str) {");
+    w.line(
+        "js_sys::Reflect::delete_property(&self.erased, &JsValue::from_str(key)).unwrap_throw();",
+    );
+    w.close("}");
+    w.line("");
+    w.open("pub fn keys(&self) -> Vec<String> {");
+    w.line("js_sys::Object::keys(&self.erased).iter().map(|key| key.as_string().unwrap_or_default()).collect()");
+    w.close("}");
+    w.line("");
+    w.open("pub fn entries(&self) -> Vec<(String, T)> {");
+    w.line("js_sys::Object::entries(&self.erased).iter().map(|entry| { let entry = entry.unchecked_into::<js_sys::Array>(); (entry.get(0).as_string().unwrap_or_default(), entry.get(1).unchecked_into()) }).collect()");
+    w.close("}");
+    w.line("");
+    w.open("pub fn to_map(&self) -> std::collections::HashMap<String, T> {");
+    w.line("self.entries().into_iter().collect()");
+    w.close("}");
+}
+
+/// The trait of the values that `JsPrimitiveRecord<T>` holds.
+const JS_PRIMITIVE: &str = "JsPrimitive";
+
+/// Renders `JsPrimitive`, which converts numbers, booleans and strings to and from `JsValue`.
+fn emit_js_primitive(w: &mut Writer) {
+    w.line("/// A number, boolean or string, converted to and from `JsValue`.");
+    w.open(&format!("pub trait {}: Sized {{", JS_PRIMITIVE));
+    w.line("/// The value, or `None` if it isn't of this type.");
+    w.line("fn from_js(value: &JsValue) -> Option<Self>;");
+    w.line("");
+    w.line("fn to_js(&self) -> JsValue;");
+    w.close("}");
+    let impls = [
+        ("f64", "value.as_f64()", "JsValue::from_f64(*self)"),
+        ("bool", "value.as_bool()", "JsValue::from_bool(*self)"),
+        ("String", "value.as_string()", "JsValue::from_str(self)"),
+    ];
+    for (ty, from_js, to_js) in impls {
+        w.line("");
+        w.open(&format!("impl {} for {} {{", JS_PRIMITIVE, ty));
+        w.open("fn from_js(value: &JsValue) -> Option<Self> {");
+        w.line(from_js);
+        w.close("}");
+        w.line("");
+        w.open("fn to_js(&self) -> JsValue {");
+        w.line(to_js);
+        w.close("}");
+        w.close("}");
+    }
+}
+
+/// Renders the accessors of `JsPrimitiveRecord<T>`, which skip values that aren't a `T`.
+fn emit_primitive_record_accessors(w: &mut Writer, this: &str) {
+    w.line("");
+    w.line("/// An empty record.");
+    w.open(&format!("pub fn new() -> {} {{", this));
+    w.line("Self::from_erased(js_sys::Object::new())");
+    w.close("}");
+    w.line("");
+    w.line("/// The value of `key`, or `None` if it is undefined or not a `T`.");
+    w.open("pub fn get(&self, key: &str) -> Option<T> {");
+    w.line(
+        "T::from_js(&js_sys::Reflect::get(&self.erased, &JsValue::from_str(key)).unwrap_throw())",
+    );
+    w.close("}");
+    w.line("");
+    w.open("pub fn set(&self, key: &str, value: &T) {");
+    w.line("js_sys::Reflect::set(&self.erased, &JsValue::from_str(key), &value.to_js()).unwrap_throw();");
+    w.close("}");
+    w.line("");
+    w.open("pub fn delete(&self, key: &str) {");
+    w.line(
+        "js_sys::Reflect::delete_property(&self.erased, &JsValue::from_str(key)).unwrap_throw();",
+    );
+    w.close("}");
+    w.line("");
+    w.open("pub fn keys(&self) -> Vec<String> {");
+    w.line("js_sys::Object::keys(&self.erased).iter().map(|key| key.as_string().unwrap_or_default()).collect()");
+    w.close("}");
+    w.line("");
+    w.line("/// The entries whose values are a `T`.");
+    w.open("pub fn entries(&self) -> Vec<(String, T)> {");
+    w.line("js_sys::Object::entries(&self.erased).iter().filter_map(|entry| { let entry = entry.unchecked_into::<js_sys::Array>(); Some((entry.get(0).as_string().unwrap_or_default(), T::from_js(&entry.get(1))?)) }).collect()");
+    w.close("}");
+    w.line("");
+    w.open("pub fn to_map(&self) -> std::collections::HashMap<String, T> {");
+    w.line("self.entries().into_iter().collect()");
+    w.close("}");
+}
+
+/// Renders `keys`, `entries` and `to_map` of a type with a string index signature, leaving
+/// out those that its members' names take.
+fn emit_entries(w: &mut Writer, e: &Entries, module: &Module, generics: &[GenericType]) {
+    let taken: Vec<&str> = module
+        .externs
+        .iter()
+        .filter_map(|item| match item {
+            ExternItem::Function(f) if f.owner() == Some(&e.this) => Some(f),
+            _ => None,
+        })
+        .flat_map(|f| [Some(f.rust_name.as_str()), f.async_name.as_deref()])
+        .flatten()
+        .collect();
+    let object = "self.unchecked_ref::<js_sys::Object>()";
+    let entry = RustType::Tuple(vec![RustType::String, e.value.clone()], None);
+    let entries = format!(
+        "js_sys::Object::entries({}).iter().map(|v| {}).collect()",
+        object,
+        from_js("v", &entry)
+    );
+    let accessors = [
+        (
+            "keys",
+            "/// The keys of the entries, in the order that `Object.keys` lists them.",
+            "Vec<String>".to_string(),
+            format!(
+                "js_sys::Object::keys({}).iter().map(|v| v.as_string().unwrap_or_default()).collect()",
+                object
+            ),
+        ),
+        (
+            "entries",
+            "/// The entries, in the order that `Object.entries` lists them.",
+            format!("Vec<{}>", entry.owned()),
+            entries.clone(),
+        ),
+        (
+            "to_map",
+            "/// Copies the entries to a `HashMap`.",
+            format!("std::collections::HashMap<String, {}>", e.value.owned()),
+            entries,
+        ),
+    ];
+    let accessors: Vec<_> = accessors
+        .iter()
+        .filter(|(name, ..)| !taken.contains(name))
+        .collect();
+    if accessors.is_empty() {
+        return;
+    }
+    w.line("");
+    match generics.iter().find(|g| g.erased == e.this) {
+        Some(g) => {
+            let args: Vec<&str> = g.type_params.iter().map(|p| p.name.as_str()).collect();
+            w.open(&format!(
+                "impl{} {}<{}> {{",
+                type_params(&g.type_params, false),
+                g.rust_name,
+                args.join(", ")
+            ));
+        }
+        None => w.open(&format!("impl {} {{", e.this)),
+    }
+    for (i, (name, doc, ret, body)) in accessors.into_iter().enumerate() {
+        if i > 0 {
+            w.line("");
+        }
+        w.line(doc);
+        if *name != "keys" && e.value.is_defaulted() {
+            w.line("///");
+            w.line(&format!("/// {}", DEFAULTS_NOTE));
+        }
+        w.open(&format!("pub fn {}(&self) -> {} {{", name, ret));
+        w.line(body);
+        w.close("}");
+    }
+    w.close("}");
+}
+
+/// Renders the wrappers of a function: the typed wrapper of a function bound untyped, and the
+/// async wrapper of one returning a promise.
+fn emit_wrappers(w: &mut Writer, f: &Function, generics: &[GenericType]) {
+    let generic_owner = f
+        .owner()
+        .and_then(|owner| generics.iter().find(|g| g.erased == owner));
+    if let Some(g) = generic_owner.filter(|_| f.has_receiver()) {
+        if let Some(f) = unshadowed(f, &g.type_params) {
+            return emit_wrappers(w, &f, generics);
+        }
+    }
+    let typed = is_wrapped(f, generics);
+    if typed {
+        emit_wrapper(w, f, generics);
+    }
+    if let Some(name) = &f.async_name {
+        if typed {
+            w.line("");
+        }
+        emit_async_wrapper(w, f, name, generic_owner.filter(|_| !typed));
+    }
+}
+
+/// Renders the typed wrapper of a function bound untyped, converting arguments to and the
+/// result from the untyped binding's types. Calls of function objects also pass `undefined`
+/// as the `this` of the call.
+fn emit_wrapper(w: &mut Writer, f: &Function, generics: &[GenericType]) {
+    let generic_owner = f
+        .owner()
+        .and_then(|owner| generics.iter().find(|g| g.erased == owner));
+    let callee = match (generic_owner, f.has_receiver()) {
+        (Some(_), true) => format!("self.erased.{}", f.rust_name),
+        (Some(g), false) => format!("{}::{}", g.erased, f.rust_name),
+        (None, true) => format!("self.{}", private_name(f)),
+        (None, false) if f.owner().is_some() => format!("Self::{}", private_name(f)),
+        (None, false) => private_name(f),
+    };
+    // Callbacks are closures that the caller keeps alive, or for the `_once` variant, closures
+    // that JavaScript frees after calling them.
+    let body = |once: bool| {
+        let this_arg =
+            matches!(f.kind, FunctionKind::Call { .. }).then(|| "&JsValue::UNDEFINED".to_string());
+        let args: Vec<String> = this_arg
+            .into_iter()
+            .chain(f.params.iter().map(|p| match &p.ty {
+                RustType::Closure(..) if once => {
+                    format!("&Closure::once_into_js({}).unchecked_into()", p.name)
+                }
+                ty => wrapper_arg(&p.name, ty, generics),
+            }))
+            .collect();
+        let call = format!("{}({})", callee, args.join(", "));
+        match &f.ret {
+            Some(ty) => wrapper_result(call, ty, generics),
+            None => call,
+        }
+    };
+    w.doc(noting_defaults(f.doc.as_deref(), f.ret.as_ref()).as_deref());
+    w.open(&format!("pub {} {{", typed_signature(f)));
+    w.line(&body(false));
+    w.close("}");
+    if f.params
+        .iter()
+        .any(|p| matches!(p.ty, RustType::Closure(..)))
+    {
+        let link = match f.owner() {
+            Some(_) => format!("[`{0}`](Self::{0})", f.rust_name),
+            None => format!("[`{}`]", f.rust_name),
+        };
+        w.line("");
+        w.doc(Some(&format!(
+            "{} with callbacks that are called at most once, which are freed after the call.",
+            link
+        )));
+        w.open(&format!("pub {} {{", once_signature(f)));
+        w.line(&body(true));
+        w.close("}");
+    }
+}
+
+/// Renders `async fn name`, which awaits the promise that `f` returns and casts its value.
+/// `erased_owner` is the generic type whose untyped binding has `f`, when its typed wrapper
+/// doesn't.
+fn emit_async_wrapper(
+    w: &mut Writer,
+    f: &Function,
+    name: &str,
+    erased_owner: Option<&GenericType>,
+) {
+    let resolves = f.resolves.clone().unwrap_or(RustType::JsValue);
+    let callee = match (f.has_receiver(), f.owner()) {
+        (true, _) => format!("self.{}", f.rust_name),
+        (false, Some(_)) => match erased_owner {
+            Some(g) => format!("{}::{}", g.erased, f.rust_name),
+            None => format!("Self::{}", f.rust_name),
+        },
+        (false, None) => f.rust_name.clone(),
+    };
+    let args: Vec<&str> = f.params.iter().map(|p| p.name.as_str()).collect();
+    let future = format!(
+        "wasm_bindgen_futures::JsFuture::from({}({})).await?",
+        callee,
+        args.join(", ")
+    );
+    let wrapper = Function {
+        rust_name: name.to_string(),
+        ret: Some(RustType::Path(
+            "Result".to_string(),
+            vec![resolves.clone(), RustType::JsValue],
+        )),
+        ..f.clone()
+    };
+    w.doc(noting_defaults(f.doc.as_deref(), Some(&resolves)).as_deref());
+    w.open(&format!("pub async {} {{", typed_signature(&wrapper)));
+    if resolves == RustType::Unit {
+        w.line(&format!("{};", future));
+        w.line("Ok(())");
+    } else {
+        w.line(&format!("let value = {};", future));
+        w.line(&resolved_value(&resolves));
+    }
+    w.close("}");
+}
+
+/// Casts `value`, the value a promise resolved to, to `ty`, as a `Result<ty, JsValue>`.
+fn resolved_value(ty: &RustType) -> String {
+    if is_copied(ty) {
+        return format!("Ok({})", from_js("value", ty));
+    }
+    match ty {
+        RustType::TypedArray(t) => format!(
+            "Ok({})",
+            typed_array_vec(&format!("value.unchecked_into::<{}>()", t.js_type()), t)
+        ),
+        RustType::Bool => "value.as_bool().ok_or(value)".to_string(),
+        RustType::F64 => "value.as_f64().ok_or(value)".to_string(),
+        RustType::String => "value.as_string().ok_or(value)".to_string(),
+        RustType::Value(name) => format!("{}::try_from(value)", name),
+        RustType::Option(inner) => match &**inner {
+            RustType::Bool => "Ok(value.as_bool())".to_string(),
+            RustType::F64 => "Ok(value.as_f64())".to_string(),
+            RustType::String => "Ok(value.as_string())".to_string(),
+            RustType::Value(name) => format!(
+                "Some(value).filter(|v| !v.is_undefined() && !v.is_null()).map({}::try_from).transpose()",
+                name
+            ),
+            RustType::TypedArray(t) => format!(
+                "Ok(Some(value).filter(|v| !v.is_undefined() && !v.is_null()).map(|v| {}))",
+                typed_array_vec(&format!("v.unchecked_into::<{}>()", t.js_type()), t)
+            ),
+            _ => "Ok(Some(value).filter(|v| !v.is_undefined() && !v.is_null()).map(JsCast::unchecked_into))"
+                .to_string(),
+        },
+        RustType::JsValue => "Ok(value)".to_string(),
+        _ => "Ok(value.unchecked_into())".to_string(),
+    }
+}
+
+/// The signature of a function with typed parameters and result, such as
+/// `fn get<T: JsCast>(&self, key: &str) -> T`.
+fn typed_signature(f: &Function) -> String {
+    signature_with(f, &f.rust_name, RustType::param)
+}
+
+/// The signature of the variant of a function taking callbacks as `impl FnOnce(...)`.
+fn once_signature(f: &Function) -> String {
+    signature_with(f, &format!("{}_once", f.rust_name), |ty| match ty {
+        RustType::Closure(..) => format!("impl {} + 'static", ty.signature("FnOnce")),
+        ty => ty.param(),
+    })
+}
+
+/// The signature of function `f` named `name`, with parameter types rendered by `param`.
+fn signature_with(f: &Function, name: &str, param: impl Fn(&RustType) -> String) -> String {
+    let mut params = Vec::new();
+    if f.has_receiver() {
+        params.push("&self".to_string());
+    }
+    params.extend(
+        f.params
+            .iter()
+            .map(|p| format!("{}: {}", p.name, param(&p.ty))),
+    );
+    let ret = match &f.ret {
+        Some(ty) => format!(" -> {}", ty.owned()),
+        None => String::new(),
+    };
+    // Type parameters only used by callbacks are erased there.
+    let used: Vec<TypeParam> = f
+        .type_params
+        .iter()
+        .filter(|p| {
+            f.params.iter().any(|param| param.ty.mentions(&p.name))
+                || f.ret.as_ref().is_some_and(|ret| ret.mentions(&p.name))
+        })
+        .cloned()
+        .collect();
+    format!(
+        "fn {}{}({}){}",
+        name,
+        type_params(&used, false),
+        params.join(", "),
+        ret
+    )
+}
+
+/// Renders an extension trait and its implementation for the extended type, whose `members`
+/// are those of the trait's imported binding.
+fn emit_extension_trait(w: &mut Writer, t: &ExtensionTrait, members: &[&Function]) {
+    w.doc(t.doc.as_deref());
+    w.open(&format!("pub trait {} {{", t.rust_name));
+    for (i, f) in members.iter().enumerate() {
+        if i > 0 {
+            w.line("");
+        }
+        w.doc(f.doc.as_deref());
+        w.line(&format!("{};", typed_signature(f)));
+    }
+    w.close("}");
+
+    w.line("");
+    w.open(&format!("impl {} for {} {{", t.rust_name, t.target));
+    for (i, f) in members.iter().enumerate() {
+        if i > 0 {
+            w.line("");
+        }
+        let args: Vec<&str> = f.params.iter().map(|p| p.name.as_str()).collect();
+        w.open(&format!("{} {{", typed_signature(f)));
+        w.line(&format!(
+            "self.unchecked_ref::<{}>().{}({})",
+            t.binding,
+            f.rust_name,
+            args.join(", ")
+        ));
+        w.close("}");
+    }
+    w.close("}");
+}
+
+/// Converts a typed argument for the untyped binding.
+fn wrapper_arg(name: &str, ty: &RustType, generics: &[GenericType]) -> String {
+    match ty {
+        RustType::Vec(_) | RustType::Tuple(..) => format!("&{}", to_js_array(name, ty)),
+        RustType::Map(_) => format!("&{}", to_js_object(name, ty)),
+        RustType::TypedArray(t) if ty.is_generic(generics) => {
+            format!("&{}", typed_array_js(name, t))
+        }
+        RustType::Generic(_) => format!("{}.as_ref()", name),
+        RustType::Path(..) if ty.is_generic(generics) => format!("{}.erased()", name),
+        RustType::Closure(..) => format!("{}.as_ref().unchecked_ref()", name),
+        RustType::Option(inner) => match &**inner {
+            RustType::Vec(_) => format!("{}.map(|v| {}).as_ref()", name, to_js_array("v", inner)),
+            RustType::Map(_) => format!("{}.map(|v| {}).as_ref()", name, to_js_object("v", inner)),
+            RustType::Tuple(..) => format!(
+                "{}.as_ref().map(|v| {}).as_ref()",
+                name,
+                to_js_array("v", inner)
+            ),
+            RustType::TypedArray(t) if inner.is_generic(generics) => {
+                format!("{}.map(|v| {}).as_ref()", name, typed_array_js("v", t))
+            }
+            RustType::Closure(..) => format!("{}.map(|f| f.as_ref().unchecked_ref())", name),
+            RustType::Generic(_) => {
+                format!("{}.map_or(&JsValue::UNDEFINED, |v| v.as_ref())", name)
+            }
+            RustType::Path(..) if inner.is_generic(generics) => {
+                format!("{}.map(|v| v.erased())", name)
+            }
+            _ => name.to_string(),
+        },
+        _ => name.to_string(),
+    }
+}
+
+/// Converts the untyped binding's result to the typed one.
+fn wrapper_result(call: String, ty: &RustType, generics: &[GenericType]) -> String {
+    match ty {
+        RustType::Vec(_) | RustType::Tuple(..) | RustType::Map(_) => {
+            from_js(&format!("JsValue::from({})", call), ty)
+        }
+        RustType::TypedArray(t) if ty.is_generic(generics) => typed_array_vec(&call, t),
+        RustType::Generic(_) => format!("{}.unchecked_into()", call),
+        RustType::Path(path, _) if ty.is_generic(generics) => {
+            format!("{}::from_erased({})", path, call)
+        }
+        RustType::Option(inner) => match &**inner {
+            RustType::Vec(_) | RustType::Tuple(..) | RustType::Map(_) => format!(
+                "{}.map(|v| {})",
+                call,
+                from_js("JsValue::from(v)", inner)
+            ),
+            RustType::TypedArray(t) if inner.is_generic(generics) => {
+                format!("{}.map(|v| {})", call, typed_array_vec("v", t))
+            }
+            RustType::Generic(_) => format!(
+                "Some({}).filter(|v| !v.is_undefined() && !v.is_null()).map(JsCast::unchecked_into)",
+                call
+            ),
+            RustType::Path(path, _) if inner.is_generic(generics) => {
+                format!("{}.map({}::from_erased)", call, path)
+            }
+            _ => call,
+        },
+        _ => call,
+    }
+}
+
+/// Copies `slice` to a new buffer or view of typed array `t`.
+fn typed_array_js(slice: &str, t: &TypedArray) -> String {
+    match t.kind {
+        TypedArrayKind::Buffer => format!("js_sys::Uint8Array::from({}).buffer()", slice),
+        _ => format!("{}::from({})", t.js_type(), slice),
+    }
+}
+
+/// Copies `value`, of typed array `t`'s js-sys type, to a `Vec`. Views are read as their
+/// bytes, whatever their type.
+fn typed_array_vec(value: &str, t: &TypedArray) -> String {
+    match t.kind {
+        TypedArrayKind::Array => format!("{}.to_vec()", value),
+        TypedArrayKind::Buffer => format!("js_sys::Uint8Array::new(&{}).to_vec()", value),
+        TypedArrayKind::View => format!(
+            "{{ let view = {}; js_sys::Uint8Array::new_with_byte_offset_and_length(&view.buffer(), view.byte_offset(), view.byte_length()).to_vec() }}",
+            value
+        ),
+    }
+}
+
+/// Whether values of `ty` are copied to and from JavaScript arrays or objects.
+fn is_copied(ty: &RustType) -> bool {
+    match ty {
+        RustType::Vec(_) | RustType::Tuple(..) | RustType::Map(_) => true,
+        RustType::Option(inner) => is_copied(inner),
+        _ => false,
+    }
+}
+
+/// Copies `place`, a `Vec` or slice or a tuple or a reference to one, to a new
+/// `js_sys::Array`.
+fn to_js_array(place: &str, ty: &RustType) -> String {
+    match ty {
+        // Imported types are collected as they are.
+        RustType::Vec(elem) if is_js(elem) => {
+            format!("{}.iter().collect::<js_sys::Array>()", place)
+        }
+        RustType::Vec(elem) => format!(
+            "{}.iter().map(|v| {}).collect::<js_sys::Array>()",
+            place,
+            to_js("v", elem, true)
+        ),
+        RustType::Tuple(elems, rest) => {
+            let fields: Vec<String> = elems
+                .iter()
+                .enumerate()
+                .map(|(i, elem)| to_js(&format!("{}.{}", place, i), elem, false))
+                .collect();
+            let mut out = format!("[{}].into_iter()", fields.join(", "));
+            if let Some(rest) = rest {
+                out.push_str(&format!(
+                    ".chain({}.{}.iter().map(|v| {}))",
+                    place,
+                    elems.len(),
+                    to_js("v", rest, true)
+                ));
+            }
+            out.push_str(".collect::<js_sys::Array>()");
+            out
+        }
+        _ => unreachable!("only arrays are copied"),
+    }
+}
+
+/// Copies `place`, a `HashMap` or a reference to one, to a new `js_sys::Object`.
+fn to_js_object(place: &str, ty: &RustType) -> String {
+    let RustType::Map(value) = ty else {
+        unreachable!("only records are copied to objects");
+    };
+    format!(
+        "{}.iter().fold(js_sys::Object::new(), |object, (k, v)| {{ js_sys::Reflect::set(&object, &JsValue::from_str(k), &{}).unwrap_throw(); object }})",
+        place,
+        to_js("v", value, true)
+    )
+}
+
+/// Converts `place`, a value of `ty` or, if `by_ref`, a reference to one, to a `JsValue`.
+fn to_js(place: &str, ty: &RustType, by_ref: bool) -> String {
+    let deref = if by_ref { "*" } else { "" };
+    match ty {
+        RustType::F64 => format!("JsValue::from_f64({}{})", deref, place),
+        RustType::Bool => format!("JsValue::from_bool({}{})", deref, place),
+        RustType::String if by_ref => format!("JsValue::from_str({})", place),
+        RustType::String => format!("JsValue::from_str(&{})", place),
+        RustType::JsValue | RustType::Value(_) => format!("JsValue::from({}.clone())", place),
+        RustType::Option(inner) => format!(
+            "{}.as_ref().map_or(JsValue::UNDEFINED, |v| {})",
+            place,
+            to_js("v", inner, true)
+        ),
+        RustType::Vec(_) | RustType::Tuple(..) => {
+            format!("JsValue::from({})", to_js_array(place, ty))
+        }
+        RustType::Map(_) => format!("JsValue::from({})", to_js_object(place, ty)),
+        _ => format!("JsValue::clone({}.as_ref())", place),
+    }
+}
+
+/// Whether `ty` is a JavaScript value, which arrays hold without conversion.
+fn is_js(ty: &RustType) -> bool {
+    matches!(
+        ty,
+        RustType::JsValue | RustType::Path(..) | RustType::Generic(_)
+    )
+}
+
+/// Warns in the documentation of functions whose results `from_js` reads with defaults.
+const DEFAULTS_NOTE: &str =
+    "Numbers, booleans and strings in the result that have another type in JavaScript are read as `NaN`, `false` and `\"\"`.";
+
+/// `doc` with [`DEFAULTS_NOTE`] added if copying a result of type `ret` reads defaults.
+fn noting_defaults(doc: Option<&str>, ret: Option<&RustType>) -> Option<String> {
+    if !ret.is_some_and(RustType::copies_with_defaults) {
+        return doc.map(str::to_string);
+    }
+    Some(match doc {
+        Some(doc) => format!("{}\n\n{}", doc, DEFAULTS_NOTE),
+        None => DEFAULTS_NOTE.to_string(),
+    })
+}
+
+/// Converts `value`, an expression of type `JsValue`, to `ty`. Numbers, booleans and strings
+/// of another type are read as `NaN`, `false` and `""`; documentation of the functions
+/// returning them says so, see [`DEFAULTS_NOTE`].
+fn from_js(value: &str, ty: &RustType) -> String {
+    match ty {
+        RustType::F64 => format!("{}.as_f64().unwrap_or(f64::NAN)", value),
+        RustType::Bool => format!("{}.as_bool().unwrap_or_default()", value),
+        RustType::String => format!("{}.as_string().unwrap_or_default()", value),
+        RustType::JsValue => value.to_string(),
+        RustType::Value(name) => format!("{}::try_from({}).unwrap_throw()", name, value),
+        RustType::Option(inner) => format!(
+            "Some({}).filter(|v| !v.is_undefined() && !v.is_null()).map(|v| {})",
+            value,
+            from_js("v", inner)
+        ),
+        RustType::Vec(elem) if **elem == RustType::JsValue => format!(
+            "{}.unchecked_into::<js_sys::Array>().iter().collect::<Vec<_>>()",
+            value
+        ),
+        RustType::Vec(elem) => format!(
+            "{}.unchecked_into::<js_sys::Array>().iter().map(|v| {}).collect::<Vec<_>>()",
+            value,
+            from_js("v", elem)
+        ),
+        RustType::Tuple(elems, rest) => {
+            let mut fields: Vec<String> = elems
+                .iter()
+                .enumerate()
+                .map(|(i, elem)| from_js(&format!("array.get({})", i), elem))
+                .collect();
+            if let Some(rest) = rest {
+                fields.push(format!(
+                    "array.iter().skip({}).map(|v| {}).collect::<Vec<_>>()",
+                    elems.len(),
+                    from_js("v", rest)
+                ));
+            }
+            let tuple = match fields.as_slice() {
+                [field] => format!("({},)", field),
+                _ => format!("({})", fields.join(", ")),
+            };
+            format!(
+                "{{ let array = {}.unchecked_into::<js_sys::Array>(); {} }}",
+                value, tuple
+            )
+        }
+        RustType::Map(elem) => format!(
+            "js_sys::Object::entries({}.unchecked_ref()).iter().map(|v| {}).collect::<std::collections::HashMap<_, _>>()",
+            value,
+            from_js("v", &RustType::Tuple(vec![RustType::String, (**elem).clone()], None))
+        ),
+        _ => format!("{}.unchecked_into()", value),
+    }
+}
+
+/// `f` with the type parameters that shadow those of its type, `outer`, renamed, or `None` if
+/// none do.
+fn unshadowed(f: &Function, outer: &[TypeParam]) -> Option<Function> {
+    let taken = |name: &str| {
+        outer.iter().any(|p| p.name == name) || f.type_params.iter().any(|p| p.name == name)
+    };
+    let mut renames = Vec::new();
+    for p in &f.type_params {
+        if outer.iter().any(|o| o.name == p.name) {
+            let mut n = 2;
+            while taken(&format!("{}{}", p.name, n)) {
+                n += 1;
+            }
+            renames.push((p.name.clone(), format!("{}{}", p.name, n)));
+        }
+    }
+    if renames.is_empty() {
+        return None;
+    }
+    let mut f = f.clone();
+    for p in &mut f.type_params {
+        if let Some((_, to)) = renames.iter().find(|(from, _)| *from == p.name) {
+            p.name = to.clone();
+        }
+    }
+    for p in &mut f.params {
+        p.ty = rename_generics(&p.ty, &renames);
+    }
+    f.ret = f.ret.as_ref().map(|ty| rename_generics(ty, &renames));
+    f.resolves = f.resolves.as_ref().map(|ty| rename_generics(ty, &renames));
+    Some(f)
+}
+
+fn rename_generics(ty: &RustType, renames: &[(String, String)]) -> RustType {
+    match ty {
+        RustType::Generic(name) => match renames.iter().find(|(from, _)| from == name) {
+            Some((_, to)) => RustType::Generic(to.clone()),
+            None => ty.clone(),
+        },
+        RustType::Path(path, args) => RustType::Path(
+            path.clone(),
+            args.iter().map(|a| rename_generics(a, renames)).collect(),
+        ),
+        RustType::Option(inner) => RustType::option(rename_generics(inner, renames)),
+        RustType::Slice(inner) => RustType::Slice(Box::new(rename_generics(inner, renames))),
+        RustType::Vec(inner) => RustType::Vec(Box::new(rename_generics(inner, renames))),
+        RustType::Map(inner) => RustType::Map(Box::new(rename_generics(inner, renames))),
+        RustType::Tuple(elems, rest) => RustType::Tuple(
+            elems.iter().map(|e| rename_generics(e, renames)).collect(),
+            rest.as_ref().map(|r| Box::new(rename_generics(r, renames))),
+        ),
+        ty => ty.clone(),
+    }
+}
+
+/// A path for a `use` declaration in module `prefix`, which can't rely on the glob imports
+/// that make root-relative paths work elsewhere.
+fn relative_path(path: &str, prefix: &str) -> String {
+    if let Some(local) = path.strip_prefix(prefix) {
+        return format!("self::{}", local);
+    }
+    let depth = prefix.matches("::").count();
+    format!("{}{}", "super::".repeat(depth), path)
+}
+
+fn localize(path: &str, prefix: &str) -> String {
+    path.strip_prefix(prefix).unwrap_or(path).to_string()
+}
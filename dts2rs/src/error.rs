@@ -0,0 +1,52 @@
+use std::error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+use dts_parser::ParseError;
+
+/// A fatal error that stops binding generation.
+#[derive(Debug)]
+pub enum Error {
+    Io(PathBuf, io::Error),
+    /// A declaration file failed to parse. Holds the file name and the rendered error.
+    Parse(String, ParseError, String),
+    /// A configuration file is malformed.
+    Config(PathBuf, String),
+    /// No declarations were found for a package, searching from a directory.
+    Package(String, PathBuf),
+}
+
+impl Error {
+    pub fn parse(file: &str, src: &str, err: ParseError) -> Error {
+        let rendered = err.display_with_source(file, src);
+        Error::Parse(file.to_string(), err, rendered)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+            Error::Parse(_, _, rendered) => write!(f, "{}", rendered),
+            Error::Config(path, msg) => write!(f, "{}: {}", path.display(), msg),
+            Error::Package(name, dir) => write!(
+                f,
+                "cannot find type declarations for package `{}` in the node_modules \
+                 directories of {}",
+                name,
+                dir.display()
+            ),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(_, err) => Some(err),
+            Error::Parse(_, err, _) => Some(err),
+            Error::Config(..) | Error::Package(..) => None,
+        }
+    }
+}
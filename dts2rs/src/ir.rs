@@ -0,0 +1,716 @@
+//! The binding model produced by lowering and consumed by [`emit`](crate::emit).
+//!
+//! Items here correspond one-to-one to Rust items in the output; all TypeScript-specific
+//! decisions have already been made.
+
+use std::fmt::Write;
+
+/// A Rust type as it appears in a binding signature. How it is rendered depends on whether it
+/// is a parameter (borrowed) or a return value (owned).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RustType {
+    Unit,
+    Bool,
+    F64,
+    String,
+    JsValue,
+    /// A named type such as `Foo` or `js_sys::Object`, with optional generic arguments.
+    Path(String, Vec<RustType>),
+    Option(Box<RustType>),
+    /// A slice parameter; returned as a `Vec`.
+    Slice(Box<RustType>),
+    /// A type defined by the generated code, such as a union enum, passed by value.
+    Value(String),
+    /// A type parameter, bounded by `JsCast`.
+    Generic(String),
+    /// An array copied to or from a `Vec`, passed on as a `js_sys::Array`: `&[T]` as a
+    /// parameter, `Vec<T>` as a result.
+    Vec(Box<RustType>),
+    /// A tuple copied to or from a Rust tuple, passed on as a `js_sys::Array`, with the type
+    /// of its trailing rest elements, which are collected into a `Vec`.
+    Tuple(Vec<RustType>, Option<Box<RustType>>),
+    /// A callback parameter, `&Closure<dyn FnMut(A, B) -> R>`, passed on as a
+    /// `js_sys::Function`. `None` for callbacks returning nothing.
+    Closure(Vec<RustType>, Option<Box<RustType>>),
+    /// A record copied to or from a `HashMap<String, V>`, passed on as a `js_sys::Object`.
+    Map(Box<RustType>),
+    /// A typed array or buffer copied from a slice and to a `Vec`, such as `&[u8]` and
+    /// `Vec<u8>` for `Uint8Array`.
+    TypedArray(TypedArray),
+}
+
+/// The element type and JavaScript type of a [`RustType::TypedArray`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TypedArray {
+    /// The element type, such as `f32` for `Float32Array`, and `u8` for buffers and views.
+    pub elem: String,
+    pub kind: TypedArrayKind,
+    /// Whether parameters are taken as `&mut [T]`, to which what JavaScript writes to the
+    /// array is copied back.
+    pub mutable: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TypedArrayKind {
+    /// A typed array such as `Uint8Array`, which wasm-bindgen copies itself.
+    Array,
+    /// An `ArrayBuffer`, passed as the buffer of a `Uint8Array`.
+    Buffer,
+    /// Any typed array or `DataView`, as for `ArrayBufferView` and `BufferSource`: passed as
+    /// a `Uint8Array` and read as its bytes.
+    View,
+}
+
+impl TypedArray {
+    /// The js-sys type the binding passes, such as `js_sys::Float32Array`.
+    pub fn js_type(&self) -> String {
+        let name = match (self.kind, self.elem.as_str()) {
+            (TypedArrayKind::Buffer, _) => "ArrayBuffer",
+            (TypedArrayKind::View, _) | (_, "u8") => "Uint8Array",
+            (_, "i8") => "Int8Array",
+            (_, "u16") => "Uint16Array",
+            (_, "i16") => "Int16Array",
+            (_, "u32") => "Uint32Array",
+            (_, "i32") => "Int32Array",
+            (_, "u64") => "BigUint64Array",
+            (_, "i64") => "BigInt64Array",
+            (_, "f32") => "Float32Array",
+            _ => "Float64Array",
+        };
+        format!("js_sys::{}", name)
+    }
+}
+
+impl RustType {
+    pub fn path(path: impl Into<String>) -> RustType {
+        RustType::Path(path.into(), Vec::new())
+    }
+
+    /// Whether the type mentions a type parameter or one of `generics`, which can't cross
+    /// the wasm ABI, or is a callback, whose closure is checked by a typed wrapper.
+    pub fn is_generic(&self, generics: &[GenericType]) -> bool {
+        match self {
+            RustType::Generic(_)
+            | RustType::Closure(..)
+            | RustType::Vec(_)
+            | RustType::Tuple(..)
+            | RustType::Map(_) => true,
+            // Buffers and views are converted by a wrapper; typed arrays by wasm-bindgen.
+            RustType::TypedArray(t) => t.kind != TypedArrayKind::Array,
+            RustType::Path(path, args) => {
+                generics.iter().any(|g| g.rust_name == *path)
+                    || args.iter().any(|a| a.is_generic(generics))
+            }
+            RustType::Option(inner) | RustType::Slice(inner) => inner.is_generic(generics),
+            _ => false,
+        }
+    }
+
+    /// Whether the type mentions type parameter `name`.
+    pub fn mentions(&self, name: &str) -> bool {
+        match self {
+            RustType::Generic(param) => param == name,
+            RustType::Path(_, args) => args.iter().any(|a| a.mentions(name)),
+            RustType::Option(inner)
+            | RustType::Slice(inner)
+            | RustType::Vec(inner)
+            | RustType::Map(inner) => inner.mentions(name),
+            RustType::Tuple(elems, rest) => {
+                elems.iter().any(|e| e.mentions(name))
+                    || rest.as_ref().is_some_and(|r| r.mentions(name))
+            }
+            RustType::Closure(params, ret) => {
+                params.iter().any(|p| p.mentions(name))
+                    || ret.as_ref().is_some_and(|r| r.mentions(name))
+            }
+            _ => false,
+        }
+    }
+
+    /// The type with typed wrappers in `generics` replaced by their untyped bindings and type
+    /// parameters by `JsValue`.
+    pub fn erased(&self, generics: &[GenericType]) -> RustType {
+        match self {
+            RustType::Generic(_) => RustType::JsValue,
+            RustType::Closure(..) => RustType::path("js_sys::Function"),
+            RustType::Vec(_) | RustType::Tuple(..) => RustType::path("js_sys::Array"),
+            RustType::Map(_) => RustType::path("js_sys::Object"),
+            RustType::TypedArray(t) if t.kind != TypedArrayKind::Array => {
+                RustType::path(t.js_type())
+            }
+            RustType::Path(path, args) => match generics.iter().find(|g| g.rust_name == *path) {
+                Some(g) => RustType::path(g.erased.clone()),
+                None => RustType::Path(
+                    path.clone(),
+                    args.iter().map(|a| a.erased(generics)).collect(),
+                ),
+            },
+            RustType::Option(inner) => RustType::option(inner.erased(generics)),
+            RustType::Slice(inner) => RustType::Slice(Box::new(inner.erased(generics))),
+            ty => ty.clone(),
+        }
+    }
+
+    /// The type with the paths of the types it names replaced by `f` of them.
+    pub fn map_paths(&self, f: &dyn Fn(&str) -> String) -> RustType {
+        match self {
+            RustType::Path(path, args) => {
+                RustType::Path(f(path), args.iter().map(|a| a.map_paths(f)).collect())
+            }
+            RustType::Value(path) => RustType::Value(f(path)),
+            RustType::Option(inner) => RustType::Option(Box::new(inner.map_paths(f))),
+            RustType::Slice(inner) => RustType::Slice(Box::new(inner.map_paths(f))),
+            RustType::Vec(inner) => RustType::Vec(Box::new(inner.map_paths(f))),
+            RustType::Map(inner) => RustType::Map(Box::new(inner.map_paths(f))),
+            RustType::Tuple(elems, rest) => RustType::Tuple(
+                elems.iter().map(|e| e.map_paths(f)).collect(),
+                rest.as_ref().map(|r| Box::new(r.map_paths(f))),
+            ),
+            RustType::Closure(params, ret) => RustType::Closure(
+                params.iter().map(|p| p.map_paths(f)).collect(),
+                ret.as_ref().map(|r| Box::new(r.map_paths(f))),
+            ),
+            ty => ty.clone(),
+        }
+    }
+
+    pub fn option(inner: RustType) -> RustType {
+        match inner {
+            // `JsValue` already covers `undefined` and `null`.
+            RustType::JsValue | RustType::Option(_) | RustType::Unit => inner,
+            inner => RustType::Option(Box::new(inner)),
+        }
+    }
+
+    /// Whether copying a value of this type from JavaScript reads the numbers, booleans and
+    /// strings in it, which are read as `NaN`, `false` and `""` when they have another type.
+    pub fn copies_with_defaults(&self) -> bool {
+        match self {
+            RustType::Option(inner) => inner.copies_with_defaults(),
+            RustType::Vec(elem) | RustType::Map(elem) => elem.is_defaulted(),
+            RustType::Tuple(elems, rest) => elems
+                .iter()
+                .chain(rest.as_deref())
+                .any(RustType::is_defaulted),
+            _ => false,
+        }
+    }
+
+    /// Whether a value of this type, or values in it, are read from JavaScript with a default
+    /// for values of another type.
+    pub fn is_defaulted(&self) -> bool {
+        match self {
+            RustType::F64 | RustType::Bool | RustType::String => true,
+            RustType::Option(inner) => inner.is_defaulted(),
+            ty => ty.copies_with_defaults(),
+        }
+    }
+
+    /// Renders the type in argument position.
+    pub fn param(&self) -> String {
+        match self {
+            RustType::String => "&str".to_string(),
+            RustType::JsValue => "&JsValue".to_string(),
+            RustType::Path(..) | RustType::Generic(_) | RustType::Closure(..) => {
+                format!("&{}", self.owned())
+            }
+            RustType::Option(inner) => format!("Option<{}>", inner.param()),
+            RustType::Slice(inner) | RustType::Vec(inner) => format!("&[{}]", inner.owned()),
+            RustType::Map(_) => format!("&{}", self.owned()),
+            RustType::TypedArray(t) if t.mutable => format!("&mut [{}]", t.elem),
+            RustType::TypedArray(t) => format!("&[{}]", t.elem),
+            _ => self.owned(),
+        }
+    }
+
+    /// Renders the type in return position, or as a field or static type.
+    pub fn owned(&self) -> String {
+        match self {
+            RustType::Unit => "()".to_string(),
+            RustType::Bool => "bool".to_string(),
+            RustType::F64 => "f64".to_string(),
+            RustType::String => "String".to_string(),
+            RustType::JsValue => "JsValue".to_string(),
+            RustType::Path(path, args) => {
+                let mut out = path.clone();
+                if !args.is_empty() {
+                    out.push('<');
+                    for (i, arg) in args.iter().enumerate() {
+                        if i > 0 {
+                            out.push_str(", ");
+                        }
+                        write!(out, "{}", arg.owned()).unwrap();
+                    }
+                    out.push('>');
+                }
+                out
+            }
+            RustType::Option(inner) => format!("Option<{}>", inner.owned()),
+            RustType::Slice(inner) | RustType::Vec(inner) => format!("Vec<{}>", inner.owned()),
+            RustType::Tuple(elems, rest) => {
+                let mut elems: Vec<String> = elems.iter().map(RustType::owned).collect();
+                if let Some(rest) = rest {
+                    elems.push(format!("Vec<{}>", rest.owned()));
+                }
+                match elems.as_slice() {
+                    [elem] => format!("({},)", elem),
+                    _ => format!("({})", elems.join(", ")),
+                }
+            }
+            RustType::Value(name) | RustType::Generic(name) => name.clone(),
+            RustType::Closure(..) => format!("Closure<dyn {}>", self.signature("FnMut")),
+            RustType::TypedArray(t) => format!("Vec<{}>", t.elem),
+            RustType::Map(value) => {
+                format!("std::collections::HashMap<String, {}>", value.owned())
+            }
+        }
+    }
+
+    /// Renders the signature of a callback as that of closure trait `f`, such as
+    /// `FnMut(String) -> bool`.
+    pub fn signature(&self, f: &str) -> String {
+        let RustType::Closure(params, ret) = self else {
+            return self.owned();
+        };
+        let params: Vec<String> = params.iter().map(RustType::owned).collect();
+        match ret {
+            Some(ret) => format!("{}({}) -> {}", f, params.join(", "), ret.owned()),
+            None => format!("{}({})", f, params.join(", ")),
+        }
+    }
+}
+
+/// A Rust module of bindings. The root module is the generated file itself.
+///
+/// Paths in the model are relative to the root module, such as `foo::Bar` for type `Bar` in
+/// module `foo`; the emitter shortens them where they are used.
+#[derive(Clone, Debug, Default)]
+pub struct Module {
+    pub name: String,
+    pub doc: Option<String>,
+    /// The ES module the items are imported from, for `declare module "pkg"` and for ES
+    /// module files when [`Config::module`](crate::Config::module) is set.
+    pub js_module: Option<String>,
+    /// The global namespace object the items are properties of, such as `["Foo", "Bar"]` for
+    /// `declare namespace Foo.Bar`.
+    pub js_namespace: Vec<String>,
+    /// Items imported from JavaScript, emitted inside `#[wasm_bindgen] extern "C"` blocks.
+    pub externs: Vec<ExternItem>,
+    /// Enums defined in Rust, emitted after the extern blocks.
+    pub enums: Vec<Enum>,
+    /// Typed wrappers of generic types.
+    pub generics: Vec<GenericType>,
+    /// Extension traits adding members to types of other crates.
+    pub traits: Vec<ExtensionTrait>,
+    /// Types whose string index signature lists its entries.
+    pub entries: Vec<Entries>,
+    /// Nested modules, one per namespace or ES module.
+    pub modules: Vec<Module>,
+    /// Items of other modules re-exported from this one, by `export ... from`.
+    pub reexports: Vec<Reexport>,
+}
+
+/// `pub use path as alias;`. The path is relative to the root module and may end in `::*`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reexport {
+    pub path: String,
+    pub alias: Option<String>,
+}
+
+impl Module {
+    /// The nested module `name`, created if it doesn't exist yet.
+    pub fn submodule_mut(&mut self, name: &str) -> &mut Module {
+        let i = match self.modules.iter().position(|m| m.name == name) {
+            Some(i) => i,
+            None => {
+                self.modules.push(Module {
+                    name: name.to_string(),
+                    ..Module::default()
+                });
+                self.modules.len() - 1
+            }
+        };
+        &mut self.modules[i]
+    }
+
+    /// The module with the paths of its own items, and of the types they refer to, replaced
+    /// by `f` of them. Submodules and re-exports are left as they are.
+    pub fn map_paths(&self, f: &dyn Fn(&str) -> String) -> Module {
+        let path = |p: &String| f(p);
+        let ty = |t: &RustType| t.map_paths(f);
+        let externs = self
+            .externs
+            .iter()
+            .map(|item| match item {
+                ExternItem::Type(t) => ExternItem::Type(TypeDecl {
+                    rust_name: path(&t.rust_name),
+                    extends: t.extends.iter().map(path).collect(),
+                    ..t.clone()
+                }),
+                ExternItem::Function(func) => ExternItem::Function(Function {
+                    kind: func.kind.map_paths(f),
+                    type_params: func.type_params.iter().map(|p| p.map_paths(f)).collect(),
+                    params: func
+                        .params
+                        .iter()
+                        .map(|p| Param {
+                            ty: ty(&p.ty),
+                            ..p.clone()
+                        })
+                        .collect(),
+                    ret: func.ret.as_ref().map(ty),
+                    resolves: func.resolves.as_ref().map(ty),
+                    ..func.clone()
+                }),
+                ExternItem::Static(s) => ExternItem::Static(Static {
+                    ty: ty(&s.ty),
+                    ..s.clone()
+                }),
+            })
+            .collect();
+        let enums = self
+            .enums
+            .iter()
+            .map(|e| Enum {
+                rust_name: path(&e.rust_name),
+                variants: e
+                    .variants
+                    .iter()
+                    .map(|v| Variant {
+                        kind: match &v.kind {
+                            VariantKind::Value(t) => VariantKind::Value(ty(t)),
+                            kind => kind.clone(),
+                        },
+                        ..v.clone()
+                    })
+                    .collect(),
+                ..e.clone()
+            })
+            .collect();
+        Module {
+            name: self.name.clone(),
+            doc: self.doc.clone(),
+            js_module: self.js_module.clone(),
+            js_namespace: self.js_namespace.clone(),
+            externs,
+            enums,
+            generics: self.generics.iter().map(|g| g.map_paths(f)).collect(),
+            traits: self
+                .traits
+                .iter()
+                .map(|t| ExtensionTrait {
+                    rust_name: path(&t.rust_name),
+                    target: path(&t.target),
+                    binding: path(&t.binding),
+                    doc: t.doc.clone(),
+                })
+                .collect(),
+            entries: self
+                .entries
+                .iter()
+                .map(|e| Entries {
+                    this: path(&e.this),
+                    value: ty(&e.value),
+                })
+                .collect(),
+            modules: self.modules.clone(),
+            reexports: self.reexports.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum ExternItem {
+    Type(TypeDecl),
+    Function(Function),
+    Static(Static),
+}
+
+/// An opaque JavaScript type, `pub type Foo;`.
+#[derive(Clone, Debug)]
+pub struct TypeDecl {
+    pub rust_name: String,
+    pub js_name: String,
+    /// Paths of the types this one can be upcast to, nearest first.
+    pub extends: Vec<String>,
+    /// A function checking whether a value is of this type, for types without a JavaScript
+    /// class to test with `instanceof`.
+    pub is_type_of: Option<String>,
+    pub doc: Option<String>,
+}
+
+/// A phantom-typed wrapper around the untyped binding of a generic type:
+/// `pub struct Cell<T: JsCast = JsValue>` around `CellErased`.
+#[derive(Clone, Debug)]
+pub struct GenericType {
+    pub rust_name: String,
+    /// The Rust name of the untyped binding.
+    pub erased: String,
+    pub type_params: Vec<TypeParam>,
+    pub kind: GenericKind,
+    pub doc: Option<String>,
+}
+
+impl GenericType {
+    /// The wrapper with the paths it refers to replaced by `f` of them.
+    pub fn map_paths(&self, f: &dyn Fn(&str) -> String) -> GenericType {
+        GenericType {
+            rust_name: f(&self.rust_name),
+            erased: f(&self.erased),
+            type_params: self.type_params.iter().map(|p| p.map_paths(f)).collect(),
+            kind: self.kind,
+            doc: self.doc.clone(),
+        }
+    }
+}
+
+/// Whether a generic type is declared by the project or is one of the typed views that
+/// bindings use, which also get typed accessors such as `get`, `set` and `keys`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GenericKind {
+    Declared,
+    /// `JsArray<T>`, the typed view of arrays.
+    Array,
+    /// `JsRecord<T>`, the typed view of records such as `Record<string, T>`.
+    Record,
+    /// `JsPrimitiveRecord<T>`, the view of records of numbers, booleans or strings, which
+    /// reads and writes them as `f64`, `bool` or `String`.
+    PrimitiveRecord,
+}
+
+/// `keys`, `entries` and `to_map` of a type with a string index signature, implemented in
+/// Rust with `Object.keys` and `Object.entries`.
+#[derive(Clone, Debug)]
+pub struct Entries {
+    /// The path of the type, which is the untyped binding for generic types.
+    pub this: String,
+    pub value: RustType,
+}
+
+/// A trait giving a type that another crate binds, such as `web_sys::Window`, the members
+/// that declarations add to it: `pub trait WindowMyLibExt`. It is implemented for the type by
+/// casting to `binding`, an imported type with those members.
+#[derive(Clone, Debug)]
+pub struct ExtensionTrait {
+    pub rust_name: String,
+    /// The path of the extended type.
+    pub target: String,
+    pub binding: String,
+    pub doc: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct TypeParam {
+    pub name: String,
+    pub default: Option<RustType>,
+}
+
+impl TypeParam {
+    fn map_paths(&self, f: &dyn Fn(&str) -> String) -> TypeParam {
+        TypeParam {
+            name: self.name.clone(),
+            default: self.default.as_ref().map(|d| d.map_paths(f)),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Param {
+    pub name: String,
+    pub ty: RustType,
+    /// A short snake_case word describing the parameter, such as `str` or `options`. Used
+    /// to tell overloads apart.
+    pub descriptor: String,
+}
+
+/// How an imported function is called from JavaScript.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FunctionKind {
+    /// A free function.
+    Free,
+    /// A method called on an instance of `this`. Structural methods are looked up by name on
+    /// the object rather than on the class prototype.
+    Method { this: String, structural: bool },
+    /// A property getter on an instance of `this`.
+    Getter { this: String, structural: bool },
+    /// A property setter on an instance of `this`.
+    Setter { this: String, structural: bool },
+    /// A constructor of `class`, called with `new`.
+    Constructor { class: String },
+    /// A static method of `class`.
+    StaticMethod { class: String },
+    /// A static property getter of `class`.
+    StaticGetter { class: String },
+    /// A static property setter of `class`.
+    StaticSetter { class: String },
+    /// An accessor of the index signature of `this`, looked up by name on the object.
+    Index { this: String, op: IndexOp },
+    /// A call of the function object `this`, bound privately as its `Function.prototype.call`
+    /// and called through a wrapper that passes `undefined` for the call's `this`.
+    Call { this: String },
+}
+
+impl FunctionKind {
+    fn map_paths(&self, f: &dyn Fn(&str) -> String) -> FunctionKind {
+        match self {
+            FunctionKind::Free => FunctionKind::Free,
+            FunctionKind::Method { this, structural } => FunctionKind::Method {
+                this: f(this),
+                structural: *structural,
+            },
+            FunctionKind::Getter { this, structural } => FunctionKind::Getter {
+                this: f(this),
+                structural: *structural,
+            },
+            FunctionKind::Setter { this, structural } => FunctionKind::Setter {
+                this: f(this),
+                structural: *structural,
+            },
+            FunctionKind::Index { this, op } => FunctionKind::Index {
+                this: f(this),
+                op: *op,
+            },
+            FunctionKind::Call { this } => FunctionKind::Call { this: f(this) },
+            FunctionKind::Constructor { class } => FunctionKind::Constructor { class: f(class) },
+            FunctionKind::StaticMethod { class } => FunctionKind::StaticMethod { class: f(class) },
+            FunctionKind::StaticGetter { class } => FunctionKind::StaticGetter { class: f(class) },
+            FunctionKind::StaticSetter { class } => FunctionKind::StaticSetter { class: f(class) },
+        }
+    }
+}
+
+/// What an index signature accessor does with the entry it indexes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndexOp {
+    Get,
+    Set,
+    Delete,
+}
+
+#[derive(Clone, Debug)]
+pub struct Function {
+    pub kind: FunctionKind,
+    pub rust_name: String,
+    /// Identifies the function across runs for name overrides; see
+    /// [`Config::names`](crate::config::Config::names).
+    pub key: String,
+    /// The JavaScript name of the function, method or property.
+    pub js_name: String,
+    /// Type parameters of the function itself. Functions whose signature
+    /// [is generic](RustType::is_generic) are bound untyped and called through a typed
+    /// wrapper.
+    pub type_params: Vec<TypeParam>,
+    pub params: Vec<Param>,
+    /// `None` for functions returning nothing.
+    pub ret: Option<RustType>,
+    /// Whether the last parameter collects the rest of the JavaScript arguments.
+    pub variadic: bool,
+    /// For a function returning a promise with [`PromiseMode::Async`](crate::PromiseMode),
+    /// the type the promise resolves to, `Unit` for `Promise<void>`.
+    pub resolves: Option<RustType>,
+    /// The name of the `async fn` awaiting the promise, which [`resolves`](Self::resolves)
+    /// functions get in place of their own name.
+    pub async_name: Option<String>,
+    pub doc: Option<String>,
+}
+
+impl Function {
+    /// The Rust type the function is a member of, if any.
+    pub fn owner(&self) -> Option<&str> {
+        match &self.kind {
+            FunctionKind::Free => None,
+            FunctionKind::Method { this, .. }
+            | FunctionKind::Getter { this, .. }
+            | FunctionKind::Setter { this, .. }
+            | FunctionKind::Index { this, .. }
+            | FunctionKind::Call { this } => Some(this),
+            FunctionKind::Constructor { class }
+            | FunctionKind::StaticMethod { class }
+            | FunctionKind::StaticGetter { class }
+            | FunctionKind::StaticSetter { class } => Some(class),
+        }
+    }
+
+    /// Whether the function is called on an instance.
+    pub fn has_receiver(&self) -> bool {
+        matches!(
+            self.kind,
+            FunctionKind::Method { .. }
+                | FunctionKind::Getter { .. }
+                | FunctionKind::Setter { .. }
+                | FunctionKind::Index { .. }
+                | FunctionKind::Call { .. }
+        )
+    }
+
+    /// Whether the signature mentions type parameters or typed wrappers, so that the function
+    /// is bound untyped and called through a typed wrapper.
+    pub fn is_generic(&self, generics: &[GenericType]) -> bool {
+        self.params.iter().any(|p| p.ty.is_generic(generics))
+            || self.ret.as_ref().is_some_and(|r| r.is_generic(generics))
+    }
+}
+
+/// A global variable, bound as a lazily initialized thread-local static.
+#[derive(Clone, Debug)]
+pub struct Static {
+    pub rust_name: String,
+    pub js_name: String,
+    pub ty: RustType,
+    pub doc: Option<String>,
+}
+
+/// A `#[wasm_bindgen]` enum standing for a TypeScript enum or union type. It converts to and
+/// from `JsValue` and can be passed to and returned from imported functions by value.
+#[derive(Clone, Debug)]
+pub struct Enum {
+    pub rust_name: String,
+    /// Converting from JavaScript tries the variants in order. Variants are either all
+    /// integers, or strings and values.
+    pub variants: Vec<Variant>,
+    /// Further names for variants, as associated constants.
+    pub aliases: Vec<EnumAlias>,
+    pub doc: Option<String>,
+}
+
+impl Enum {
+    /// Whether all variants are string values, which makes this a plain string enum.
+    pub fn is_string_enum(&self) -> bool {
+        self.variants
+            .iter()
+            .all(|v| matches!(v.kind, VariantKind::Str(_)))
+    }
+
+    /// Whether all variants are integers, which makes this a C-style enum.
+    pub fn is_int_enum(&self) -> bool {
+        self.variants
+            .iter()
+            .all(|v| matches!(v.kind, VariantKind::Int(_)))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Variant {
+    pub name: String,
+    pub kind: VariantKind,
+    pub doc: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum VariantKind {
+    /// A unit variant with an integer discriminant.
+    Int(i64),
+    /// A unit variant for a string value.
+    Str(String),
+    /// A variant holding a value of another type.
+    Value(RustType),
+}
+
+/// `pub const NAME: Enum = Enum::Variant;`, for enum members that share a value with an
+/// earlier one.
+#[derive(Clone, Debug)]
+pub struct EnumAlias {
+    pub name: String,
+    pub variant: String,
+    pub doc: Option<String>,
+}
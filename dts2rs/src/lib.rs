@@ -0,0 +1,152 @@
+//! Generates `wasm-bindgen` bindings from TypeScript declaration files.
+//!
+//! ```
+//! let output = dts2rs::Generator::new()
+//!     .generate_source("lib.d.ts", "declare function foo(a: number): string;")
+//!     .unwrap();
+//! assert!(output.code.contains("pub fn foo(a: f64) -> String;"));
+//! ```
+
+pub mod config;
+pub mod diagnostics;
+pub mod emit;
+mod error;
+pub mod ir;
+pub mod libs;
+pub mod lower;
+pub mod names;
+pub mod project;
+mod resolve;
+pub mod tsconfig;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+pub use crate::config::{
+    ArrayMode, Config, GenericsMode, PromiseMode, RecordMode, TypedArrayMode, UmdMode,
+    UnionStrategy,
+};
+pub use crate::diagnostics::{Diagnostic, Severity};
+pub use crate::error::Error;
+pub use crate::tsconfig::CompilerOptions;
+
+use crate::diagnostics::Diagnostics;
+use crate::lower::Lowerer;
+use crate::project::Project;
+
+/// The result of a successful run.
+#[derive(Debug)]
+pub struct Output {
+    /// The generated Rust source.
+    pub code: String,
+    pub diagnostics: Vec<Diagnostic>,
+    /// The Rust name chosen for every function, method and accessor, by the key used in
+    /// [`Config::names`]. Writing these back into the config pins them.
+    pub names: BTreeMap<String, String>,
+    /// The Rust name of every object literal type bound as a named type, by the key used in
+    /// [`Config::type_names`].
+    pub type_names: BTreeMap<String, String>,
+    /// The `web-sys` cargo features the bindings need, one per `web-sys` type they use.
+    pub web_sys_features: BTreeSet<String>,
+    /// Whether the bindings need `wasm-bindgen-futures`, for async wrappers.
+    pub futures: bool,
+}
+
+impl Output {
+    /// A `Cargo.toml` for a crate named `name` holding the bindings, with the dependencies
+    /// they need.
+    pub fn cargo_toml(&self, name: &str) -> String {
+        let mut out = format!(
+            "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n\
+             [dependencies]\njs-sys = \"0.3\"\nwasm-bindgen = \"0.2\"\n",
+            name
+        );
+        if self.futures {
+            out.push_str("wasm-bindgen-futures = \"0.4\"\n");
+        }
+        if !self.web_sys_features.is_empty() {
+            let features: Vec<String> = self
+                .web_sys_features
+                .iter()
+                .map(|f| format!("    \"{}\",\n", f))
+                .collect();
+            out.push_str(&format!(
+                "\n[dependencies.web-sys]\nversion = \"0.3\"\nfeatures = [\n{}]\n",
+                features.concat()
+            ));
+        }
+        out
+    }
+}
+
+/// Turns declaration files into Rust bindings.
+#[derive(Debug, Default)]
+pub struct Generator {
+    config: Config,
+    options: CompilerOptions,
+}
+
+impl Generator {
+    pub fn new() -> Generator {
+        Generator::default()
+    }
+
+    pub fn with_config(config: Config) -> Generator {
+        Generator {
+            config,
+            options: CompilerOptions::default(),
+        }
+    }
+
+    /// Resolves modules and picks the built-in libraries as the TypeScript compiler does with
+    /// `options`, usually read from a `tsconfig.json` with [`CompilerOptions::from_file`].
+    pub fn compiler_options(mut self, options: CompilerOptions) -> Generator {
+        self.options = options;
+        self
+    }
+
+    /// Generates bindings for the declaration file at `path` and the files it imports.
+    pub fn generate_file(&self, path: &Path) -> Result<Output, Error> {
+        let mut diags = Diagnostics::default();
+        let project = Project::load(path, &self.options, &mut diags)?;
+        Ok(self.generate(&project, diags))
+    }
+
+    /// Generates bindings for package `name`, installed in a `node_modules` directory of `dir`
+    /// or one of its ancestors, and the files it imports. Declarations are imported from the
+    /// package unless [`Config::module`] says otherwise.
+    pub fn generate_package(&self, name: &str, dir: &Path) -> Result<Output, Error> {
+        let mut diags = Diagnostics::default();
+        let project = Project::load_package(name, dir, &self.options, &mut diags)?;
+        let config = Config {
+            module: Some(
+                self.config
+                    .module
+                    .clone()
+                    .unwrap_or_else(|| name.to_string()),
+            ),
+            ..self.config.clone()
+        };
+        Ok(Generator::with_config(config).generate(&project, diags))
+    }
+
+    /// Generates bindings for declaration source text. `file_name` is only used in messages.
+    /// Imports of other files can't be followed and are bound as `JsValue`.
+    pub fn generate_source(&self, file_name: &str, src: &str) -> Result<Output, Error> {
+        let mut diags = Diagnostics::default();
+        let project = Project::from_source(file_name, src, &mut diags)?;
+        Ok(self.generate(&project, diags))
+    }
+
+    fn generate(&self, project: &Project, mut diags: Diagnostics) -> Output {
+        let lowered = Lowerer::new(project, &self.config, &mut diags).lower_project();
+        Output {
+            code: emit::emit(&lowered.module),
+            diagnostics: diags.list,
+            names: lowered.names,
+            type_names: lowered.type_names,
+            web_sys_features: lowered.web_sys_features,
+            futures: lowered.futures,
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! The built-in TypeScript libraries whose globals a project may use, such as `es2020` or
+//! `dom`.
+//!
+//! A library name stands for the libraries it references as well, the way `lib.es2020.d.ts`
+//! references `lib.es2019.d.ts` and `lib.es2020.bigint.d.ts`. The set comes from the `lib`,
+//! `target` and `noLib` compiler options and from `/// <reference lib="..." />` directives.
+
+use std::collections::BTreeSet;
+
+/// The libraries each library references.
+const REFERENCES: &[(&str, &[&str])] = &[
+    ("es6", &["es2015"]),
+    ("es7", &["es2016"]),
+    (
+        "es2015",
+        &[
+            "es5",
+            "es2015.core",
+            "es2015.collection",
+            "es2015.iterable",
+            "es2015.generator",
+            "es2015.promise",
+            "es2015.proxy",
+            "es2015.reflect",
+            "es2015.symbol",
+            "es2015.symbol.wellknown",
+        ],
+    ),
+    ("es2016", &["es2015", "es2016.array.include", "es2016.intl"]),
+    (
+        "es2017",
+        &[
+            "es2016",
+            "es2017.arraybuffer",
+            "es2017.date",
+            "es2017.intl",
+            "es2017.object",
+            "es2017.sharedmemory",
+            "es2017.string",
+            "es2017.typedarrays",
+        ],
+    ),
+    (
+        "es2018",
+        &[
+            "es2017",
+            "es2018.asyncgenerator",
+            "es2018.asynciterable",
+            "es2018.intl",
+            "es2018.promise",
+            "es2018.regexp",
+        ],
+    ),
+    (
+        "es2019",
+        &[
+            "es2018",
+            "es2019.array",
+            "es2019.intl",
+            "es2019.object",
+            "es2019.string",
+            "es2019.symbol",
+        ],
+    ),
+    (
+        "es2020",
+        &[
+            "es2019",
+            "es2020.bigint",
+            "es2020.date",
+            "es2020.intl",
+            "es2020.number",
+            "es2020.promise",
+            "es2020.sharedmemory",
+            "es2020.string",
+            "es2020.symbol.wellknown",
+        ],
+    ),
+    (
+        "es2021",
+        &[
+            "es2020",
+            "es2021.intl",
+            "es2021.promise",
+            "es2021.string",
+            "es2021.weakref",
+        ],
+    ),
+    (
+        "es2022",
+        &[
+            "es2021",
+            "es2022.array",
+            "es2022.error",
+            "es2022.intl",
+            "es2022.object",
+            "es2022.regexp",
+            "es2022.string",
+        ],
+    ),
+    (
+        "es2023",
+        &["es2022", "es2023.array", "es2023.collection", "es2023.intl"],
+    ),
+    (
+        "es2024",
+        &[
+            "es2023",
+            "es2024.arraybuffer",
+            "es2024.collection",
+            "es2024.object",
+            "es2024.promise",
+            "es2024.regexp",
+            "es2024.sharedmemory",
+            "es2024.string",
+        ],
+    ),
+    (
+        "esnext",
+        &[
+            "es2024",
+            "esnext.array",
+            "esnext.collection",
+            "esnext.decorators",
+            "esnext.disposable",
+            "esnext.error",
+            "esnext.float16",
+            "esnext.intl",
+            "esnext.iterator",
+            "esnext.promise",
+            "esnext.sharedmemory",
+        ],
+    ),
+];
+
+/// A set of libraries, including those they reference.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Libs {
+    names: BTreeSet<String>,
+}
+
+impl Libs {
+    /// The libraries named, as in the `lib` compiler option. Names are case-insensitive.
+    pub fn new<'n>(names: impl IntoIterator<Item = &'n str>) -> Libs {
+        let mut libs = Libs::none();
+        for name in names {
+            libs.add(name);
+        }
+        libs
+    }
+
+    /// No libraries at all, as with `noLib`.
+    pub fn none() -> Libs {
+        Libs {
+            names: BTreeSet::new(),
+        }
+    }
+
+    /// The libraries `tsc` includes by default for a `target` such as `ES2020`.
+    pub fn for_target(target: &str) -> Libs {
+        let target = target.to_ascii_lowercase();
+        let es = match target.as_str() {
+            "es3" | "es5" => "es5",
+            _ if REFERENCES.iter().any(|(name, _)| *name == target) => target.as_str(),
+            _ => "esnext",
+        };
+        let mut libs = Libs::new([es, "dom", "webworker.importscripts", "scripthost"]);
+        if es != "es5" {
+            libs.add("dom.iterable");
+        }
+        libs
+    }
+
+    /// Adds a library and the libraries it references.
+    pub fn add(&mut self, name: &str) {
+        let name = name.to_ascii_lowercase();
+        if !self.names.insert(name.clone()) {
+            return;
+        }
+        if let Some((_, references)) = REFERENCES.iter().find(|(lib, _)| *lib == name) {
+            for reference in *references {
+                self.add(reference);
+            }
+        }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+}
+
+/// Every standard library and the DOM, which is what bindings are generated against when no
+/// `tsconfig.json` says otherwise.
+impl Default for Libs {
+    fn default() -> Libs {
+        Libs::new(["esnext", "dom", "dom.iterable", "dom.asynciterable"])
+    }
+}
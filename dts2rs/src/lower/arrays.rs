@@ -0,0 +1,102 @@
+//! Lowering of array and tuple types.
+//!
+//! With [`ArrayMode::Array`], `string[]`, `ReadonlyArray<Foo>`, `Array<number>` and
+//! `[string, number]` are all `js_sys::Array`. With [`ArrayMode::Vec`], arrays are copied to
+//! and from `Vec<T>` and tuples to and from Rust tuples: `[string, number?, ...boolean[]]` is
+//! `(String, Option<f64>, Vec<bool>)`. The bindings take and return `js_sys::Array` and are
+//! called through typed wrappers that copy. With [`ArrayMode::Typed`], arrays are
+//! `JsArray<T>`, a typed view of the JavaScript array with the same kind of wrapper as
+//! generic types, and tuples are still copied.
+//!
+//! Copying doesn't check types: numbers, booleans and strings of another type in JavaScript are
+//! read as `NaN`, `false` and `""`. Results and properties copied that way are reported.
+
+use dts_parser::ast::{self, TypeKind};
+
+use super::typed_arrays::js_sys_array;
+use super::Lowerer;
+use crate::config::ArrayMode;
+use crate::ir::{self, RustType};
+
+/// The typed view of arrays, defined in the root module when used.
+pub(super) const JS_ARRAY: &str = "JsArray";
+
+impl<'a> Lowerer<'a> {
+    /// Maps `T[]` for element type `elem`.
+    pub(super) fn map_array(&mut self, elem: &ast::Type) -> RustType {
+        match self.config.arrays {
+            ArrayMode::Array => RustType::path("js_sys::Array"),
+            ArrayMode::Vec => RustType::Vec(Box::new(self.element_type(elem))),
+            ArrayMode::Typed => {
+                self.js_array = true;
+                RustType::Path(JS_ARRAY.to_string(), vec![self.type_arg(elem)])
+            }
+        }
+    }
+
+    /// Maps a tuple type. Tuples with rest elements anywhere but last stay `js_sys::Array`.
+    pub(super) fn map_tuple(&mut self, elems: &[ast::TupleElement]) -> RustType {
+        let (fixed, rest) = match elems.split_last() {
+            Some((last, fixed)) if last.rest => (fixed, Some(last)),
+            _ => (elems, None),
+        };
+        let copied = self.config.arrays != ArrayMode::Array
+            && !elems.is_empty()
+            && !fixed.iter().any(|e| e.rest);
+        if !copied {
+            return RustType::path("js_sys::Array");
+        }
+        let rest = match rest.map(|e| &e.ty.kind) {
+            Some(TypeKind::Array(elem)) => Some(Box::new(self.element_type(elem))),
+            Some(_) => return RustType::path("js_sys::Array"),
+            None => None,
+        };
+        let fixed = fixed
+            .iter()
+            .map(|e| {
+                let ty = self.element_type(&e.ty);
+                if e.optional {
+                    RustType::option(ty)
+                } else {
+                    ty
+                }
+            })
+            .collect();
+        RustType::Tuple(fixed, rest)
+    }
+
+    /// Warns if `mapped`, what `ty` is bound as where it is read from JavaScript, is copied
+    /// with defaults for values of another type.
+    pub(super) fn report_defaults(&mut self, ty: &ast::Type, mapped: &RustType) {
+        if mapped.copies_with_defaults() {
+            self.diags.warn(
+                self.file,
+                ty.span,
+                "values copied from this type aren't checked; numbers, booleans and strings of \
+                 another type are read as `NaN`, `false` and `\"\"`",
+            );
+        }
+    }
+
+    fn element_type(&mut self, ty: &ast::Type) -> RustType {
+        let mapped = self.map_type(ty);
+        js_sys_array(self.value_type(mapped))
+    }
+
+    /// Adds `JsArray<T>` to the root module if any array is bound as one.
+    pub(super) fn push_js_array(&self, out: &mut ir::Module) {
+        if !self.js_array {
+            return;
+        }
+        out.generics.push(ir::GenericType {
+            rust_name: JS_ARRAY.to_string(),
+            erased: "js_sys::Array".to_string(),
+            type_params: vec![ir::TypeParam {
+                name: "T".to_string(),
+                default: None,
+            }],
+            kind: ir::GenericKind::Array,
+            doc: Some("A JavaScript array of `T`.".to_string()),
+        });
+    }
+}
@@ -0,0 +1,175 @@
+//! Global types of the built-in libraries, which `js-sys` and `web-sys` already bind.
+//!
+//! The standard JavaScript classes map to `js-sys` by a table. Types of the `dom` and
+//! `webworker` libraries map to `web-sys`, which names them after their WebIDL interfaces in
+//! upper camel case: `HTMLElement` is `web_sys::HtmlElement` and `EXT_sRGB` is
+//! `web_sys::ExtSRgb`. Since only case and underscores differ, names are matched against the
+//! types web-sys has without regard to either. A type is only mapped when the library
+//! declaring it is available; see [`Libs`].
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use super::Lowerer;
+use crate::libs::Libs;
+
+/// Built-in JavaScript classes that `js-sys` binds, with the library declaring them.
+const JS_SYS_TYPES: &[(&str, &str, &str)] = &[
+    ("Array", "js_sys::Array", "es5"),
+    ("ReadonlyArray", "js_sys::Array", "es5"),
+    ("Promise", "js_sys::Promise", "es5"),
+    ("PromiseLike", "js_sys::Promise", "es5"),
+    ("Function", "js_sys::Function", "es5"),
+    ("Object", "js_sys::Object", "es5"),
+    ("Boolean", "js_sys::Boolean", "es5"),
+    ("Number", "js_sys::Number", "es5"),
+    ("String", "js_sys::JsString", "es5"),
+    ("Error", "js_sys::Error", "es5"),
+    ("EvalError", "js_sys::EvalError", "es5"),
+    ("RangeError", "js_sys::RangeError", "es5"),
+    ("ReferenceError", "js_sys::ReferenceError", "es5"),
+    ("SyntaxError", "js_sys::SyntaxError", "es5"),
+    ("TypeError", "js_sys::TypeError", "es5"),
+    ("URIError", "js_sys::UriError", "es5"),
+    ("Date", "js_sys::Date", "es5"),
+    ("RegExp", "js_sys::RegExp", "es5"),
+    ("ArrayBuffer", "js_sys::ArrayBuffer", "es5"),
+    ("DataView", "js_sys::DataView", "es5"),
+    ("Int8Array", "js_sys::Int8Array", "es5"),
+    ("Uint8Array", "js_sys::Uint8Array", "es5"),
+    ("Uint8ClampedArray", "js_sys::Uint8ClampedArray", "es5"),
+    ("Int16Array", "js_sys::Int16Array", "es5"),
+    ("Uint16Array", "js_sys::Uint16Array", "es5"),
+    ("Int32Array", "js_sys::Int32Array", "es5"),
+    ("Uint32Array", "js_sys::Uint32Array", "es5"),
+    ("Float32Array", "js_sys::Float32Array", "es5"),
+    ("Float64Array", "js_sys::Float64Array", "es5"),
+    ("Intl.Collator", "js_sys::Intl::Collator", "es5"),
+    ("Intl.DateTimeFormat", "js_sys::Intl::DateTimeFormat", "es5"),
+    ("Intl.NumberFormat", "js_sys::Intl::NumberFormat", "es5"),
+    ("Map", "js_sys::Map", "es2015.collection"),
+    ("ReadonlyMap", "js_sys::Map", "es2015.collection"),
+    ("Set", "js_sys::Set", "es2015.collection"),
+    ("ReadonlySet", "js_sys::Set", "es2015.collection"),
+    ("WeakMap", "js_sys::WeakMap", "es2015.collection"),
+    ("WeakSet", "js_sys::WeakSet", "es2015.collection"),
+    ("Iterator", "js_sys::Iterator", "es2015.iterable"),
+    ("IterableIterator", "js_sys::Iterator", "es2015.iterable"),
+    ("Generator", "js_sys::Generator", "es2015.generator"),
+    ("Proxy", "js_sys::Proxy", "es2015.proxy"),
+    ("Symbol", "js_sys::Symbol", "es2015.symbol"),
+    (
+        "SharedArrayBuffer",
+        "js_sys::SharedArrayBuffer",
+        "es2017.sharedmemory",
+    ),
+    (
+        "AsyncIterator",
+        "js_sys::AsyncIterator",
+        "es2018.asynciterable",
+    ),
+    (
+        "AsyncIterableIterator",
+        "js_sys::AsyncIterator",
+        "es2018.asynciterable",
+    ),
+    (
+        "AsyncGenerator",
+        "js_sys::AsyncGenerator",
+        "es2018.asyncgenerator",
+    ),
+    (
+        "Intl.PluralRules",
+        "js_sys::Intl::PluralRules",
+        "es2018.intl",
+    ),
+    ("BigInt", "js_sys::BigInt", "es2020.bigint"),
+    ("BigInt64Array", "js_sys::BigInt64Array", "es2020.bigint"),
+    ("BigUint64Array", "js_sys::BigUint64Array", "es2020.bigint"),
+    (
+        "Intl.DisplayNames",
+        "js_sys::Intl::DisplayNames",
+        "es2020.intl",
+    ),
+    ("Intl.Locale", "js_sys::Intl::Locale", "es2020.intl"),
+    (
+        "Intl.RelativeTimeFormat",
+        "js_sys::Intl::RelativeTimeFormat",
+        "es2020.intl",
+    ),
+    ("AggregateError", "js_sys::AggregateError", "es2021.promise"),
+    ("WeakRef", "js_sys::WeakRef", "es2021.weakref"),
+    (
+        "FinalizationRegistry",
+        "js_sys::FinalizationRegistry",
+        "es2021.weakref",
+    ),
+    ("Intl.ListFormat", "js_sys::Intl::ListFormat", "es2021.intl"),
+    ("Intl.Segmenter", "js_sys::Intl::Segmenter", "es2022.intl"),
+    ("Float16Array", "js_sys::Float16Array", "esnext.float16"),
+];
+
+/// The libraries whose types `web-sys` binds.
+const WEB_SYS_LIBS: &[&str] = &["dom", "webworker"];
+
+/// The `js-sys` binding of a built-in JavaScript class, if there is one among `libs`.
+fn js_sys_type(name: &str, libs: &Libs) -> Option<&'static str> {
+    JS_SYS_TYPES
+        .iter()
+        .find(|(ts, _, lib)| *ts == name && libs.contains(lib))
+        .map(|(_, path, _)| *path)
+}
+
+/// The name `web-sys` binds a DOM or web worker type under, which is also the name of the
+/// cargo feature enabling it, if `libs` declare such types.
+fn web_sys_type(name: &str, libs: &Libs) -> Option<&'static str> {
+    static TYPES: OnceLock<HashMap<String, &'static str>> = OnceLock::new();
+    if !WEB_SYS_LIBS.iter().any(|lib| libs.contains(lib)) {
+        return None;
+    }
+    let types = TYPES.get_or_init(|| {
+        include_str!("web_sys_types.txt")
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|name| (fold(name), name))
+            .collect()
+    });
+    types.get(&fold(name)).copied()
+}
+
+/// `HTMLElement` and `HtmlElement` -> `htmlelement`.
+fn fold(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_')
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+impl<'a> Lowerer<'a> {
+    /// The Rust type binding a global type that isn't declared in the project: the one
+    /// [`Config::globals`](crate::Config::globals) names, or else a `js-sys` or `web-sys`
+    /// type. Records the `web-sys` features needed.
+    pub(super) fn builtin_type(&mut self, name: &str) -> Option<String> {
+        let libs = &self.project.libs;
+        let path = match self.config.globals.get(name) {
+            Some(path) => path.clone(),
+            None => match js_sys_type(name, libs) {
+                Some(path) => path.to_string(),
+                None => format!("web_sys::{}", web_sys_type(name, libs)?),
+            },
+        };
+        if let Some(feature) = path.strip_prefix("web_sys::") {
+            self.web_sys_features.insert(feature.to_string());
+        }
+        Some(path)
+    }
+
+    /// Whether a global type that isn't declared in the project is built in, without
+    /// recording features.
+    pub(super) fn is_builtin(&self, name: &str) -> bool {
+        let libs = &self.project.libs;
+        self.config.globals.contains_key(name)
+            || js_sys_type(name, libs).is_some()
+            || web_sys_type(name, libs).is_some()
+    }
+}
@@ -0,0 +1,117 @@
+//! Lowering of callback parameters to typed closures.
+//!
+//! A parameter of function type, such as `cb: (err: Error | null, data: string) => void`,
+//! takes `&Closure<dyn FnMut(Option<js_sys::Error>, String)>`. The binding itself takes a
+//! `js_sys::Function` and is called through a typed wrapper, as generic functions are, along
+//! with a second wrapper `f_once` taking `impl FnOnce(...)` for callbacks that are called at
+//! most once, which frees the closure after the call. Callbacks that a closure can't stand
+//! for, with rest parameters or more than eight parameters, stay `js_sys::Function`.
+
+use dts_parser::ast::{self, TypeKind};
+
+use super::arrays::JS_ARRAY;
+use super::generics::without_type_params;
+use super::records::JS_RECORD;
+use super::types::is_nullish;
+use super::unions::MAX_ALIAS_DEPTH;
+use super::Lowerer;
+use crate::ir::RustType;
+
+/// The most parameters that wasm-bindgen closures take.
+const MAX_CLOSURE_PARAMS: usize = 8;
+
+impl<'a> Lowerer<'a> {
+    /// Maps the type of a parameter to a closure if it is a function type, possibly through
+    /// a type alias or with `| null` or `| undefined`.
+    pub(super) fn map_callback(&mut self, ty: &ast::Type) -> Option<RustType> {
+        self.callback(ty, 0)
+    }
+
+    /// Maps a parameter type to a closure through at most [`MAX_ALIAS_DEPTH`] aliases.
+    fn callback(&mut self, ty: &ast::Type, depth: usize) -> Option<RustType> {
+        match &ty.kind {
+            TypeKind::Function(f) if !f.is_abstract => self.map_closure(&f.sig),
+            TypeKind::Union(types) => {
+                let rest: Vec<&ast::Type> = types.iter().filter(|t| !is_nullish(t)).collect();
+                match rest.as_slice() {
+                    [ty] if rest.len() < types.len() => {
+                        self.callback(ty, depth).map(RustType::option)
+                    }
+                    _ => None,
+                }
+            }
+            TypeKind::Reference(r) if r.type_args.is_empty() => {
+                let name = r.name.to_dotted();
+                if self.map_type_param(&name).is_some() {
+                    return None;
+                }
+                let candidates = self.candidates(&name);
+                if Self::find(&self.types, &candidates).is_some() {
+                    return None;
+                }
+                let (scope, item) = Self::find(&self.aliases, &candidates)?.1.clone();
+                let ast::ItemKind::TypeAlias(decl) = &item.kind else {
+                    return None;
+                };
+                if !decl.type_params.is_empty() {
+                    return None;
+                }
+                // Aliases referring to themselves are bound as `JsValue`, and reported, when
+                // the parameter is mapped as a value.
+                if depth == MAX_ALIAS_DEPTH {
+                    return None;
+                }
+                self.in_scope(scope, |this| this.callback(&decl.ty, depth + 1))
+            }
+            _ => None,
+        }
+    }
+
+    fn map_closure(&mut self, sig: &ast::Signature) -> Option<RustType> {
+        let params: Vec<&ast::Param> = sig
+            .params
+            .iter()
+            .filter(|p| p.name.name() != "this")
+            .collect();
+        if params.len() > MAX_CLOSURE_PARAMS || params.iter().any(|p| p.rest) {
+            return None;
+        }
+        let (types, _) = self.with_type_params(&sig.type_params, |this| {
+            let params: Vec<RustType> = params
+                .iter()
+                .map(|p| {
+                    let ty =
+                        this.in_context(p.name.name(), |this| this.map_param_type(p.ty.as_ref()));
+                    let ty = if p.optional { RustType::option(ty) } else { ty };
+                    this.closure_type(ty)
+                })
+                .collect();
+            let ret = this
+                .map_return_type(sig.ret.as_ref())
+                .map(|ty| Box::new(this.closure_type(ty)));
+            (params, ret)
+        });
+        Some(RustType::Closure(types.0, types.1))
+    }
+
+    /// A type that a closure can take or return: generic types are passed untyped.
+    fn closure_type(&self, ty: RustType) -> RustType {
+        match without_type_params(ty) {
+            RustType::Path(path, _) if path == JS_ARRAY => RustType::path("js_sys::Array"),
+            RustType::Path(path, _) if path == JS_RECORD => RustType::path("js_sys::Object"),
+            RustType::Path(path, args) => {
+                let erased = self
+                    .generic_types
+                    .iter()
+                    .find(|(_, wrapper)| **wrapper == path)
+                    .and_then(|(name, _)| self.types.get(name));
+                match erased {
+                    Some(erased) => RustType::path(erased.clone()),
+                    None => RustType::Path(path, args),
+                }
+            }
+            RustType::Option(inner) => RustType::option(self.closure_type(*inner)),
+            ty => ty,
+        }
+    }
+}
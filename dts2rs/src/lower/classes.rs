@@ -0,0 +1,175 @@
+//! Lowering of class declarations.
+
+use dts_parser::ast::{self, Accessibility, ClassMemberKind};
+
+use super::interfaces::MemberTarget;
+use super::{merging, Lowerer};
+use crate::ir::{self, FunctionKind, RustType};
+
+impl<'a> Lowerer<'a> {
+    pub(super) fn lower_class(
+        &mut self,
+        decl: &ast::ClassDecl,
+        item: &ast::Item,
+        out: &mut ir::Module,
+    ) {
+        let Some(name) = &decl.name else {
+            self.diags
+                .warn(self.file, item.span, "anonymous default export skipped");
+            return;
+        };
+        let qualified = self.scope.qualify(&name.name);
+        if !self.is_primary(&qualified, item) {
+            return;
+        }
+        let start = out.externs.len();
+        let rust_name = self.types[&qualified].clone();
+        let extends = self.ancestors(&qualified, name.span);
+        let ((), type_params) = self.with_type_params(&decl.type_params, |this| {
+            let (doc, instance) =
+                this.generic_wrapper(&qualified, &decl.type_params, item.doc.as_deref());
+            out.externs.push(ir::ExternItem::Type(ir::TypeDecl {
+                rust_name: rust_name.clone(),
+                js_name: this.js_name(&name.name),
+                extends,
+                is_type_of: None,
+                doc,
+            }));
+            this.this_type = Some(rust_name.clone());
+            let mut target = MemberTarget {
+                this: &rust_name,
+                js_this: &qualified,
+                instance: instance.unwrap_or_else(|| RustType::path(rust_name.clone())),
+                structural: false,
+                is_static: false,
+            };
+            let mut has_constructor = false;
+            this.context.push(name.name.clone());
+            for member in &decl.members {
+                if let ClassMemberKind::Constructor(_) = member.kind {
+                    has_constructor = true;
+                }
+                if matches!(
+                    member.accessibility,
+                    Some(Accessibility::Private | Accessibility::Protected)
+                ) {
+                    continue;
+                }
+                target.is_static = member.is_static;
+                this.lower_class_member(decl, member, &mut target, out);
+            }
+            // Without a declared constructor, a base class gets the implicit no-argument one.
+            // Derived classes inherit their parent's constructor signatures instead.
+            if !has_constructor && !decl.is_abstract && decl.extends.is_none() {
+                let no_args = ast::Signature {
+                    type_params: Vec::new(),
+                    params: Vec::new(),
+                    ret: None,
+                    span: name.span,
+                };
+                this.push_constructor(&mut target, &no_args, None, out);
+            }
+            this.context.pop();
+            target.is_static = false;
+            this.lower_merged_members(&qualified, item, &mut target, out);
+            this.this_type = None;
+        });
+        merging::dedup_members(&mut out.externs, start);
+        self.push_generic(&qualified, type_params, item.doc.as_deref(), out);
+    }
+
+    fn lower_class_member(
+        &mut self,
+        class: &ast::ClassDecl,
+        member: &ast::ClassMember,
+        target: &mut MemberTarget,
+        out: &mut ir::Module,
+    ) {
+        let doc = member.doc.as_deref();
+        match &member.kind {
+            ClassMemberKind::Constructor(sig) => {
+                if !class.is_abstract {
+                    self.push_constructor(target, sig, doc, out);
+                }
+            }
+            ClassMemberKind::Property(prop) => {
+                if matches!(prop.name, ast::PropName::Private(_)) {
+                    return;
+                }
+                let Some(js_name) = self.prop_js_name(&prop.name) else {
+                    return;
+                };
+                let ty = self.in_context(js_name, |this| this.map_param_type(prop.ty.as_ref()));
+                if let Some(prop_ty) = &prop.ty {
+                    self.report_defaults(prop_ty, &ty);
+                }
+                let ty = if prop.optional {
+                    RustType::option(ty)
+                } else {
+                    ty
+                };
+                self.push_getter(target, js_name, ty.clone(), doc, out);
+                if !prop.readonly {
+                    self.push_setter(target, js_name, ty, doc, out);
+                }
+            }
+            ClassMemberKind::Method(method) => {
+                if matches!(method.name, ast::PropName::Private(_)) {
+                    return;
+                }
+                if let Some(js_name) = self.prop_js_name(&method.name) {
+                    self.push_method(target, js_name, &method.sig, doc, out);
+                }
+            }
+            ClassMemberKind::Getter(getter) => {
+                if let Some(js_name) = self.prop_js_name(&getter.name) {
+                    let ty =
+                        self.in_context(js_name, |this| this.map_param_type(getter.ty.as_ref()));
+                    self.push_getter(target, js_name, ty, doc, out);
+                }
+            }
+            ClassMemberKind::Setter(setter) => {
+                if let Some(js_name) = self.prop_js_name(&setter.name) {
+                    let ty = self.in_context(js_name, |this| {
+                        this.map_param_type(setter.param.ty.as_ref())
+                    });
+                    self.push_setter(target, js_name, ty, doc, out);
+                }
+            }
+            ClassMemberKind::Index(sig) => {
+                self.push_index_accessors(target, sig, member.span, doc, out);
+            }
+            ClassMemberKind::StaticBlock => {}
+        }
+    }
+
+    fn push_constructor(
+        &mut self,
+        target: &mut MemberTarget,
+        sig: &ast::Signature,
+        doc: Option<&str>,
+        out: &mut ir::Module,
+    ) {
+        let (params, variadic) =
+            self.in_context("constructor", |this| this.lower_params(&sig.params));
+        out.externs.push(ir::ExternItem::Function(ir::Function {
+            kind: FunctionKind::Constructor {
+                class: target.this.to_string(),
+            },
+            rust_name: "new".to_string(),
+            key: format!(
+                "{}.constructor({})",
+                target.js_this,
+                self.signature_key(&sig.params)
+            ),
+            js_name: target.this.to_string(),
+            type_params: Vec::new(),
+            params,
+            ret: Some(target.instance.clone()),
+            variadic,
+            resolves: None,
+            async_name: None,
+            doc: doc.map(str::to_string),
+        }));
+    }
+}
@@ -0,0 +1,922 @@
+//! Evaluation of mapped, conditional and indexed access types.
+//!
+//! Libraries compute types from others with mapped types such as
+//! `{ [K in keyof T]: () => T[K] }`, conditional types such as
+//! `T extends string ? "text" : "other"`, lookups such as `Config["mode"]` and `keyof`. Where
+//! every type argument is known they are evaluated to the types they stand for: a mapped type
+//! to an object type with the mapped members, a conditional type to the branch its check
+//! selects, with the types that its `infer` clauses matched, `T[K]` to the type of property
+//! `K` of `T` and `keyof T` to the union of the names of `T`'s properties. `Exclude`,
+//! `Extract` and `NonNullable` filter unions the same way.
+//!
+//! A mapped type written inline is bound like an object literal type with its members.
+//!
+//! Evaluation is bounded by [`MAX_DEPTH`], and so is mapping the types that evaluated types
+//! stand for, which may evaluate to themselves again. A type that can't be evaluated, because
+//! a check can't be decided, a lookup has no answer or it depends on the type parameters of a
+//! generic function or type, is bound as `JsValue` with a warning.
+
+use std::collections::HashMap;
+
+use dts_parser::ast::{self, KeywordType, LiteralType, MemberKind, TypeKind};
+use dts_parser::Span;
+
+use super::evaluate::{any, member_name, substitute, type_arg_map, Members, MAX_DEPTH};
+use super::namespaces::Scope;
+use super::types::is_nullish;
+use super::Lowerer;
+use crate::ir::RustType;
+
+/// The types that the `infer` clauses of a conditional type matched, by name.
+type Bindings = HashMap<String, ast::Type>;
+
+impl<'a> Lowerer<'a> {
+    /// Maps a mapped, conditional, indexed access or `keyof` type, or `Exclude`, `Extract` or
+    /// `NonNullable`, by evaluating it. A mapped type with evaluable members is bound like
+    /// an object literal type with those members.
+    pub(super) fn map_computed(&mut self, ty: &ast::Type) -> RustType {
+        if let TypeKind::Mapped(mapped) = &ty.kind {
+            // `{ [K in string]: T }` is `Record<string, T>`.
+            if is_record(mapped) {
+                return self.map_record(&mapped.constraint, mapped.ty.as_ref());
+            }
+            // Members referring to type parameters can't be bound on a type of their own.
+            if !mentions(ty, &self.type_params) {
+                if let Some(members) = self.members_of(ty, 0) {
+                    return self.map_evaluated_object(ty, &members);
+                }
+            }
+        } else if let Some((scope, reduced)) = self.reduce(ty, 0) {
+            // Types evaluating to types that evaluate to them again, as `T["a"]` does for a
+            // property `a: T["a"]` of `T`.
+            if self.reductions > MAX_DEPTH {
+                self.diags.warn(
+                    self.file,
+                    ty.span,
+                    format!(
+                        "this {} refers to itself too deeply to evaluate; it is bound as `JsValue`",
+                        describe(ty)
+                    ),
+                );
+                return RustType::JsValue;
+            }
+            self.reductions += 1;
+            let mapped = self.in_scope(scope, |this| this.map_type(&reduced));
+            self.reductions -= 1;
+            return mapped;
+        }
+        self.cannot_evaluate(ty);
+        RustType::JsValue
+    }
+
+    /// Maps an instance of generic type alias `qualified` by evaluating its type with the
+    /// instance's type arguments.
+    pub(super) fn map_alias_instance(
+        &mut self,
+        qualified: &str,
+        type_args: &[ast::Type],
+    ) -> RustType {
+        let (scope, item) = self.aliases[qualified].clone();
+        let ast::ItemKind::TypeAlias(decl) = &item.kind else {
+            return RustType::JsValue;
+        };
+        // Aliases whose instances refer to ever larger instances of themselves.
+        if self.instances > MAX_DEPTH {
+            self.in_scope(scope, |this| {
+                this.diags.warn(
+                    this.file,
+                    decl.name.span,
+                    format!(
+                        "instances of `{}` nest too deeply to evaluate; they are bound as `JsValue`",
+                        decl.name.name
+                    ),
+                )
+            });
+            return RustType::JsValue;
+        }
+        let args = type_arg_map(&decl.type_params, type_args);
+        let args = self.union_args(&decl.ty, args, 0);
+        let ty = substitute(&decl.ty, &args);
+        self.instances += 1;
+        // Instances are bounded by their own count.
+        let reductions = std::mem::take(&mut self.reductions);
+        let this_type = self.this_type.take();
+        let mapped = self.in_scope(scope, |this| this.map_type(&ty));
+        self.this_type = this_type;
+        self.reductions = reductions;
+        self.instances -= 1;
+        mapped
+    }
+
+    /// Warns that `ty` can't be evaluated, unless it refers to the type parameters of a type
+    /// alias, which only its instances give arguments to.
+    pub(super) fn cannot_evaluate(&mut self, ty: &ast::Type) {
+        if mentions(ty, &self.alias_params) {
+            return;
+        }
+        let message = match self
+            .type_params
+            .iter()
+            .find(|p| mentions(ty, std::slice::from_ref(p)))
+        {
+            Some(param) => format!(
+                "can't evaluate this {} for every `{}`; it is bound as `JsValue`",
+                describe(ty),
+                param
+            ),
+            None => format!(
+                "can't evaluate this {}; it is bound as `JsValue`",
+                describe(ty)
+            ),
+        };
+        self.diags.warn(self.file, ty.span, message);
+    }
+
+    /// Whether alias type `ty` evaluates to an object type of its own, rather than naming
+    /// another type. Object literal types are only their own once `evaluated`; those written
+    /// out are aliases of anonymous types.
+    pub(super) fn is_computed_object(
+        &mut self,
+        ty: &ast::Type,
+        evaluated: bool,
+        depth: usize,
+    ) -> bool {
+        if depth > MAX_DEPTH {
+            return false;
+        }
+        match &ty.kind {
+            TypeKind::Mapped(mapped) => !is_record(mapped),
+            TypeKind::Object(members) => {
+                evaluated
+                    && !matches!(
+                        members.as_slice(),
+                        [ast::Member {
+                            kind: MemberKind::Index(_),
+                            ..
+                        }]
+                    )
+            }
+            TypeKind::Conditional(_) | TypeKind::Indexed { .. } => match self.reduce(ty, depth + 1)
+            {
+                Some((scope, reduced)) => self.in_scope(scope, |this| {
+                    this.is_computed_object(&reduced, true, depth + 1)
+                }),
+                None => false,
+            },
+            TypeKind::Reference(r) => {
+                let name = r.name.to_dotted();
+                if super::evaluate::OBJECT_UTILITIES.contains(&name.as_str())
+                    && !self.is_declared(&name)
+                {
+                    return true;
+                }
+                if r.type_args.is_empty() {
+                    return false;
+                }
+                match self.alias_type(r, depth + 1) {
+                    Some((scope, ty)) => {
+                        self.in_scope(scope, |this| this.is_computed_object(&ty, true, depth + 1))
+                    }
+                    None => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether `name` is declared in the project, which shadows the utility types.
+    pub(super) fn is_declared(&self, name: &str) -> bool {
+        let candidates = self.candidates(name);
+        Self::find(&self.types, &candidates).is_some()
+            || Self::find(&self.generic_types, &candidates).is_some()
+            || Self::find(&self.aliases, &candidates).is_some()
+    }
+
+    /// Evaluates a conditional, indexed access or `keyof` type, or `Exclude`, `Extract` or
+    /// `NonNullable`, to the type it stands for, with the scope that is written in. Other
+    /// types stand for themselves.
+    pub(super) fn reduce(&mut self, ty: &ast::Type, depth: usize) -> Option<(Scope, ast::Type)> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        match &ty.kind {
+            TypeKind::Conditional(c) => {
+                let mut bindings = Bindings::new();
+                let branch = if self.extends(&c.check, &c.extends, &mut bindings, depth + 1)? {
+                    substitute(&c.true_ty, &bindings)
+                } else {
+                    c.false_ty.clone()
+                };
+                self.reduce(&branch, depth + 1)
+            }
+            TypeKind::Indexed { object, index } => self.indexed(object, index, depth + 1),
+            TypeKind::Operator(ast::TypeOperator::Keyof, inner) => {
+                let mut keys = Vec::new();
+                for (_, m) in self.members_of(inner, depth + 1)? {
+                    match &m.kind {
+                        // Any string is a key of a type with a string index signature, and
+                        // so is any number.
+                        MemberKind::Index(sig) => {
+                            let key = sig.params.first()?.ty.clone()?;
+                            if matches!(key.kind, TypeKind::Keyword(KeywordType::String)) {
+                                keys.push(ast::Type {
+                                    kind: TypeKind::Keyword(KeywordType::Number),
+                                    span: key.span,
+                                });
+                            }
+                            keys.push(key);
+                        }
+                        _ => keys.extend(member_name(&m).map(|name| literal(name, ty.span))),
+                    }
+                }
+                Some((self.scope.clone(), union_of(keys, ty.span)))
+            }
+            TypeKind::Reference(r) if !self.is_declared(&r.name.to_dotted()) => {
+                let name = r.name.to_dotted();
+                let kept = match (name.as_str(), r.type_args.as_slice()) {
+                    ("Exclude" | "Extract", [t, u]) => {
+                        let mut kept = Vec::new();
+                        for member in self.union_members(t, depth + 1)? {
+                            let matches =
+                                self.extends(&member, u, &mut Bindings::new(), depth + 1)?;
+                            if matches == (name == "Extract") {
+                                kept.push(member);
+                            }
+                        }
+                        kept
+                    }
+                    ("NonNullable", [t]) => self
+                        .union_members(t, depth + 1)?
+                        .into_iter()
+                        .filter(|m| !is_nullish(m))
+                        .collect(),
+                    _ => return Some((self.scope.clone(), ty.clone())),
+                };
+                Some((self.scope.clone(), union_of(kept, ty.span)))
+            }
+            _ => Some((self.scope.clone(), ty.clone())),
+        }
+    }
+
+    /// `args`, with the arguments given for type parameters that conditional types in `ty`
+    /// check replaced by the unions they evaluate to, such as those of aliases of unions and
+    /// of `keyof` types, for the conditional types to distribute over.
+    pub(super) fn union_args(
+        &mut self,
+        ty: &ast::Type,
+        mut args: Bindings,
+        depth: usize,
+    ) -> Bindings {
+        let mut checked = Vec::new();
+        checked_params(ty, &mut checked);
+        for name in checked {
+            let Some(arg) = args.get(&name).cloned() else {
+                continue;
+            };
+            if matches!(arg.kind, TypeKind::Union(_)) {
+                continue;
+            }
+            if let Some(members) = self.union_members(&arg, depth + 1) {
+                if members.len() > 1 {
+                    args.insert(name, union_of(members, arg.span));
+                }
+            }
+        }
+        args
+    }
+
+    /// The members of union `ty`, or `ty` itself if it isn't one.
+    fn union_members(&mut self, ty: &ast::Type, depth: usize) -> Option<Vec<ast::Type>> {
+        let ty = self.resolved(ty, depth + 1)?;
+        match ty.kind {
+            TypeKind::Union(types) => {
+                let mut out = Vec::new();
+                for ty in &types {
+                    out.extend(self.union_members(ty, depth + 1)?);
+                }
+                Some(out)
+            }
+            _ => Some(vec![ty]),
+        }
+    }
+
+    /// Evaluates `object[index]`.
+    fn indexed(
+        &mut self,
+        object: &ast::Type,
+        index: &ast::Type,
+        depth: usize,
+    ) -> Option<(Scope, ast::Type)> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let is_number = matches!(index.kind, TypeKind::Keyword(KeywordType::Number));
+        match &object.kind {
+            TypeKind::Array(elem) if is_number => {
+                return Some((self.scope.clone(), (**elem).clone()))
+            }
+            TypeKind::Tuple(elems) if elems.iter().all(|e| !e.rest) => {
+                let ty = match &index.kind {
+                    TypeKind::Literal(LiteralType::Num(n)) => {
+                        elems.get(n.parse::<usize>().ok()?)?.ty.clone()
+                    }
+                    _ if is_number => {
+                        union_of(elems.iter().map(|e| e.ty.clone()).collect(), object.span)
+                    }
+                    _ => return None,
+                };
+                return Some((self.scope.clone(), ty));
+            }
+            TypeKind::Reference(r)
+                if is_number || matches!(index.kind, TypeKind::Literal(LiteralType::Num(_))) =>
+            {
+                if let (Some(elem), false) = (array_elem(r), self.is_declared(&r.name.to_dotted()))
+                {
+                    return Some((self.scope.clone(), elem.clone()));
+                }
+                if let Some((scope, ty)) = self.alias_type(r, depth + 1) {
+                    if matches!(ty.kind, TypeKind::Array(_) | TypeKind::Tuple(_)) {
+                        return self.in_scope(scope, |this| this.indexed(&ty, index, depth + 1));
+                    }
+                }
+            }
+            _ => {}
+        }
+        let keys = self.keys_of(index, depth + 1)?;
+        let members = self.members_of(object, depth + 1)?;
+        let mut found: Vec<(Scope, ast::Type)> = Vec::new();
+        for key in &keys {
+            let (scope, m) = members
+                .iter()
+                .find(|(_, m)| member_name(m) == Some(key.as_str()))?;
+            found.push((scope.clone(), member_type(m)?));
+        }
+        let scope = found.first()?.0.clone();
+        // The types of a union must be written in one scope.
+        if found.iter().any(|(s, _)| *s != scope) {
+            return None;
+        }
+        let types = found.into_iter().map(|(_, ty)| ty).collect();
+        Some((scope, union_of(types, index.span)))
+    }
+
+    /// `ty` with aliases, conditional types and lookups evaluated, as far as needed to
+    /// compare it, or `None` if it is a type parameter or an unknown type.
+    fn resolved(&mut self, ty: &ast::Type, depth: usize) -> Option<ast::Type> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let (_, ty) = self.reduce(ty, depth + 1)?;
+        match &ty.kind {
+            TypeKind::Reference(r) => {
+                let name = r.name.to_dotted();
+                if let [part] = r.name.parts.as_slice() {
+                    if self.type_params.contains(&part.name)
+                        || self.alias_params.contains(&part.name)
+                    {
+                        return None;
+                    }
+                }
+                if self.is_declared(&name) {
+                    if let Some((_, ty)) = self.alias_type(r, depth + 1) {
+                        return self.resolved(&ty, depth + 1);
+                    }
+                } else if let Some(elem) = array_elem(r) {
+                    return Some(ast::Type {
+                        kind: TypeKind::Array(Box::new(elem.clone())),
+                        span: ty.span,
+                    });
+                } else if !self.is_builtin(&name) {
+                    return None;
+                }
+                Some(ty)
+            }
+            TypeKind::Query(_) => {
+                let (_, sig) = self.signature_of(&ty, depth + 1)?;
+                Some(ast::Type {
+                    kind: TypeKind::Function(Box::new(ast::FunctionType {
+                        is_abstract: false,
+                        sig,
+                    })),
+                    span: ty.span,
+                })
+            }
+            TypeKind::Operator(ast::TypeOperator::Readonly, inner) => {
+                self.resolved(inner, depth + 1)
+            }
+            _ => Some(ty),
+        }
+    }
+
+    /// Whether `a` is assignable to `b`, binding the `infer` clauses in `b`, or `None` if
+    /// that can't be decided.
+    pub(super) fn extends(
+        &mut self,
+        a: &ast::Type,
+        b: &ast::Type,
+        bindings: &mut Bindings,
+        depth: usize,
+    ) -> Option<bool> {
+        use KeywordType as K;
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        if let TypeKind::Infer { name, .. } = &b.kind {
+            bindings.insert(name.name.clone(), a.clone());
+            return Some(true);
+        }
+        let b = self.resolved(b, depth + 1)?;
+        if matches!(b.kind, TypeKind::Keyword(K::Any | K::Unknown)) {
+            return Some(true);
+        }
+        let a = self.resolved(a, depth + 1)?;
+        let primitive = |t: &ast::Type| {
+            matches!(
+                t.kind,
+                TypeKind::Keyword(_) | TypeKind::Literal(_) | TypeKind::Template { .. }
+            )
+        };
+        match (&a.kind, &b.kind) {
+            (TypeKind::Keyword(K::Never), _) => Some(true),
+            // `any` selects both branches.
+            (TypeKind::Keyword(K::Any), _) => None,
+            (TypeKind::Union(types), _) => {
+                for ty in types {
+                    if !self.extends(ty, &b, bindings, depth + 1)? {
+                        return Some(false);
+                    }
+                }
+                Some(true)
+            }
+            (_, TypeKind::Union(types)) => {
+                let mut undecided = false;
+                for ty in types {
+                    match self.extends(&a, ty, bindings, depth + 1) {
+                        Some(true) => return Some(true),
+                        Some(false) => {}
+                        None => undecided = true,
+                    }
+                }
+                if undecided {
+                    None
+                } else {
+                    Some(false)
+                }
+            }
+            (TypeKind::Keyword(x), TypeKind::Keyword(y)) => {
+                Some(x == y || (*x == K::Undefined && *y == K::Void))
+            }
+            (TypeKind::Literal(x), TypeKind::Literal(y)) => Some(x == y),
+            (TypeKind::Literal(x), TypeKind::Keyword(y)) => Some(matches!(
+                (x, y),
+                (LiteralType::Str(_), K::String)
+                    | (LiteralType::Num(_), K::Number)
+                    | (LiteralType::Bool(_), K::Boolean)
+                    | (LiteralType::BigInt(_), K::BigInt)
+            )),
+            (TypeKind::Template { .. }, TypeKind::Keyword(K::String)) => Some(true),
+            // Primitives have the members of their wrapper objects.
+            (_, TypeKind::Object(_)) if primitive(&a) => None,
+            _ if primitive(&a) => Some(false),
+            (_, TypeKind::Keyword(K::Object)) => Some(true),
+            _ if primitive(&b) => Some(false),
+            (TypeKind::Array(x), TypeKind::Array(y)) => self.extends(x, y, bindings, depth + 1),
+            (TypeKind::Tuple(elems), TypeKind::Array(y)) => {
+                for elem in elems {
+                    if !self.extends(&elem.ty, y, bindings, depth + 1)? {
+                        return Some(false);
+                    }
+                }
+                Some(true)
+            }
+            (TypeKind::Tuple(xs), TypeKind::Tuple(ys)) => {
+                if xs.iter().chain(ys).any(|e| e.rest || e.optional) {
+                    return None;
+                }
+                if xs.len() != ys.len() {
+                    return Some(false);
+                }
+                for (x, y) in xs.iter().zip(ys) {
+                    if !self.extends(&x.ty, &y.ty, bindings, depth + 1)? {
+                        return Some(false);
+                    }
+                }
+                Some(true)
+            }
+            (TypeKind::Array(_), TypeKind::Tuple(_)) => Some(false),
+            (TypeKind::Function(f), TypeKind::Function(g)) => {
+                self.signature_extends(&f.sig, &g.sig, bindings, depth + 1)
+            }
+            (TypeKind::Reference(x), TypeKind::Reference(y)) => {
+                self.reference_extends(&a, x, y, bindings, depth + 1)
+            }
+            (_, TypeKind::Object(members)) => {
+                let source = self.members_of(&a, depth + 1)?;
+                self.members_extend(&source, members, bindings, depth + 1)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether a function with signature `f` is assignable to one with signature `g`. Only
+    /// return types are compared, and the parameters bind `infer` clauses in `g`:
+    /// `(...args: infer P) => void` binds `P` to the tuple of `f`'s parameter types.
+    fn signature_extends(
+        &mut self,
+        f: &ast::Signature,
+        g: &ast::Signature,
+        bindings: &mut Bindings,
+        depth: usize,
+    ) -> Option<bool> {
+        let params: Vec<&ast::Param> = f
+            .params
+            .iter()
+            .filter(|p| p.name.name() != "this")
+            .collect();
+        for (i, p) in g.params.iter().enumerate() {
+            let Some(TypeKind::Infer { name, .. }) = p.ty.as_ref().map(|t| &t.kind) else {
+                continue;
+            };
+            let ty = if p.rest {
+                let elems = params[i.min(params.len())..]
+                    .iter()
+                    .map(|p| ast::TupleElement {
+                        name: None,
+                        ty: p.ty.clone().unwrap_or_else(|| any(p.span)),
+                        optional: p.optional,
+                        rest: p.rest,
+                        span: p.span,
+                    })
+                    .collect();
+                ast::Type {
+                    kind: TypeKind::Tuple(elems),
+                    span: p.span,
+                }
+            } else {
+                match params.get(i) {
+                    Some(fp) => fp.ty.clone().unwrap_or_else(|| any(fp.span)),
+                    None => any(p.span),
+                }
+            };
+            bindings.insert(name.name.clone(), ty);
+        }
+        match (&f.ret, &g.ret) {
+            (_, None) => Some(true),
+            (Some(x), Some(y)) => self.extends(x, y, bindings, depth + 1),
+            (None, Some(y)) => self.extends(&any(y.span), y, bindings, depth + 1),
+        }
+    }
+
+    /// Whether `a`, a reference `x`, is assignable to reference `y`: if they are instances
+    /// of one type with assignable type arguments, if `y` is a declared ancestor of `x` or if
+    /// `a` has `y`'s members.
+    fn reference_extends(
+        &mut self,
+        a: &ast::Type,
+        x: &ast::TypeRef,
+        y: &ast::TypeRef,
+        bindings: &mut Bindings,
+        depth: usize,
+    ) -> Option<bool> {
+        let qualify = |this: &Self, r: &ast::TypeRef| {
+            let name = r.name.to_dotted();
+            match this.lookup(&this.declarations, &name) {
+                Some((qualified, _)) => (qualified.clone(), true),
+                None => (name, false),
+            }
+        };
+        let (x_name, x_declared) = qualify(self, x);
+        let (y_name, y_declared) = qualify(self, y);
+        if x_name == y_name {
+            if x.type_args.len() != y.type_args.len() {
+                return None;
+            }
+            for (x, y) in x.type_args.iter().zip(&y.type_args) {
+                if !self.extends(x, y, bindings, depth + 1)? {
+                    return Some(false);
+                }
+            }
+            return Some(true);
+        }
+        if !x_declared || !y_declared {
+            return None;
+        }
+        let mut queue = vec![x_name];
+        let mut seen = Vec::new();
+        while let Some(name) = queue.pop() {
+            if name == y_name {
+                return Some(true);
+            }
+            if !seen.contains(&name) {
+                queue.extend(self.parents.get(&name).cloned().unwrap_or_default());
+                seen.push(name);
+            }
+        }
+        let source = self.members_of(a, depth + 1)?;
+        let target = self.declared_members(&y_name, &y.type_args, depth + 1)?;
+        let target: Vec<ast::Member> = target.into_iter().map(|(_, m)| m).collect();
+        self.members_extend(&source, &target, bindings, depth + 1)
+    }
+
+    /// Whether an object type with members `source` has the members `target`, with
+    /// assignable types.
+    fn members_extend(
+        &mut self,
+        source: &Members,
+        target: &[ast::Member],
+        bindings: &mut Bindings,
+        depth: usize,
+    ) -> Option<bool> {
+        for m in target {
+            let name = member_name(m)?;
+            let Some((_, found)) = source.iter().find(|(_, s)| member_name(s) == Some(name)) else {
+                if is_optional(m) {
+                    continue;
+                }
+                return Some(false);
+            };
+            let (Some(x), Some(y)) = (member_type(found), member_type(m)) else {
+                return None;
+            };
+            if !self.extends(&x, &y, bindings, depth + 1)? {
+                return Some(false);
+            }
+        }
+        Some(true)
+    }
+
+    /// The members of mapped type `mapped`: a property for each key of its constraint, named
+    /// by its `as` clause. Mapping `keyof T` keeps the modifiers of `T`'s properties.
+    pub(super) fn mapped_members(
+        &mut self,
+        mapped: &ast::MappedType,
+        span: Span,
+        depth: usize,
+    ) -> Option<Members> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let (keys, source) = match &mapped.constraint.kind {
+            TypeKind::Operator(ast::TypeOperator::Keyof, source) => {
+                let members = self.members_of(source, depth + 1)?;
+                let keys = members
+                    .iter()
+                    .filter_map(|(_, m)| member_name(m).map(str::to_string))
+                    .collect();
+                (keys, members)
+            }
+            _ => (self.keys_of(&mapped.constraint, depth + 1)?, Vec::new()),
+        };
+        let mut out: Members = Vec::new();
+        let mut seen: Vec<String> = Vec::new();
+        for key in keys {
+            if seen.contains(&key) {
+                continue;
+            }
+            seen.push(key.clone());
+            let args = HashMap::from([(mapped.param.name.clone(), literal(&key, span))]);
+            let name = match &mapped.name_type {
+                Some(name_type) => {
+                    match self
+                        .keys_of(&substitute(name_type, &args), depth + 1)?
+                        .as_slice()
+                    {
+                        // `as never` filters the key out.
+                        [] => continue,
+                        [name] => name.clone(),
+                        _ => return None,
+                    }
+                }
+                None => key.clone(),
+            };
+            let found = source
+                .iter()
+                .find(|(_, m)| member_name(m) == Some(key.as_str()))
+                .map(|(_, m)| m);
+            let modifier = |modifier: Option<ast::MappedModifier>, kept: bool| match modifier {
+                Some(ast::MappedModifier::Add) => true,
+                Some(ast::MappedModifier::Remove) => false,
+                None => kept,
+            };
+            let optional = modifier(mapped.optional, found.is_some_and(is_optional));
+            let readonly = modifier(mapped.readonly, found.is_some_and(is_readonly));
+            out.push((
+                self.scope.clone(),
+                ast::Member {
+                    kind: MemberKind::Property(ast::PropertySig {
+                        name: ast::PropName::Str(name, span),
+                        optional,
+                        readonly,
+                        ty: Some(match &mapped.ty {
+                            Some(ty) => substitute(ty, &args),
+                            None => any(span),
+                        }),
+                    }),
+                    doc: found.and_then(|m| m.doc.clone()),
+                    span,
+                },
+            ));
+        }
+        Some(out)
+    }
+}
+
+/// What kind of evaluated type `ty` is, for diagnostics.
+fn describe(ty: &ast::Type) -> &'static str {
+    match &ty.kind {
+        TypeKind::Mapped(_) => "mapped type",
+        TypeKind::Conditional(_) => "conditional type",
+        TypeKind::Indexed { .. } => "indexed access type",
+        TypeKind::Reference(_) => "utility type",
+        _ => "`keyof` type",
+    }
+}
+
+/// Whether mapped type `mapped` maps every string or number, like a record.
+fn is_record(mapped: &ast::MappedType) -> bool {
+    mapped.name_type.is_none()
+        && matches!(
+            mapped.constraint.kind,
+            TypeKind::Keyword(KeywordType::String | KeywordType::Number)
+        )
+}
+
+/// Whether `ty` is a type that [`Lowerer::reduce`] evaluates.
+pub(super) fn is_computed(ty: &ast::Type) -> bool {
+    matches!(
+        ty.kind,
+        TypeKind::Conditional(_)
+            | TypeKind::Indexed { .. }
+            | TypeKind::Operator(ast::TypeOperator::Keyof, _)
+    )
+}
+
+/// The element type of `Array<T>` or `ReadonlyArray<T>`.
+fn array_elem(r: &ast::TypeRef) -> Option<&ast::Type> {
+    match (r.name.to_dotted().as_str(), r.type_args.as_slice()) {
+        ("Array" | "ReadonlyArray", [elem]) => Some(elem),
+        _ => None,
+    }
+}
+
+/// String literal type `s`.
+fn literal(s: &str, span: Span) -> ast::Type {
+    ast::Type {
+        kind: TypeKind::Literal(LiteralType::Str(s.to_string())),
+        span,
+    }
+}
+
+/// Adds the type parameters that conditional types in `ty` check to `out`.
+fn checked_params(ty: &ast::Type, out: &mut Vec<String>) {
+    if let TypeKind::Conditional(c) = &ty.kind {
+        if let TypeKind::Reference(r) = &c.check.kind {
+            if let ([part], true) = (r.name.parts.as_slice(), r.type_args.is_empty()) {
+                if !out.contains(&part.name) {
+                    out.push(part.name.clone());
+                }
+            }
+        }
+    }
+    let mut walk = |t: &ast::Type| checked_params(t, out);
+    match &ty.kind {
+        TypeKind::Conditional(c) => {
+            walk(&c.true_ty);
+            walk(&c.false_ty);
+        }
+        TypeKind::Union(types) | TypeKind::Intersection(types) => types.iter().for_each(walk),
+        TypeKind::Array(elem) | TypeKind::Operator(_, elem) => walk(elem),
+        TypeKind::Tuple(elems) => elems.iter().for_each(|e| walk(&e.ty)),
+        TypeKind::Reference(r) => r.type_args.iter().for_each(walk),
+        TypeKind::Indexed { object, index } => {
+            walk(object);
+            walk(index);
+        }
+        TypeKind::Mapped(mapped) => mapped.ty.iter().for_each(walk),
+        TypeKind::Object(members) => members
+            .iter()
+            .filter_map(member_type)
+            .for_each(|t| walk(&t)),
+        TypeKind::Function(f) => {
+            f.sig.ret.iter().for_each(&mut walk);
+            f.sig
+                .params
+                .iter()
+                .filter_map(|p| p.ty.as_ref())
+                .for_each(walk);
+        }
+        _ => {}
+    }
+}
+
+/// The union of `types`: `never` if there are none.
+fn union_of(mut types: Vec<ast::Type>, span: Span) -> ast::Type {
+    match types.len() {
+        0 => ast::Type {
+            kind: TypeKind::Keyword(KeywordType::Never),
+            span,
+        },
+        1 => types.pop().unwrap(),
+        _ => ast::Type {
+            kind: TypeKind::Union(types),
+            span,
+        },
+    }
+}
+
+/// The type of a property, method or getter; that of an optional one includes `undefined`.
+pub(super) fn member_type(m: &ast::Member) -> Option<ast::Type> {
+    let ty = match &m.kind {
+        MemberKind::Property(p) => p.ty.clone().unwrap_or_else(|| any(m.span)),
+        MemberKind::Method(method) => ast::Type {
+            kind: TypeKind::Function(Box::new(ast::FunctionType {
+                is_abstract: false,
+                sig: method.sig.clone(),
+            })),
+            span: m.span,
+        },
+        MemberKind::Getter(g) => g.ty.clone().unwrap_or_else(|| any(m.span)),
+        _ => return None,
+    };
+    if !is_optional(m) {
+        return Some(ty);
+    }
+    let undefined = ast::Type {
+        kind: TypeKind::Keyword(KeywordType::Undefined),
+        span: m.span,
+    };
+    Some(union_of(vec![ty, undefined], m.span))
+}
+
+fn is_optional(m: &ast::Member) -> bool {
+    match &m.kind {
+        MemberKind::Property(p) => p.optional,
+        MemberKind::Method(method) => method.optional,
+        _ => false,
+    }
+}
+
+fn is_readonly(m: &ast::Member) -> bool {
+    match &m.kind {
+        MemberKind::Property(p) => p.readonly,
+        MemberKind::Getter(_) => true,
+        _ => false,
+    }
+}
+
+/// Whether `ty` refers to any of the type parameters `names`.
+pub(super) fn mentions(ty: &ast::Type, names: &[String]) -> bool {
+    let any_of = |types: &[ast::Type]| types.iter().any(|t| mentions(t, names));
+    match &ty.kind {
+        TypeKind::Reference(r) => {
+            matches!(r.name.parts.as_slice(), [part] if names.contains(&part.name))
+                || any_of(&r.type_args)
+        }
+        TypeKind::Array(inner) | TypeKind::Operator(_, inner) => mentions(inner, names),
+        TypeKind::Tuple(elems) => elems.iter().any(|e| mentions(&e.ty, names)),
+        TypeKind::Union(types) | TypeKind::Intersection(types) => any_of(types),
+        TypeKind::Template { types, .. }
+        | TypeKind::Import {
+            type_args: types, ..
+        } => any_of(types),
+        TypeKind::Function(f) | TypeKind::Constructor(f) => signature_mentions(&f.sig, names),
+        TypeKind::Object(members) => members.iter().any(|m| member_mentions(m, names)),
+        TypeKind::Indexed { object, index } => mentions(object, names) || mentions(index, names),
+        TypeKind::Mapped(mapped) => {
+            mentions(&mapped.constraint, names)
+                || mapped
+                    .name_type
+                    .iter()
+                    .chain(&mapped.ty)
+                    .any(|t| mentions(t, names))
+        }
+        TypeKind::Conditional(c) => [&c.check, &c.extends, &c.true_ty, &c.false_ty]
+            .iter()
+            .any(|t| mentions(t, names)),
+        TypeKind::Infer {
+            constraint: Some(constraint),
+            ..
+        } => mentions(constraint, names),
+        TypeKind::Predicate { ty: Some(ty), .. } => mentions(ty, names),
+        _ => false,
+    }
+}
+
+fn signature_mentions(sig: &ast::Signature, names: &[String]) -> bool {
+    sig.params
+        .iter()
+        .filter_map(|p| p.ty.as_ref())
+        .chain(&sig.ret)
+        .any(|t| mentions(t, names))
+}
+
+fn member_mentions(m: &ast::Member, names: &[String]) -> bool {
+    match &m.kind {
+        MemberKind::Property(p) => p.ty.iter().any(|t| mentions(t, names)),
+        MemberKind::Method(method) => signature_mentions(&method.sig, names),
+        MemberKind::Call(sig) | MemberKind::Construct(sig) => signature_mentions(sig, names),
+        MemberKind::Index(sig) => sig.ty.iter().any(|t| mentions(t, names)),
+        MemberKind::Getter(g) => g.ty.iter().any(|t| mentions(t, names)),
+        MemberKind::Setter(s) => s.param.ty.iter().any(|t| mentions(t, names)),
+    }
+}
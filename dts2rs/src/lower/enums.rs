@@ -0,0 +1,334 @@
+//! Lowering of enum declarations.
+//!
+//! Numeric enums become C-style `#[wasm_bindgen]` enums and string enums become string enums.
+//! Member values are computed the way TypeScript does: members without an initializer follow
+//! the previous one, and initializers may be constant expressions over literals and other
+//! members. Members sharing a value with an earlier one become associated constants.
+
+use dts_parser::ast::{self, BinaryOp, ExprKind, UnaryOp};
+
+use super::Lowerer;
+use crate::ir::{self, RustType, VariantKind};
+use crate::names;
+
+/// The value of an enum member.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum Const {
+    Num(f64),
+    Str(String),
+}
+
+impl<'a> Lowerer<'a> {
+    /// Lowers an enum, defining its Rust enum if it can be represented as one, and records
+    /// the type references to it map to.
+    pub(super) fn lower_enum(&mut self, decl: &ast::EnumDecl, doc: Option<&str>) {
+        let name = &decl.name.name;
+        let qualified = self.scope.qualify(name);
+        if self.enum_types.contains_key(&qualified) {
+            // Merged enum declarations are not supported; the first one wins.
+            self.diags.warn(
+                self.file,
+                decl.name.span,
+                format!(
+                    "enum `{}` is declared more than once; later declarations are skipped",
+                    name
+                ),
+            );
+            return;
+        }
+        let mut members: Vec<(String, Option<Const>)> = Vec::new();
+        for member in &decl.members {
+            let Some(js_name) = self.prop_js_name(&member.name) else {
+                continue;
+            };
+            let value = match &member.init {
+                Some(init) => {
+                    let value = self.fold(init, name, &members);
+                    if value.is_none() {
+                        self.diags.warn(
+                            self.file,
+                            init.span,
+                            format!("can't compute the value of enum member `{}`", js_name),
+                        );
+                    }
+                    value
+                }
+                None => match members.last() {
+                    None => Some(Const::Num(0.0)),
+                    Some((_, Some(Const::Num(prev)))) => Some(Const::Num(prev + 1.0)),
+                    Some(_) => {
+                        self.diags.warn(
+                            self.file,
+                            member.span,
+                            format!("enum member `{}` needs an initializer", js_name),
+                        );
+                        None
+                    }
+                },
+            };
+            members.push((js_name.to_string(), value));
+        }
+        self.enum_values.insert(
+            qualified.clone(),
+            members
+                .iter()
+                .filter_map(|(n, v)| Some((n.clone(), v.clone()?)))
+                .collect(),
+        );
+
+        let ty = match self.enum_kinds(decl, &members) {
+            Ok(kinds) => {
+                let rust_name = self.fresh_type_name(&names::type_name(name));
+                self.define_enum(&rust_name, decl, kinds, doc);
+                RustType::Value(rust_name)
+            }
+            Err(fallback) => fallback,
+        };
+        self.enum_types.insert(qualified, ty);
+    }
+
+    /// The variant kinds of the members, or the type to map the enum to if it can't be a
+    /// Rust enum.
+    fn enum_kinds(
+        &mut self,
+        decl: &ast::EnumDecl,
+        members: &[(String, Option<Const>)],
+    ) -> Result<Vec<VariantKind>, RustType> {
+        let name = &decl.name.name;
+        let all_numbers = members
+            .iter()
+            .all(|(_, v)| !matches!(v, Some(Const::Str(_))));
+        let all_strings = members
+            .iter()
+            .all(|(_, v)| matches!(v, Some(Const::Str(_))));
+        let fallback = if all_numbers {
+            RustType::F64
+        } else {
+            RustType::JsValue
+        };
+        if members.is_empty() {
+            return Err(fallback);
+        }
+        let problem = if members.iter().any(|(_, v)| v.is_none()) {
+            Some("has members with unknown values")
+        } else if !(all_numbers || all_strings) {
+            Some("mixes numbers and strings")
+        } else {
+            None
+        };
+        if let Some(problem) = problem {
+            self.diags.warn(
+                self.file,
+                decl.name.span,
+                format!(
+                    "enum `{}` {}; it is bound as `{}`",
+                    name,
+                    problem,
+                    fallback.owned()
+                ),
+            );
+            return Err(fallback);
+        }
+        if all_strings {
+            return Ok(members
+                .iter()
+                .map(|(_, v)| match v {
+                    Some(Const::Str(s)) => VariantKind::Str(s.clone()),
+                    _ => unreachable!(),
+                })
+                .collect());
+        }
+        // wasm-bindgen stores discriminants as `u32`, or as `i32` if any is negative.
+        let values: Option<Vec<i64>> = members
+            .iter()
+            .map(|(_, v)| match v {
+                Some(Const::Num(n)) if n.fract() == 0.0 && n.abs() <= u32::MAX as f64 => {
+                    Some(*n as i64)
+                }
+                _ => None,
+            })
+            .collect();
+        let fits = values.as_ref().is_some_and(|values| {
+            let signed = values.iter().any(|v| *v < 0);
+            values.iter().all(|v| {
+                if signed {
+                    i32::try_from(*v).is_ok()
+                } else {
+                    u32::try_from(*v).is_ok()
+                }
+            })
+        });
+        match values {
+            Some(values) if fits => Ok(values.into_iter().map(VariantKind::Int).collect()),
+            _ => {
+                self.diags.warn(
+                    self.file,
+                    decl.name.span,
+                    format!(
+                        "enum `{}` has values that are not 32-bit integers; it is bound as `f64`",
+                        name
+                    ),
+                );
+                Err(RustType::F64)
+            }
+        }
+    }
+
+    fn define_enum(
+        &mut self,
+        rust_name: &str,
+        decl: &ast::EnumDecl,
+        kinds: Vec<VariantKind>,
+        doc: Option<&str>,
+    ) {
+        let mut variants: Vec<ir::Variant> = Vec::new();
+        let mut aliases: Vec<ir::EnumAlias> = Vec::new();
+        let named = decl.members.iter().filter(|m| m.name.as_str().is_some());
+        for (member, kind) in named.zip(kinds) {
+            let js_name = member.name.as_str().unwrap();
+            if let Some(first) = variants.iter().find(|v| v.kind == kind) {
+                aliases.push(ir::EnumAlias {
+                    name: names::upper_snake_case(js_name),
+                    variant: first.name.clone(),
+                    doc: member.doc.clone(),
+                });
+                continue;
+            }
+            let base = names::variant_name(js_name);
+            let mut name = base.clone();
+            let mut n = 2;
+            while variants.iter().any(|v| v.name == name) {
+                name = format!("{}{}", base, n);
+                n += 1;
+            }
+            variants.push(ir::Variant {
+                name,
+                kind,
+                doc: member.doc.clone(),
+            });
+        }
+        self.enums.push(ir::Enum {
+            rust_name: rust_name.to_string(),
+            variants,
+            aliases,
+            doc: doc.map(str::to_string),
+        });
+    }
+
+    /// Evaluates a constant expression in an initializer of enum `this`, whose members so
+    /// far are `members`.
+    fn fold(
+        &self,
+        expr: &ast::Expr,
+        this: &str,
+        members: &[(String, Option<Const>)],
+    ) -> Option<Const> {
+        let member = |enum_name: &str, name: &str| -> Option<Const> {
+            if enum_name == this {
+                members
+                    .iter()
+                    .find(|(n, _)| n == name)
+                    .and_then(|(_, v)| v.clone())
+            } else {
+                let (_, values) = self.lookup(&self.enum_values, enum_name)?;
+                values.get(name).cloned()
+            }
+        };
+        match &expr.kind {
+            ExprKind::Num(text) => parse_number(text).map(Const::Num),
+            ExprKind::Str(s) | ExprKind::Template(s) => Some(Const::Str(s.clone())),
+            ExprKind::Ident(ident) => match ident.name.as_str() {
+                "Infinity" => Some(Const::Num(f64::INFINITY)),
+                "NaN" => Some(Const::Num(f64::NAN)),
+                name => member(this, name),
+            },
+            ExprKind::Member(object, prop) => match &object.kind {
+                ExprKind::Ident(e) => member(&e.name, &prop.name),
+                _ => None,
+            },
+            ExprKind::Index(object, index) => match (&object.kind, &index.kind) {
+                (ExprKind::Ident(e), ExprKind::Str(key)) => member(&e.name, key),
+                _ => None,
+            },
+            ExprKind::Unary(op, operand) => {
+                let Const::Num(n) = self.fold(operand, this, members)? else {
+                    return None;
+                };
+                match op {
+                    UnaryOp::Neg => Some(Const::Num(-n)),
+                    UnaryOp::Plus => Some(Const::Num(n)),
+                    UnaryOp::BitNot => Some(Const::Num(!to_int32(n) as f64)),
+                    UnaryOp::Not => None,
+                }
+            }
+            ExprKind::Binary(op, lhs, rhs) => {
+                let lhs = self.fold(lhs, this, members)?;
+                let rhs = self.fold(rhs, this, members)?;
+                binary(*op, lhs, rhs)
+            }
+            _ => None,
+        }
+    }
+}
+
+fn binary(op: BinaryOp, lhs: Const, rhs: Const) -> Option<Const> {
+    let (a, b) = match (lhs, rhs) {
+        (Const::Num(a), Const::Num(b)) => (a, b),
+        (lhs, rhs) if op == BinaryOp::Add => {
+            return Some(Const::Str(format!(
+                "{}{}",
+                to_string(&lhs),
+                to_string(&rhs)
+            )));
+        }
+        _ => return None,
+    };
+    let shift = (to_int32(b) as u32) & 31;
+    let n = match op {
+        BinaryOp::Add => a + b,
+        BinaryOp::Sub => a - b,
+        BinaryOp::Mul => a * b,
+        BinaryOp::Div => a / b,
+        BinaryOp::Rem => a % b,
+        BinaryOp::Exp => a.powf(b),
+        BinaryOp::Shl => to_int32(a).wrapping_shl(shift) as f64,
+        BinaryOp::Shr => (to_int32(a) >> shift) as f64,
+        BinaryOp::UShr => ((to_int32(a) as u32) >> shift) as f64,
+        BinaryOp::BitAnd => (to_int32(a) & to_int32(b)) as f64,
+        BinaryOp::BitOr => (to_int32(a) | to_int32(b)) as f64,
+        BinaryOp::BitXor => (to_int32(a) ^ to_int32(b)) as f64,
+    };
+    Some(Const::Num(n))
+}
+
+/// JavaScript's conversion of numbers to 32-bit integers for bitwise operators.
+fn to_int32(n: f64) -> i32 {
+    if !n.is_finite() {
+        return 0;
+    }
+    n.trunc().rem_euclid(4_294_967_296.0) as u32 as i32
+}
+
+/// JavaScript's string conversion, for `+` with a string operand.
+fn to_string(c: &Const) -> String {
+    match c {
+        Const::Str(s) => s.clone(),
+        Const::Num(n) if n.fract() == 0.0 && n.abs() < 1e21 => format!("{}", *n as i64),
+        Const::Num(n) => n.to_string(),
+    }
+}
+
+/// Parses a JavaScript numeric literal: decimal, `0x`, `0o` or `0b`, with `_` separators.
+fn parse_number(text: &str) -> Option<f64> {
+    let text = text.replace('_', "");
+    let lower = text.to_ascii_lowercase();
+    let radix = match lower.get(..2) {
+        Some("0x") => 16,
+        Some("0o") => 8,
+        Some("0b") => 2,
+        _ => return text.parse().ok(),
+    };
+    lower[2..].chars().try_fold(0.0, |acc, c| {
+        Some(acc * radix as f64 + c.to_digit(radix)? as f64)
+    })
+}
@@ -0,0 +1,784 @@
+//! Evaluation of utility types.
+//!
+//! `type Opts = Partial<Pick<Config, "a" | "b">>` stands for an object type whose members are
+//! computed from those of `Config`. Aliases of `Partial`, `Required`, `Readonly`, `Pick` and
+//! `Omit` whose members can be computed are bound like interfaces with those members, so
+//! that `Opts` gets `a` and `b` returning `Option`s. The members of a generic type are those
+//! of its instance: `Partial<Box<string>>` has the members of `Box` with `T` replaced by
+//! `string`, resolved where `Box` is declared.
+//!
+//! Aliases of mapped types, of conditional types and of instances of generic aliases that
+//! evaluate to object types are bound the same way, with the members that
+//! [`computed`](super::computed) evaluates them to.
+//!
+//! Written elsewhere, as in `p: Partial<Config>`, these utility types are bound like object
+//! literal types with the members they evaluate to.
+//!
+//! `ReturnType<F>` and `Parameters<F>` evaluate to the return type and the tuple of parameter
+//! types of a function type, of `typeof f` for a declared function `f` or of a type
+//! evaluating to either.
+
+use std::collections::HashMap;
+
+use dts_parser::ast::{self, ClassMemberKind, MemberKind, TypeKind};
+
+use super::computed::mentions;
+use super::interfaces::MemberTarget;
+use super::namespaces::Scope;
+use super::{merging, Lowerer};
+use crate::ir::{self, RustType};
+use crate::names;
+
+/// How deeply aliases and utility types may nest, which also stops aliases that refer to
+/// themselves.
+pub(super) const MAX_DEPTH: usize = 16;
+
+/// Utility types that evaluate to object types.
+pub(super) const OBJECT_UTILITIES: &[&str] = &["Partial", "Required", "Readonly", "Pick", "Omit"];
+
+/// The members of an evaluated object type, each with the scope its types are written in.
+pub(super) type Members = Vec<(Scope, ast::Member)>;
+
+impl<'a> Lowerer<'a> {
+    /// Finds the type aliases of utility types that evaluate to object types, which are then
+    /// declared types.
+    pub(super) fn collect_object_aliases(&mut self) {
+        let mut aliases: Vec<(String, Scope, ast::TypeAliasDecl)> = self
+            .aliases
+            .iter()
+            .filter_map(|(name, (scope, item))| match &item.kind {
+                ast::ItemKind::TypeAlias(decl) => Some((name.clone(), scope.clone(), decl.clone())),
+                _ => None,
+            })
+            .collect();
+        aliases.sort_by(|a, b| a.0.cmp(&b.0));
+        for (name, scope, decl) in aliases {
+            if !decl.type_params.is_empty() || self.types.contains_key(&name) {
+                continue;
+            }
+            let evaluated = self.in_scope(scope.clone(), |this| {
+                if let Some(intersection) = this.intersection(&decl.ty, 0) {
+                    for member in &intersection.conflicts {
+                        this.diags.warn(
+                            this.file,
+                            decl.ty.span,
+                            format!(
+                                "member `{}` has conflicting types in this intersection; the first is bound",
+                                member
+                            ),
+                        );
+                    }
+                    return Some((intersection.parents, intersection.members));
+                }
+                if this.is_computed_object(&decl.ty, false, 0) {
+                    Some((Vec::new(), this.members_of(&decl.ty, 0)?))
+                } else {
+                    None
+                }
+            });
+            let Some((parents, members)) = evaluated else {
+                continue;
+            };
+            if !parents.is_empty() {
+                self.parents.insert(name.clone(), parents);
+            }
+            let rust_name = scope.rust_path(&names::type_name(&decl.name.name));
+            self.types.insert(name.clone(), rust_name.clone());
+            self.interfaces.insert(rust_name.clone());
+            self.type_names.insert(rust_name);
+            self.object_aliases.insert(name, members);
+        }
+    }
+
+    /// Lowers a type alias that evaluates to an object type, like an interface with its
+    /// members.
+    pub(super) fn lower_object_alias(
+        &mut self,
+        decl: &ast::TypeAliasDecl,
+        item: &ast::Item,
+        out: &mut ir::Module,
+    ) {
+        let qualified = self.scope.qualify(&decl.name.name);
+        let Some(members) = self.object_aliases.get(&qualified).cloned() else {
+            return;
+        };
+        // Only the first declaration of an alias counts.
+        let (scope, primary) = &self.aliases[&qualified];
+        if scope.file != self.scope.file || primary.span != item.span {
+            return;
+        }
+        let start = out.externs.len();
+        let rust_name = self.types[&qualified].clone();
+        let extends = self.ancestors(&qualified, decl.name.span);
+        out.externs.push(ir::ExternItem::Type(ir::TypeDecl {
+            rust_name: rust_name.clone(),
+            js_name: decl.name.name.clone(),
+            extends,
+            is_type_of: Some("JsValue::is_object".to_string()),
+            doc: item.doc.clone(),
+        }));
+        self.this_type = Some(rust_name.clone());
+        let mut target = MemberTarget {
+            this: &rust_name,
+            js_this: &qualified,
+            instance: RustType::path(rust_name.clone()),
+            structural: true,
+            is_static: false,
+        };
+        for (scope, member) in &members {
+            self.in_scope(scope.clone(), |this| {
+                this.in_context(&decl.name.name, |this| {
+                    this.lower_member(member, &mut target, out)
+                })
+            });
+        }
+        self.this_type = None;
+        merging::dedup_members(&mut out.externs, start);
+    }
+
+    /// Evaluates `ReturnType<F>`, `Parameters<F>` and `Readonly<T>` where they are written,
+    /// binds `Partial`, `Required`, `Pick` and `Omit` written there like object literal types
+    /// with their members, or returns `None` for other types.
+    pub(super) fn map_utility(&mut self, r: &ast::TypeRef) -> Option<RustType> {
+        let ty = ast::Type {
+            kind: TypeKind::Reference(r.clone()),
+            span: r.span,
+        };
+        let name = r.name.to_dotted();
+        match (name.as_str(), r.type_args.as_slice()) {
+            ("ReturnType", [f]) => {
+                let Some((scope, sig)) = self.signature_of(f, 0) else {
+                    self.cannot_evaluate(&ty);
+                    return Some(RustType::JsValue);
+                };
+                Some(self.in_scope(scope, |this| match &sig.ret {
+                    Some(ret) => this.map_type(ret),
+                    None => RustType::JsValue,
+                }))
+            }
+            // Readonly types are bound as the types they make readonly.
+            ("Readonly", [ty]) => Some(self.map_type(ty)),
+            ("Parameters", [f]) => {
+                let Some((scope, sig)) = self.signature_of(f, 0) else {
+                    self.cannot_evaluate(&ty);
+                    return Some(RustType::JsValue);
+                };
+                let elems: Vec<ast::TupleElement> = sig
+                    .params
+                    .iter()
+                    .filter(|p| p.name.name() != "this")
+                    .map(|p| ast::TupleElement {
+                        name: None,
+                        ty: p.ty.clone().unwrap_or_else(|| any(p.span)),
+                        optional: p.optional,
+                        rest: p.rest,
+                        span: p.span,
+                    })
+                    .collect();
+                Some(self.in_scope(scope, |this| this.map_tuple(&elems)))
+            }
+            ("Partial" | "Required", [_]) | ("Pick" | "Omit", [_, _]) => {
+                // Members referring to type parameters can't be bound on a type of their own.
+                if !mentions(&ty, &self.type_params) {
+                    if let Some(members) = self.members_of(&ty, 0) {
+                        return Some(self.map_evaluated_object(&ty, &members));
+                    }
+                }
+                self.cannot_evaluate(&ty);
+                Some(RustType::JsValue)
+            }
+            _ => None,
+        }
+    }
+
+    /// The signature of a function type, of `typeof f` for a declared function, of an alias
+    /// of either or of a type evaluating to one, with the scope it is written in.
+    pub(super) fn signature_of(
+        &mut self,
+        ty: &ast::Type,
+        depth: usize,
+    ) -> Option<(Scope, ast::Signature)> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        match &ty.kind {
+            TypeKind::Function(f) => Some((self.scope.clone(), f.sig.clone())),
+            TypeKind::Query(name) => {
+                let (scope, item) = self.lookup(&self.functions, &name.to_dotted())?.1.clone();
+                match item.kind {
+                    ast::ItemKind::Function(decl) => Some((scope, decl.sig)),
+                    _ => None,
+                }
+            }
+            TypeKind::Reference(r) => {
+                let (scope, ty) = self.alias_type(r, depth + 1)?;
+                self.in_scope(scope, |this| this.signature_of(&ty, depth + 1))
+            }
+            TypeKind::Conditional(_) | TypeKind::Indexed { .. } => {
+                let (scope, ty) = self.reduce(ty, depth + 1)?;
+                self.in_scope(scope, |this| this.signature_of(&ty, depth + 1))
+            }
+            _ => None,
+        }
+    }
+
+    /// The members of an object type, or `None` if they can't be computed.
+    pub(super) fn members_of(&mut self, ty: &ast::Type, depth: usize) -> Option<Members> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        match &ty.kind {
+            TypeKind::Object(members) => Some(
+                members
+                    .iter()
+                    .map(|m| (self.scope.clone(), m.clone()))
+                    .collect(),
+            ),
+            TypeKind::Reference(r) => self.members_of_ref(r, depth),
+            TypeKind::Mapped(mapped) => self.mapped_members(mapped, ty.span, depth + 1),
+            TypeKind::Intersection(_) => Some(self.intersection(ty, depth + 1)?.all_members()),
+            TypeKind::Conditional(_) | TypeKind::Indexed { .. } => {
+                let (scope, ty) = self.reduce(ty, depth + 1)?;
+                self.in_scope(scope, |this| this.members_of(&ty, depth + 1))
+            }
+            _ => None,
+        }
+    }
+
+    fn members_of_ref(&mut self, r: &ast::TypeRef, depth: usize) -> Option<Members> {
+        let name = r.name.to_dotted();
+        let candidates = self.candidates(&name);
+        if let Some((qualified, _)) = Self::find(&self.object_aliases, &candidates) {
+            let qualified = qualified.clone();
+            return self.named_members(&qualified, depth + 1);
+        }
+        if let Some((qualified, _)) = Self::find(&self.declarations, &candidates) {
+            let qualified = qualified.clone();
+            return self.declared_members(&qualified, &r.type_args, depth + 1);
+        }
+        if let Some((scope, ty)) = self.alias_type(r, depth + 1) {
+            return self.in_scope(scope, |this| this.members_of(&ty, depth + 1));
+        }
+        let members = match (name.as_str(), r.type_args.as_slice()) {
+            ("Partial", [ty]) => self
+                .members_of(ty, depth + 1)?
+                .into_iter()
+                .map(|(scope, m)| (scope, optional(m, true)))
+                .collect(),
+            ("Required", [ty]) => self
+                .members_of(ty, depth + 1)?
+                .into_iter()
+                .map(|(scope, m)| (scope, optional(m, false)))
+                .collect(),
+            ("Readonly", [ty]) => self
+                .members_of(ty, depth + 1)?
+                .into_iter()
+                .filter_map(|(scope, m)| Some((scope, readonly(m)?)))
+                .collect(),
+            ("Pick" | "Omit", [ty, keys]) => {
+                let keys = self.keys_of(keys, depth + 1)?;
+                let pick = name == "Pick";
+                self.members_of(ty, depth + 1)?
+                    .into_iter()
+                    .filter(|(_, m)| {
+                        member_name(m).is_some_and(|name| keys.iter().any(|k| k == name)) == pick
+                    })
+                    .collect()
+            }
+            _ => return None,
+        };
+        Some(members)
+    }
+
+    /// The members of declared interface or class `qualified` instantiated with `type_args`,
+    /// including those it inherits.
+    pub(super) fn declared_members(
+        &mut self,
+        qualified: &str,
+        type_args: &[ast::Type],
+        depth: usize,
+    ) -> Option<Members> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let mut out = Vec::new();
+        for (scope, item) in self.declarations[qualified].clone() {
+            let (type_params, members): (&[ast::TypeParam], Vec<ast::Member>) = match &item.kind {
+                ast::ItemKind::Interface(decl) => (&decl.type_params, decl.members.clone()),
+                ast::ItemKind::Class(decl) => (
+                    &decl.type_params,
+                    decl.members.iter().filter_map(instance_member).collect(),
+                ),
+                _ => continue,
+            };
+            let args = type_arg_map(type_params, type_args);
+            out.extend(
+                members
+                    .iter()
+                    .map(|m| (scope.clone(), substitute_member(m, &args))),
+            );
+        }
+        self.inherit(qualified, &mut out, depth)?;
+        Some(out)
+    }
+
+    /// The members of declared type or object type alias `qualified`, including those it
+    /// inherits, or `None` for other types.
+    fn named_members(&mut self, qualified: &str, depth: usize) -> Option<Members> {
+        if self.declarations.contains_key(qualified) {
+            return self.declared_members(qualified, &[], depth + 1);
+        }
+        let mut out = self.object_aliases.get(qualified)?.clone();
+        self.inherit(qualified, &mut out, depth)?;
+        Some(out)
+    }
+
+    /// Adds the members that `qualified` inherits to its own members `out`, unless they are
+    /// overridden.
+    fn inherit(&mut self, qualified: &str, out: &mut Members, depth: usize) -> Option<()> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        for parent in self.parents.get(qualified).cloned().unwrap_or_default() {
+            // Built-in types have no members to evaluate.
+            let Some(inherited) = self.named_members(&parent, depth + 1) else {
+                if self.declarations.contains_key(&parent) {
+                    return None;
+                }
+                continue;
+            };
+            let own: Vec<String> = out
+                .iter()
+                .filter_map(|(_, m)| member_name(m).map(str::to_string))
+                .collect();
+            out.extend(
+                inherited
+                    .into_iter()
+                    .filter(|(_, m)| member_name(m).is_none_or(|n| !own.iter().any(|o| o == n))),
+            );
+        }
+        Some(())
+    }
+
+    /// The property names that a union of string literal types, an alias of one, `keyof T` or
+    /// a template literal type over them stands for.
+    pub(super) fn keys_of(&mut self, ty: &ast::Type, depth: usize) -> Option<Vec<String>> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        match &ty.kind {
+            TypeKind::Literal(ast::LiteralType::Str(s) | ast::LiteralType::Num(s)) => {
+                Some(vec![s.clone()])
+            }
+            TypeKind::Literal(ast::LiteralType::Bool(b)) => Some(vec![b.to_string()]),
+            TypeKind::Keyword(ast::KeywordType::Never) => Some(Vec::new()),
+            TypeKind::Union(types) => {
+                let mut keys = Vec::new();
+                for ty in types {
+                    keys.extend(self.keys_of(ty, depth + 1)?);
+                }
+                Some(keys)
+            }
+            TypeKind::Operator(ast::TypeOperator::Keyof, ty) => {
+                let members = self.members_of(ty, depth + 1)?;
+                // The keys of index signatures are not names.
+                if members
+                    .iter()
+                    .any(|(_, m)| matches!(m.kind, MemberKind::Index(_)))
+                {
+                    return None;
+                }
+                Some(
+                    members
+                        .iter()
+                        .filter_map(|(_, m)| member_name(m).map(str::to_string))
+                        .collect(),
+                )
+            }
+            // `${K}Changed`
+            TypeKind::Template { quasis, types } => {
+                let mut keys = vec![quasis[0].clone()];
+                for (ty, quasi) in types.iter().zip(&quasis[1..]) {
+                    let parts = self.keys_of(ty, depth + 1)?;
+                    keys = keys
+                        .iter()
+                        .flat_map(|key| {
+                            parts
+                                .iter()
+                                .map(move |part| format!("{}{}{}", key, part, quasi))
+                        })
+                        .collect();
+                }
+                Some(keys)
+            }
+            // `K & string` in the `as` clause of a mapped type.
+            TypeKind::Intersection(types) => types
+                .iter()
+                .filter(|t| !matches!(t.kind, TypeKind::Keyword(_)))
+                .find_map(|t| self.keys_of(t, depth + 1)),
+            TypeKind::Conditional(_) | TypeKind::Indexed { .. } => {
+                let (scope, ty) = self.reduce(ty, depth + 1)?;
+                self.in_scope(scope, |this| this.keys_of(&ty, depth + 1))
+            }
+            TypeKind::Reference(r) => {
+                if let Some((scope, ty)) = self.alias_type(r, depth + 1) {
+                    return self.in_scope(scope, |this| this.keys_of(&ty, depth + 1));
+                }
+                let name = r.name.to_dotted();
+                let case: fn(&str) -> String = match (name.as_str(), r.type_args.as_slice()) {
+                    ("Exclude" | "Extract" | "NonNullable", _) => {
+                        let (scope, ty) = self.reduce(ty, depth + 1)?;
+                        return self.in_scope(scope, |this| this.keys_of(&ty, depth + 1));
+                    }
+                    ("Capitalize", [_]) => capitalize,
+                    ("Uncapitalize", [_]) => uncapitalize,
+                    ("Uppercase", [_]) => str::to_uppercase,
+                    ("Lowercase", [_]) => str::to_lowercase,
+                    _ => return None,
+                };
+                let keys = self.keys_of(&r.type_args[0], depth + 1)?;
+                Some(keys.iter().map(|k| case(k)).collect())
+            }
+            _ => None,
+        }
+    }
+
+    /// The type that a reference to a type alias stands for, with the alias's type
+    /// parameters replaced by the reference's type arguments, and the scope it is written
+    /// in.
+    pub(super) fn alias_type(
+        &mut self,
+        r: &ast::TypeRef,
+        depth: usize,
+    ) -> Option<(Scope, ast::Type)> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let (_, (scope, item)) = self.lookup(&self.aliases, &r.name.to_dotted())?;
+        let (scope, item) = (scope.clone(), item.clone());
+        let ast::ItemKind::TypeAlias(decl) = &item.kind else {
+            return None;
+        };
+        let args = type_arg_map(&decl.type_params, &r.type_args);
+        let args = self.union_args(&decl.ty, args, depth + 1);
+        Some((scope, substitute(&decl.ty, &args)))
+    }
+}
+
+/// `any`, for parameters without a type annotation.
+pub(super) fn any(span: dts_parser::Span) -> ast::Type {
+    ast::Type {
+        kind: TypeKind::Keyword(ast::KeywordType::Any),
+        span,
+    }
+}
+
+/// The name of a property, method or accessor.
+pub(super) fn member_name(m: &ast::Member) -> Option<&str> {
+    match &m.kind {
+        MemberKind::Property(p) => p.name.as_str(),
+        MemberKind::Method(method) => method.name.as_str(),
+        MemberKind::Getter(g) => g.name.as_str(),
+        MemberKind::Setter(s) => s.name.as_str(),
+        _ => None,
+    }
+}
+
+/// A public instance member of a class, as the member of an object type.
+fn instance_member(m: &ast::ClassMember) -> Option<ast::Member> {
+    if m.is_static
+        || matches!(
+            m.accessibility,
+            Some(ast::Accessibility::Private | ast::Accessibility::Protected)
+        )
+    {
+        return None;
+    }
+    let kind = match &m.kind {
+        ClassMemberKind::Property(p) if !matches!(p.name, ast::PropName::Private(_)) => {
+            MemberKind::Property(ast::PropertySig {
+                readonly: p.readonly || m.readonly,
+                ..p.clone()
+            })
+        }
+        ClassMemberKind::Method(method) if !matches!(method.name, ast::PropName::Private(_)) => {
+            MemberKind::Method(method.clone())
+        }
+        ClassMemberKind::Getter(g) => MemberKind::Getter(g.clone()),
+        ClassMemberKind::Setter(s) => MemberKind::Setter(s.clone()),
+        ClassMemberKind::Index(sig) => MemberKind::Index(sig.clone()),
+        _ => return None,
+    };
+    Some(ast::Member {
+        kind,
+        doc: m.doc.clone(),
+        span: m.span,
+    })
+}
+
+/// `m` as a member of `Partial<T>`, if `optional`, or of `Required<T>`. A getter of a
+/// partial type is an optional readonly property.
+fn optional(mut m: ast::Member, optional: bool) -> ast::Member {
+    match &mut m.kind {
+        MemberKind::Property(p) => p.optional = optional,
+        MemberKind::Method(method) => method.optional = optional,
+        MemberKind::Getter(g) if optional => {
+            m.kind = MemberKind::Property(ast::PropertySig {
+                name: g.name.clone(),
+                optional: true,
+                readonly: true,
+                ty: g.ty.clone(),
+            });
+        }
+        _ => {}
+    }
+    m
+}
+
+/// `m` as a member of `Readonly<T>`, which has no setters.
+fn readonly(mut m: ast::Member) -> Option<ast::Member> {
+    match &mut m.kind {
+        MemberKind::Property(p) => p.readonly = true,
+        MemberKind::Index(sig) => sig.readonly = true,
+        MemberKind::Setter(_) => return None,
+        _ => {}
+    }
+    Some(m)
+}
+
+/// The type arguments of an instance of a generic declaration by type parameter name,
+/// falling back to the parameters' defaults.
+pub(super) fn type_arg_map(
+    params: &[ast::TypeParam],
+    args: &[ast::Type],
+) -> HashMap<String, ast::Type> {
+    params
+        .iter()
+        .enumerate()
+        .filter_map(|(i, p)| {
+            let arg = args.get(i).or(p.default.as_ref())?;
+            Some((p.name.name.clone(), arg.clone()))
+        })
+        .collect()
+}
+
+/// `ty` with references to the type parameters in `args` replaced by their arguments.
+pub(super) fn substitute(ty: &ast::Type, args: &HashMap<String, ast::Type>) -> ast::Type {
+    if args.is_empty() {
+        return ty.clone();
+    }
+    let sub = |t: &ast::Type| substitute(t, args);
+    let kind = match &ty.kind {
+        TypeKind::Reference(r) => {
+            if let ([part], true) = (r.name.parts.as_slice(), r.type_args.is_empty()) {
+                if let Some(arg) = args.get(&part.name) {
+                    return arg.clone();
+                }
+            }
+            TypeKind::Reference(ast::TypeRef {
+                type_args: r.type_args.iter().map(sub).collect(),
+                ..r.clone()
+            })
+        }
+        TypeKind::Array(elem) => TypeKind::Array(Box::new(sub(elem))),
+        TypeKind::Tuple(elems) => TypeKind::Tuple(
+            elems
+                .iter()
+                .map(|e| ast::TupleElement {
+                    ty: sub(&e.ty),
+                    ..e.clone()
+                })
+                .collect(),
+        ),
+        TypeKind::Union(types) => TypeKind::Union(types.iter().map(sub).collect()),
+        TypeKind::Intersection(types) => TypeKind::Intersection(types.iter().map(sub).collect()),
+        TypeKind::Function(f) => TypeKind::Function(Box::new(substitute_function(f, args))),
+        TypeKind::Constructor(f) => TypeKind::Constructor(Box::new(substitute_function(f, args))),
+        TypeKind::Object(members) => {
+            TypeKind::Object(members.iter().map(|m| substitute_member(m, args)).collect())
+        }
+        TypeKind::Operator(op, inner) => TypeKind::Operator(*op, Box::new(sub(inner))),
+        TypeKind::Indexed { object, index } => TypeKind::Indexed {
+            object: Box::new(sub(object)),
+            index: Box::new(sub(index)),
+        },
+        TypeKind::Mapped(mapped) => {
+            let inner = without(args, std::slice::from_ref(&mapped.param.name));
+            TypeKind::Mapped(Box::new(ast::MappedType {
+                constraint: sub(&mapped.constraint),
+                name_type: mapped.name_type.as_ref().map(|t| substitute(t, &inner)),
+                ty: mapped.ty.as_ref().map(|t| substitute(t, &inner)),
+                ..(**mapped).clone()
+            }))
+        }
+        // Conditional types distribute over unions given for the type parameter they check.
+        TypeKind::Conditional(c) if distributes(c, args) => {
+            let TypeKind::Reference(r) = &c.check.kind else {
+                unreachable!()
+            };
+            let TypeKind::Union(types) = &args[&r.name.parts[0].name].kind else {
+                unreachable!()
+            };
+            TypeKind::Union(
+                types
+                    .iter()
+                    .map(|ty| {
+                        let mut args = args.clone();
+                        args.insert(r.name.parts[0].name.clone(), ty.clone());
+                        let conditional = ast::Type {
+                            kind: TypeKind::Conditional(c.clone()),
+                            span: ty.span,
+                        };
+                        substitute(&conditional, &args)
+                    })
+                    .collect(),
+            )
+        }
+        TypeKind::Conditional(c) => TypeKind::Conditional(Box::new(ast::ConditionalType {
+            check: sub(&c.check),
+            extends: sub(&c.extends),
+            true_ty: sub(&c.true_ty),
+            false_ty: sub(&c.false_ty),
+        })),
+        TypeKind::Template { quasis, types } => TypeKind::Template {
+            quasis: quasis.clone(),
+            types: types.iter().map(sub).collect(),
+        },
+        TypeKind::Predicate {
+            asserts,
+            param,
+            ty: Some(inner),
+        } => TypeKind::Predicate {
+            asserts: *asserts,
+            param: param.clone(),
+            ty: Some(Box::new(sub(inner))),
+        },
+        TypeKind::Import {
+            module,
+            qualifier,
+            type_args,
+        } => TypeKind::Import {
+            module: module.clone(),
+            qualifier: qualifier.clone(),
+            type_args: type_args.iter().map(sub).collect(),
+        },
+        kind => kind.clone(),
+    };
+    ast::Type {
+        kind,
+        span: ty.span,
+    }
+}
+
+/// Whether conditional type `c` checks a type parameter that `args` gives a union for. Those
+/// given an alias of a union, or another type evaluating to one, are given the union by
+/// [`union_args`](Lowerer::union_args).
+fn distributes(c: &ast::ConditionalType, args: &HashMap<String, ast::Type>) -> bool {
+    match &c.check.kind {
+        TypeKind::Reference(r) if r.type_args.is_empty() => match r.name.parts.as_slice() {
+            [part] => args
+                .get(&part.name)
+                .is_some_and(|arg| matches!(arg.kind, TypeKind::Union(_))),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// `Capitalize<S>`
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// `Uncapitalize<S>`
+fn uncapitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) => c.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// `args` without the type parameters that `shadowing` declares.
+fn without(args: &HashMap<String, ast::Type>, shadowing: &[String]) -> HashMap<String, ast::Type> {
+    args.iter()
+        .filter(|(name, _)| !shadowing.contains(name))
+        .map(|(name, ty)| (name.clone(), ty.clone()))
+        .collect()
+}
+
+fn substitute_function(
+    f: &ast::FunctionType,
+    args: &HashMap<String, ast::Type>,
+) -> ast::FunctionType {
+    ast::FunctionType {
+        is_abstract: f.is_abstract,
+        sig: substitute_signature(&f.sig, args),
+    }
+}
+
+fn substitute_signature(sig: &ast::Signature, args: &HashMap<String, ast::Type>) -> ast::Signature {
+    let own: Vec<String> = sig
+        .type_params
+        .iter()
+        .map(|p| p.name.name.clone())
+        .collect();
+    let args = without(args, &own);
+    ast::Signature {
+        params: sig
+            .params
+            .iter()
+            .map(|p| substitute_param(p, &args))
+            .collect(),
+        ret: sig.ret.as_ref().map(|t| substitute(t, &args)),
+        ..sig.clone()
+    }
+}
+
+fn substitute_param(p: &ast::Param, args: &HashMap<String, ast::Type>) -> ast::Param {
+    ast::Param {
+        ty: p.ty.as_ref().map(|t| substitute(t, args)),
+        ..p.clone()
+    }
+}
+
+/// `m` with the type parameters in `args` replaced.
+pub(super) fn substitute_member(m: &ast::Member, args: &HashMap<String, ast::Type>) -> ast::Member {
+    if args.is_empty() {
+        return m.clone();
+    }
+    let ty = |t: &Option<ast::Type>| t.as_ref().map(|t| substitute(t, args));
+    let kind = match &m.kind {
+        MemberKind::Property(p) => MemberKind::Property(ast::PropertySig {
+            ty: ty(&p.ty),
+            ..p.clone()
+        }),
+        MemberKind::Method(method) => MemberKind::Method(ast::MethodSig {
+            sig: substitute_signature(&method.sig, args),
+            ..method.clone()
+        }),
+        MemberKind::Call(sig) => MemberKind::Call(substitute_signature(sig, args)),
+        MemberKind::Construct(sig) => MemberKind::Construct(substitute_signature(sig, args)),
+        MemberKind::Index(sig) => MemberKind::Index(ast::IndexSig {
+            ty: ty(&sig.ty),
+            ..sig.clone()
+        }),
+        MemberKind::Getter(g) => MemberKind::Getter(ast::GetterSig {
+            ty: ty(&g.ty),
+            ..g.clone()
+        }),
+        MemberKind::Setter(s) => MemberKind::Setter(ast::SetterSig {
+            param: substitute_param(&s.param, args),
+            ..s.clone()
+        }),
+    };
+    ast::Member {
+        kind,
+        doc: m.doc.clone(),
+        span: m.span,
+    }
+}
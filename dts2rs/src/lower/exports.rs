@@ -0,0 +1,196 @@
+//! ES module, CommonJS and UMD exports.
+//!
+//! Declarations imported from an ES module are bound under the names the module exports them
+//! as: `export default function main()` binds `js_name = "default"`, and a function that the
+//! entry file re-exports with `export { make as mk } from "./util"` binds `js_name = "mk"`
+//! when the files of the package are imported from [`Config::module`](crate::Config::module).
+//! Files with declarations the entry file doesn't re-export are imported from their own path
+//! in the package instead, such as `my-package/util`.
+//!
+//! `export = jQuery` makes the declaration `jQuery` the module itself. Imported from the ES
+//! module, it is the default export, so `function jQuery(...)` binds `js_name = "default"`,
+//! and a namespace `jQuery` merged with it has its members read from `default`. A UMD
+//! library, declaring `export as namespace $`, can instead be bound through its global with
+//! [`UmdMode::Global`](crate::UmdMode::Global): its declarations are then properties of `$`,
+//! and what `export =` names is `$` itself.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use dts_parser::ast;
+
+use super::imports::ModuleRef;
+use super::Lowerer;
+use crate::config::UmdMode;
+
+/// How many `export * from` are followed before giving up, which also ends cycles.
+const MAX_DEPTH: usize = 16;
+
+impl<'a> Lowerer<'a> {
+    /// Finds the declarations that `export =` names, which JavaScript knows by the name the
+    /// module is bound as.
+    pub(super) fn collect_js_aliases(&mut self) {
+        for file in 0..self.project.files.len() {
+            let Some(name) = self.export_assignment(file) else {
+                continue;
+            };
+            if name.contains('.') {
+                continue;
+            }
+            let scope = self.file_scope(file);
+            let alias = match self.umd_namespace(file) {
+                Some(global) => global,
+                None if scope.js_module.is_some() => "default".to_string(),
+                None => continue,
+            };
+            self.js_aliases.insert(scope.qualify(name), alias);
+        }
+        self.collect_export_names();
+    }
+
+    /// Finds the declarations of ES modules that their JavaScript module exports under other
+    /// names, and the files of the entry file's package that are imported from their own path.
+    fn collect_export_names(&mut self) {
+        let project = self.project;
+        let values: HashMap<String, usize> = (0..project.files.len())
+            .filter(|&file| project.files[file].is_module)
+            .flat_map(|file| {
+                let scope = self.file_scope(file);
+                project.files[file]
+                    .module
+                    .items
+                    .iter()
+                    .flat_map(value_names)
+                    .map(move |name| (scope.qualify(&name.name), file))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        let entry = self.export_names(0, &values);
+        for file in 0..project.files.len() {
+            let project_file = &project.files[file];
+            if !project_file.is_module
+                || self.export_assignment(file).is_some()
+                || self.umd_namespace(file).is_some()
+            {
+                continue;
+            }
+            let own = self.export_names(file, &values);
+            let mut names = own.clone();
+            // The files of the entry file's package are imported from the package, which
+            // exports what its entry file does.
+            if let (Some(module), Some(path), None) = (
+                &self.config.module,
+                &project_file.name,
+                &project_file.js_module,
+            ) {
+                if own.keys().all(|qualified| entry.contains_key(qualified)) {
+                    names = entry.clone();
+                } else {
+                    self.js_modules.insert(file, format!("{}/{}", module, path));
+                }
+            }
+            if self.file_scope(file).js_module.is_none() {
+                continue;
+            }
+            for (qualified, &declared) in &values {
+                if declared != file {
+                    continue;
+                }
+                let Some(js_name) = names.get(qualified) else {
+                    continue;
+                };
+                if qualified.rsplit('.').next() != Some(js_name.as_str()) {
+                    self.js_aliases.insert(qualified.clone(), js_name.clone());
+                }
+            }
+        }
+    }
+
+    /// The names module `file` exports the top-level values of files in `values` as, by
+    /// their qualified names. A value exported under its own name and others keeps its own.
+    fn export_names(
+        &self,
+        file: usize,
+        values: &HashMap<String, usize>,
+    ) -> HashMap<String, String> {
+        let mut public = BTreeSet::new();
+        self.public_names(file, 0, &mut HashSet::new(), &mut public);
+        let mut out: HashMap<String, String> = HashMap::new();
+        let scope = self.file_scope(file);
+        for item in &self.project.files[file].module.items {
+            if item.default {
+                for name in value_names(item) {
+                    out.insert(scope.qualify(&name.name), "default".to_string());
+                }
+            }
+        }
+        for name in public {
+            let candidates = self.export_candidates(&ModuleRef::File(file), &[&name]);
+            let Some(qualified) = candidates.into_iter().find(|c| values.contains_key(c)) else {
+                continue;
+            };
+            let own = qualified.rsplit('.').next() == Some(name.as_str());
+            if own || !out.contains_key(&qualified) {
+                out.insert(qualified, name);
+            }
+        }
+        out
+    }
+
+    /// Adds the names module `file` exports, other than `default`, to `out`.
+    fn public_names(
+        &self,
+        file: usize,
+        depth: usize,
+        seen: &mut HashSet<usize>,
+        out: &mut BTreeSet<String>,
+    ) {
+        if depth > MAX_DEPTH || !seen.insert(file) {
+            return;
+        }
+        let module = &self.project.files[file].module;
+        out.extend(
+            module
+                .items
+                .iter()
+                .filter(|item| item.export && !item.default)
+                .flat_map(value_names)
+                .map(|name| name.name.clone()),
+        );
+        out.extend(
+            self.exported_names(file)
+                .filter(|name| *name != "default")
+                .map(str::to_string),
+        );
+        for star in self.star_files(file) {
+            self.public_names(star, depth + 1, seen, out);
+        }
+    }
+
+    /// The global that the declarations of `file` are bound through, when it is a UMD
+    /// library and bindings use its global.
+    pub(super) fn umd_namespace(&self, file: usize) -> Option<String> {
+        match self.config.umd {
+            UmdMode::Global => self.umd_global(file).map(str::to_string),
+            UmdMode::Module => None,
+        }
+    }
+
+    /// The JavaScript name of declaration `name` in the current scope.
+    pub(super) fn js_name(&self, name: &str) -> String {
+        match self.js_aliases.get(&self.scope.qualify(name)) {
+            Some(alias) => alias.clone(),
+            None => name.to_string(),
+        }
+    }
+}
+
+/// The names of the values `item` declares.
+fn value_names(item: &ast::Item) -> Vec<&ast::Ident> {
+    match &item.kind {
+        ast::ItemKind::Variable(decl) => decl.declarators.iter().map(|d| &d.name).collect(),
+        ast::ItemKind::Function(_) | ast::ItemKind::Class(_) | ast::ItemKind::Namespace(_) => {
+            item.kind.name().into_iter().collect()
+        }
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,189 @@
+//! Augmentations of types that other crates bind.
+//!
+//! A global interface with the name of a type from `js-sys`, `web-sys` or
+//! [`Config::globals`](crate::Config::globals), as in `declare global { interface Window {
+//! myLib: MyLib } }`, adds members to that type. Since the type can't be reopened, the members
+//! go to an imported type of their own, `WindowMyLib`, and an extension trait implemented for
+//! the type, `WindowMyLibExt`, forwards to them. Each library adding members to a type gets a
+//! trait of its own, named after the library's package or module; members that the entry
+//! package adds without a [`Config::module`](crate::Config::module) to name it are bound on
+//! `WindowMembers` and reached through `WindowExt`.
+
+use dts_parser::ast;
+
+use super::interfaces::MemberTarget;
+use super::{merging, Lowerer};
+use crate::ir::{self, RustType};
+use crate::names;
+
+impl<'a> Lowerer<'a> {
+    /// Finds the global interfaces that reopen types of other crates, and takes them out of
+    /// the types declared in the project.
+    pub(super) fn collect_extensions(&mut self) {
+        let mut names: Vec<String> = self
+            .declarations
+            .iter()
+            .filter(|(_, declarations)| {
+                declarations.iter().all(|(scope, item)| {
+                    scope.global && matches!(item.kind, ast::ItemKind::Interface(_))
+                })
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        for name in names {
+            let Some(target) = self.builtin_type(&name) else {
+                continue;
+            };
+            if let Some(rust_name) = self.types.remove(&name) {
+                self.interfaces.remove(&rust_name);
+            }
+            self.generic_types.remove(&name);
+            self.extensions.insert(name, target);
+        }
+    }
+
+    /// Binds the members that the global interfaces named `name` add to `target`, a type of
+    /// another crate: for each library declaring some, an imported type with the members and
+    /// an extension trait forwarding to it.
+    pub(super) fn lower_extension(&mut self, name: &str, target: &str, out: &mut ir::Module) {
+        let mut libraries: Vec<(Option<String>, Vec<usize>)> = Vec::new();
+        for (i, (scope, _)) in self.declarations[name].iter().enumerate() {
+            let library = self.library_name(scope.file);
+            match libraries.iter_mut().find(|(l, _)| *l == library) {
+                Some((_, declarations)) => declarations.push(i),
+                None => libraries.push((library, vec![i])),
+            }
+        }
+        let target_name = target.rsplit("::").next().unwrap_or(target);
+        for (library, declarations) in libraries {
+            let (type_name, trait_name, library) = match &library {
+                Some(library) => {
+                    let type_name = format!("{}{}", target_name, names::type_name(library));
+                    let trait_name = format!("{}Ext", type_name);
+                    (type_name, trait_name, format!("`{}`", library))
+                }
+                None => (
+                    format!("{}Members", target_name),
+                    format!("{}Ext", target_name),
+                    "this library".to_string(),
+                ),
+            };
+            let binding = self.scope.rust_path(&type_name);
+            // The extended type goes first, so that the binding derefs to it.
+            let mut extends = vec![target.to_string()];
+            if target != "js_sys::Object" {
+                extends.push("js_sys::Object".to_string());
+            }
+            out.externs.push(ir::ExternItem::Type(ir::TypeDecl {
+                rust_name: binding.clone(),
+                js_name: name.to_string(),
+                extends,
+                is_type_of: Some("JsValue::is_object".to_string()),
+                doc: Some(format!(
+                    "`{}` with the members that {} adds to it; see [`{}`].",
+                    name, library, trait_name
+                )),
+            }));
+            let start = out.externs.len();
+            let mut member_target = MemberTarget {
+                this: &binding,
+                js_this: name,
+                instance: RustType::path(binding.clone()),
+                structural: true,
+                is_static: false,
+            };
+            self.this_type = Some(binding.clone());
+            for i in declarations {
+                let (scope, item) = self.declarations[name][i].clone();
+                let ast::ItemKind::Interface(decl) = &item.kind else {
+                    continue;
+                };
+                let first = out.externs.len();
+                self.in_scope(scope, |this| {
+                    this.with_type_params(&decl.type_params, |this| {
+                        this.in_context(name, |this| {
+                            for member in &decl.members {
+                                this.lower_member(member, &mut member_target, out);
+                            }
+                        })
+                    })
+                });
+                // The extended type's parameters are those of another crate's binding, which
+                // isn't generic; its members take and return them untyped. Traits don't get
+                // async wrappers, so promises are returned as they are.
+                let params: Vec<&str> = decl
+                    .type_params
+                    .iter()
+                    .map(|p| p.name.name.as_str())
+                    .collect();
+                for item in &mut out.externs[first..] {
+                    if let ir::ExternItem::Function(f) = item {
+                        erase_type_params(f, &params);
+                        f.resolves = None;
+                    }
+                }
+            }
+            self.this_type = None;
+            merging::dedup_members(&mut out.externs, start);
+            out.traits.push(ir::ExtensionTrait {
+                rust_name: self.scope.rust_path(&trait_name),
+                target: target.to_string(),
+                binding,
+                doc: Some(format!(
+                    "The members that {} adds to [`{}`].",
+                    library, target
+                )),
+            });
+        }
+    }
+
+    /// The name of the library whose declarations are in `file`: the package of files of
+    /// other packages, and otherwise the module the bindings import from, if configured.
+    fn library_name(&self, file: usize) -> Option<String> {
+        let specifier = self.project.files[file]
+            .js_module
+            .as_ref()
+            .or(self.config.module.as_ref())?;
+        let specifier = specifier.strip_prefix("@types/").unwrap_or(specifier);
+        let mut parts = specifier.split('/');
+        let first = parts.next().unwrap_or(specifier);
+        // `@scope/pkg` is named after `pkg`.
+        Some(match first.starts_with('@') {
+            true => parts.next().unwrap_or(first).to_string(),
+            false => first.to_string(),
+        })
+    }
+}
+
+/// Replaces type parameters `params` in the signature of `f` that `f` doesn't declare itself
+/// with `JsValue`.
+fn erase_type_params(f: &mut ir::Function, params: &[&str]) {
+    fn erase(ty: &RustType, params: &[&str]) -> RustType {
+        match ty {
+            RustType::Generic(name) if params.contains(&name.as_str()) => RustType::JsValue,
+            RustType::Path(path, args) => RustType::Path(
+                path.clone(),
+                args.iter().map(|a| erase(a, params)).collect(),
+            ),
+            RustType::Option(inner) => RustType::option(erase(inner, params)),
+            RustType::Slice(inner) => RustType::Slice(Box::new(erase(inner, params))),
+            RustType::Vec(inner) => RustType::Vec(Box::new(erase(inner, params))),
+            RustType::Map(inner) => RustType::Map(Box::new(erase(inner, params))),
+            RustType::Tuple(elems, rest) => RustType::Tuple(
+                elems.iter().map(|e| erase(e, params)).collect(),
+                rest.as_ref().map(|r| Box::new(erase(r, params))),
+            ),
+            ty => ty.clone(),
+        }
+    }
+    let params: Vec<&str> = params
+        .iter()
+        .copied()
+        .filter(|p| !f.type_params.iter().any(|own| own.name == *p))
+        .collect();
+    for param in &mut f.params {
+        param.ty = erase(&param.ty, &params);
+    }
+    f.ret = f.ret.as_ref().map(|ty| erase(ty, &params));
+}
@@ -0,0 +1,161 @@
+//! Lowering of type parameters.
+//!
+//! With [`GenericsMode::Typed`], a generic interface or class is bound untyped as
+//! `BoxErased`, with its type parameters as `JsValue`, and gets a phantom-typed wrapper
+//! `Box<T>` whose members cast to and from `T`. Type parameters and type arguments are bound
+//! as types implementing `JsCast`: `Box<string>` becomes `Box<js_sys::JsString>`. With
+//! [`GenericsMode::Erased`], type parameters are simply `JsValue`.
+
+use dts_parser::ast;
+
+use super::Lowerer;
+use crate::config::GenericsMode;
+use crate::ir::{self, RustType};
+use crate::names;
+
+impl<'a> Lowerer<'a> {
+    pub(super) fn typed_generics(&self) -> bool {
+        self.config.generics == GenericsMode::Typed
+    }
+
+    /// Registers generic type `name`, declared in the current scope as `qualified`, returning
+    /// the Rust path of its untyped binding.
+    pub(super) fn register_generic(&mut self, qualified: &str, name: &str) -> String {
+        let rust_name = names::type_name(name);
+        let erased = self.scope.rust_path(&format!("{}Erased", rust_name));
+        self.generic_types
+            .insert(qualified.to_string(), self.scope.rust_path(&rust_name));
+        erased
+    }
+
+    /// For generic type `name`, qualified, the documentation of its untyped binding and the
+    /// type of its instances, `Box<T>`. Other types keep their documentation.
+    pub(super) fn generic_wrapper(
+        &self,
+        name: &str,
+        params: &[ast::TypeParam],
+        doc: Option<&str>,
+    ) -> (Option<String>, Option<RustType>) {
+        match self.generic_types.get(name) {
+            Some(wrapper) => (
+                Some(format!(
+                    "The untyped binding of [`{}`].",
+                    wrapper.rsplit("::").next().unwrap_or(wrapper)
+                )),
+                Some(RustType::Path(
+                    wrapper.clone(),
+                    params
+                        .iter()
+                        .map(|p| RustType::Generic(p.name.name.clone()))
+                        .collect(),
+                )),
+            ),
+            None => (doc.map(str::to_string), None),
+        }
+    }
+
+    /// Defines the typed wrapper of a generic type, unless an earlier declaration of the
+    /// same type already did.
+    pub(super) fn push_generic(
+        &self,
+        name: &str,
+        type_params: Vec<ir::TypeParam>,
+        doc: Option<&str>,
+        out: &mut ir::Module,
+    ) {
+        let Some(wrapper) = self.generic_types.get(name) else {
+            return;
+        };
+        if out.generics.iter().any(|g| g.rust_name == *wrapper) {
+            return;
+        }
+        out.generics.push(ir::GenericType {
+            rust_name: wrapper.clone(),
+            erased: self.types[name].clone(),
+            type_params,
+            kind: ir::GenericKind::Declared,
+            doc: doc.map(str::to_string),
+        });
+    }
+
+    /// Runs `f` with `params` in scope, returning its result and the lowered parameters.
+    pub(super) fn with_type_params<R>(
+        &mut self,
+        params: &[ast::TypeParam],
+        f: impl FnOnce(&mut Self) -> R,
+    ) -> (R, Vec<ir::TypeParam>) {
+        let outer = self.type_params.len();
+        self.type_params
+            .extend(params.iter().map(|p| p.name.name.clone()));
+        let lowered = if self.typed_generics() {
+            params
+                .iter()
+                .map(|p| ir::TypeParam {
+                    name: p.name.name.clone(),
+                    default: p.default.as_ref().map(|d| self.type_arg(d)),
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let result = f(self);
+        self.type_params.truncate(outer);
+        (result, lowered)
+    }
+
+    /// Maps a type parameter in scope, or `None` if `name` isn't one.
+    pub(super) fn map_type_param(&self, name: &str) -> Option<RustType> {
+        if !self.type_params.iter().any(|p| p == name) {
+            return None;
+        }
+        Some(if self.typed_generics() {
+            RustType::Generic(name.to_string())
+        } else {
+            RustType::JsValue
+        })
+    }
+
+    /// Maps a reference to a generic type to its typed wrapper.
+    pub(super) fn map_generic_ref(&mut self, r: &ast::TypeRef, rust_name: String) -> RustType {
+        let args = r.type_args.iter().map(|a| self.type_arg(a)).collect();
+        RustType::Path(rust_name, args)
+    }
+
+    /// Maps a type argument to a type implementing `JsCast`.
+    pub(super) fn type_arg(&mut self, ty: &ast::Type) -> RustType {
+        js_cast_type(self.map_type(ty))
+    }
+}
+
+/// The `JsCast` type standing for `ty` as a type argument.
+pub(super) fn js_cast_type(ty: RustType) -> RustType {
+    match ty {
+        RustType::F64 => RustType::path("js_sys::Number"),
+        RustType::String => RustType::path("js_sys::JsString"),
+        RustType::Bool => RustType::path("js_sys::Boolean"),
+        ty @ (RustType::Path(..) | RustType::Generic(_)) => ty,
+        RustType::Vec(_) | RustType::Tuple(..) => RustType::path("js_sys::Array"),
+        RustType::Map(_) => RustType::path("js_sys::Object"),
+        RustType::TypedArray(t) => RustType::path(t.js_type()),
+        _ => RustType::JsValue,
+    }
+}
+
+/// The type with type parameters replaced by `JsValue`, copied arrays and tuples by
+/// `js_sys::Array`, copied records by `js_sys::Object` and typed arrays by their js-sys
+/// types, for places that can't be generic, such as the variants of a union enum.
+pub(super) fn without_type_params(ty: RustType) -> RustType {
+    match ty {
+        RustType::Generic(_) => RustType::JsValue,
+        RustType::Path(path, args) => {
+            RustType::Path(path, args.into_iter().map(without_type_params).collect())
+        }
+        RustType::Option(inner) => RustType::option(without_type_params(*inner)),
+        RustType::Slice(inner) => RustType::Slice(Box::new(without_type_params(*inner))),
+        // Copied arrays, tuples and records are only converted in signatures.
+        RustType::Vec(_) | RustType::Tuple(..) => RustType::path("js_sys::Array"),
+        RustType::Map(_) => RustType::path("js_sys::Object"),
+        RustType::TypedArray(t) => RustType::path(t.js_type()),
+        ty => ty,
+    }
+}
@@ -0,0 +1,514 @@
+//! Resolution of names across the files of a project.
+//!
+//! Each file has a table of the names it imports and one of the names it exports. A name
+//! written in a file is looked up in the file's own scopes first, then through its imports,
+//! and finally among global declarations. Following an import leads to the exports of the
+//! imported file, which are followed in turn through aliased exports, re-exports and
+//! `export * from` until a declaration is reached. Declarations are found by qualified name,
+//! like all others; see [`Scope`](super::namespaces::Scope).
+
+use std::collections::{HashMap, HashSet};
+
+use dts_parser::ast;
+
+use super::Lowerer;
+use crate::ir;
+use crate::names;
+
+/// How many imports and re-exports are followed before giving up, which also ends cycles.
+const MAX_DEPTH: usize = 16;
+
+/// A module that names can be imported from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) enum ModuleRef {
+    /// A file of the project.
+    File(usize),
+    /// A module declared with `declare module "pkg" { ... }`.
+    Ambient(String),
+}
+
+/// What an imported or exported name refers to.
+#[derive(Clone, Debug)]
+enum Binding {
+    /// A possibly dotted name in the top-level scope of the file, as in `export { a as b }`
+    /// or `import X = A.B`.
+    Local(String),
+    /// An export of a module, as in `import { a } from "m"`.
+    Export(ModuleRef, String),
+    /// A whole module, as in `import * as ns from "m"`.
+    Module(ModuleRef),
+}
+
+/// A function that a module re-exports, which is re-exported once every function has its
+/// final Rust name.
+#[derive(Debug)]
+pub(super) struct FunctionReexport {
+    /// The Rust module re-exporting the function.
+    module: Vec<String>,
+    /// The Rust module the function is bound in.
+    declared: Vec<String>,
+    /// The qualified name of the function, which begins the keys of its overloads.
+    qualified: String,
+    /// The Rust names of the function and of its re-export, before overloads are named.
+    name: String,
+    alias: String,
+}
+
+/// The imports and exports of a file.
+#[derive(Debug, Default)]
+pub(super) struct Links {
+    imports: HashMap<String, Binding>,
+    exports: HashMap<String, Binding>,
+    /// Modules all of whose exports are re-exported, by `export * from "m"`.
+    stars: Vec<ModuleRef>,
+    /// The possibly dotted name of the value that is the whole module, by `export = name`.
+    assigned: Option<String>,
+    /// The global of a UMD library, by `export as namespace Name`.
+    umd: Option<String>,
+    /// The names imported from modules that aren't found, which are reported once, where
+    /// they are imported.
+    unresolved: HashSet<String>,
+}
+
+impl<'a> Lowerer<'a> {
+    /// Builds the import and export tables of every file.
+    pub(super) fn link_files(&mut self) {
+        let project = self.project;
+        let ambient = project.ambient_modules();
+        let module_ref = |file: usize, specifier: &str| match project.files[file]
+            .resolved
+            .get(specifier)
+        {
+            Some(&i) => Some(ModuleRef::File(i)),
+            None if ambient.contains(specifier) => Some(ModuleRef::Ambient(specifier.to_string())),
+            None => None,
+        };
+        self.links = project
+            .files
+            .iter()
+            .enumerate()
+            .map(|(i, file)| {
+                let mut links = Links::default();
+                for item in &file.module.items {
+                    links.add(item, |specifier| module_ref(i, specifier));
+                }
+                links
+            })
+            .collect();
+    }
+
+    /// The module a specifier written in the current file refers to.
+    pub(super) fn module_ref(&self, specifier: &str) -> Option<ModuleRef> {
+        let file = &self.project.files[self.scope.file];
+        match file.resolved.get(specifier) {
+            Some(&i) => Some(ModuleRef::File(i)),
+            None => self
+                .project
+                .ambient_modules()
+                .contains(specifier)
+                .then(|| ModuleRef::Ambient(specifier.to_string())),
+        }
+    }
+
+    /// The qualified names a possibly qualified `name` written in the current scope may refer
+    /// to, most likely first.
+    pub(super) fn candidates(&self, name: &str) -> Vec<String> {
+        let mut out = self.scope.candidates(name);
+        // Imports shadow global declarations, but not those of the file itself.
+        let global = out.pop();
+        let path: Vec<&str> = name.split('.').collect();
+        if let Some(binding) = self.links[self.scope.file].imports.get(path[0]) {
+            self.expand(self.scope.file, binding, &path[1..], 0, &mut out);
+        }
+        out.extend(global);
+        // The globals of UMD libraries stand for their modules.
+        for (file, links) in self.links.iter().enumerate() {
+            if links.umd.as_deref() == Some(path[0]) {
+                let binding = Binding::Module(ModuleRef::File(file));
+                self.expand(file, &binding, &path[1..], 0, &mut out);
+            }
+        }
+        out
+    }
+
+    /// Whether the first part of `name` is imported into the current file from a module that
+    /// isn't found.
+    pub(super) fn is_unresolved_import(&self, name: &str) -> bool {
+        let first = name.split('.').next().unwrap_or(name);
+        self.links[self.scope.file].unresolved.contains(first)
+    }
+
+    /// The names `file` exports by `export { ... }` statements.
+    pub(super) fn exported_names(&self, file: usize) -> impl Iterator<Item = &str> {
+        self.links[file].exports.keys().map(String::as_str)
+    }
+
+    /// The files all of whose exports `file` re-exports, by `export * from`.
+    pub(super) fn star_files(&self, file: usize) -> impl Iterator<Item = usize> + '_ {
+        self.links[file].stars.iter().filter_map(|star| match star {
+            ModuleRef::File(file) => Some(*file),
+            ModuleRef::Ambient(_) => None,
+        })
+    }
+
+    /// The possibly dotted name that `export = name` in `file` makes the whole module.
+    pub(super) fn export_assignment(&self, file: usize) -> Option<&str> {
+        self.links[file].assigned.as_deref()
+    }
+
+    /// The global that `file` defines as a UMD library, by `export as namespace Name`.
+    pub(super) fn umd_global(&self, file: usize) -> Option<&str> {
+        self.links[file].umd.as_deref()
+    }
+
+    /// The qualified names that `path` may refer to as an export of `module`.
+    pub(super) fn export_candidates(&self, module: &ModuleRef, path: &[&str]) -> Vec<String> {
+        let mut out = Vec::new();
+        self.exported(module, path, 0, &mut out);
+        out
+    }
+
+    /// The entry for the first of `candidates` that `map` has.
+    pub(super) fn find<'m, V>(
+        map: &'m HashMap<String, V>,
+        candidates: &[String],
+    ) -> Option<(&'m String, &'m V)> {
+        candidates.iter().find_map(|key| map.get_key_value(key))
+    }
+
+    /// Looks up a possibly qualified `name` written in the current scope.
+    pub(super) fn lookup<'m, V>(
+        &self,
+        map: &'m HashMap<String, V>,
+        name: &str,
+    ) -> Option<(&'m String, &'m V)> {
+        Self::find(map, &self.candidates(name))
+    }
+
+    /// Follows `binding` of `file`, then `rest` of the path inside what it refers to.
+    fn expand(
+        &self,
+        file: usize,
+        binding: &Binding,
+        rest: &[&str],
+        depth: usize,
+        out: &mut Vec<String>,
+    ) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+        match binding {
+            Binding::Local(name) => {
+                let mut path: Vec<&str> = name.split('.').collect();
+                path.extend(rest);
+                self.top_level(file, &path, depth + 1, out);
+            }
+            Binding::Export(module, name) => {
+                let mut path = vec![name.as_str()];
+                path.extend(rest);
+                self.exported(module, &path, depth + 1, out);
+            }
+            Binding::Module(module) => self.exported(module, rest, depth + 1, out),
+        }
+    }
+
+    /// The qualified names `path` may refer to when written at the top level of `file`,
+    /// leaving out global declarations.
+    fn top_level(&self, file: usize, path: &[&str], depth: usize, out: &mut Vec<String>) {
+        let dotted = path.join(".");
+        match &self.project.files[file].name {
+            Some(name) => out.push(format!("{}.{}", name, dotted)),
+            None => out.push(dotted),
+        }
+        if let Some(binding) = self.links[file].imports.get(path[0]) {
+            self.expand(file, binding, &path[1..], depth, out);
+        }
+    }
+
+    /// The qualified names `path` may refer to as an export of `module`.
+    fn exported(&self, module: &ModuleRef, path: &[&str], depth: usize, out: &mut Vec<String>) {
+        if depth > MAX_DEPTH {
+            return;
+        }
+        match module {
+            ModuleRef::Ambient(_) if path.is_empty() => {}
+            ModuleRef::Ambient(name) => out.push(format!("{}.{}", name, path.join("."))),
+            ModuleRef::File(file) => {
+                let links = &self.links[*file];
+                // A module made of `export = name` is that value, and its exports are the
+                // value's members.
+                if let Some(assigned) = &links.assigned {
+                    let binding = Binding::Local(assigned.clone());
+                    self.expand(*file, &binding, path, depth, out);
+                }
+                if path.is_empty() {
+                    return;
+                }
+                if let Some(binding) = links.exports.get(path[0]) {
+                    self.expand(*file, binding, &path[1..], depth, out);
+                }
+                // Declarations are found whether they are exported or not, which saves
+                // following `export` modifiers.
+                self.top_level(*file, path, depth, out);
+                for star in &links.stars {
+                    self.exported(star, path, depth + 1, out);
+                }
+            }
+        }
+    }
+
+    /// The Rust items the current file re-exports, as `pub use` declarations.
+    pub(super) fn reexports(&mut self, module: &ast::Module) -> Vec<ir::Reexport> {
+        let mut out: Vec<ir::Reexport> = Vec::new();
+        let here = &self.scope.rust;
+        for item in &module.items {
+            let ast::ItemKind::Export(decl) = &item.kind else {
+                continue;
+            };
+            match decl {
+                ast::ExportDecl::All {
+                    alias, module: m, ..
+                } => {
+                    let Some(ModuleRef::File(file)) = self.module_ref(m) else {
+                        continue;
+                    };
+                    let target = &self.project.files[file];
+                    if !target.is_module || target.rust.is_empty() {
+                        continue;
+                    }
+                    let path = target.rust.join("::");
+                    match alias {
+                        None => out.push(ir::Reexport {
+                            path: format!("{}::*", path),
+                            alias: None,
+                        }),
+                        Some(alias) => {
+                            let alias = names::snake_case(&alias.name);
+                            let mut own = here.clone();
+                            own.push(alias.clone());
+                            if own != target.rust {
+                                out.push(ir::Reexport {
+                                    path,
+                                    alias: Some(alias),
+                                });
+                            }
+                        }
+                    }
+                }
+                ast::ExportDecl::Named {
+                    specifiers,
+                    module: m,
+                } => {
+                    for spec in specifiers {
+                        let candidates = match m {
+                            Some((m, _)) => match self.module_ref(m) {
+                                Some(m) => self.export_candidates(&m, &[&spec.name.name]),
+                                None => continue,
+                            },
+                            None => {
+                                let mut out = Vec::new();
+                                self.top_level(self.scope.file, &[&spec.name.name], 0, &mut out);
+                                out
+                            }
+                        };
+                        if let Some((qualified, (scope, item))) =
+                            Self::find(&self.functions, &candidates)
+                        {
+                            if let ast::ItemKind::Function(ast::FunctionDecl {
+                                name: Some(name),
+                                ..
+                            }) = &item.kind
+                            {
+                                self.function_reexports.push(FunctionReexport {
+                                    module: here.clone(),
+                                    declared: scope.rust.clone(),
+                                    qualified: qualified.clone(),
+                                    name: names::snake_case(&name.name),
+                                    alias: names::snake_case(&spec.local().name),
+                                });
+                            }
+                        }
+                        let local = names::type_name(&spec.local().name);
+                        for (path, alias) in self.exported_items(&candidates, &local) {
+                            let (parent, last) = path.rsplit_once("::").unwrap_or(("", &path));
+                            let in_place = parent.split("::").filter(|p| !p.is_empty()).eq(here);
+                            if in_place && last == alias {
+                                continue;
+                            }
+                            out.push(ir::Reexport {
+                                alias: (last != alias).then_some(alias),
+                                path,
+                            });
+                        }
+                    }
+                }
+                ast::ExportDecl::Default(_)
+                | ast::ExportDecl::Assign(_)
+                | ast::ExportDecl::AsNamespace(_) => {}
+            }
+        }
+        let mut seen = HashSet::new();
+        out.retain(|r| seen.insert((r.path.clone(), r.alias.clone())));
+        out
+    }
+
+    /// Re-exports the functions that modules re-export, with each of their overloads and
+    /// async wrappers. An overload named `make_with_str` re-exported as `mk` is
+    /// `mk_with_str`.
+    pub(super) fn push_function_reexports(&mut self, out: &mut ir::Module) {
+        for r in std::mem::take(&mut self.function_reexports) {
+            let mut declared = &*out;
+            for name in &r.declared {
+                match declared.modules.iter().find(|m| &m.name == name) {
+                    Some(module) => declared = module,
+                    None => break,
+                }
+            }
+            let prefix = format!("{}(", r.qualified);
+            let mut found: Vec<String> = Vec::new();
+            for item in &declared.externs {
+                let ir::ExternItem::Function(f) = item else {
+                    continue;
+                };
+                if f.kind == ir::FunctionKind::Free && f.key.starts_with(&prefix) {
+                    found.push(f.rust_name.clone());
+                    found.extend(f.async_name.clone());
+                }
+            }
+            let mut reexports = Vec::new();
+            for name in found {
+                let alias = match name.strip_prefix(&r.name) {
+                    Some(rest) => format!("{}{}", r.alias, rest),
+                    None => name.clone(),
+                };
+                if r.module == r.declared && alias == name {
+                    continue;
+                }
+                let mut path = r.declared.clone();
+                path.push(name.clone());
+                reexports.push(ir::Reexport {
+                    path: path.join("::"),
+                    alias: (alias != name).then_some(alias),
+                });
+            }
+            let mut module = &mut *out;
+            for name in &r.module {
+                module = module.submodule_mut(name);
+            }
+            for reexport in reexports {
+                if !module.reexports.contains(&reexport) {
+                    module.reexports.push(reexport);
+                }
+            }
+        }
+    }
+
+    /// The Rust paths of the type, typed wrapper or enum that `candidates` name, with the
+    /// names to re-export them under when they are exported as `local`.
+    fn exported_items(&self, candidates: &[String], local: &str) -> Vec<(String, String)> {
+        let mut out = Vec::new();
+        if let Some((_, wrapper)) = Self::find(&self.generic_types, candidates) {
+            out.push((wrapper.clone(), local.to_string()));
+            if let Some((_, erased)) = Self::find(&self.types, candidates) {
+                out.push((erased.clone(), format!("{}Erased", local)));
+            }
+        } else if let Some((_, path)) = Self::find(&self.types, candidates) {
+            out.push((path.clone(), local.to_string()));
+        } else if let Some((_, ir::RustType::Path(path, _))) =
+            Self::find(&self.enum_types, candidates)
+        {
+            out.push((path.clone(), local.to_string()));
+        }
+        out
+    }
+}
+
+impl Links {
+    fn add(&mut self, item: &ast::Item, module_ref: impl Fn(&str) -> Option<ModuleRef>) {
+        match &item.kind {
+            ast::ItemKind::Import(decl) => {
+                let Some(module) = module_ref(&decl.module) else {
+                    self.unresolved.extend(
+                        decl.default
+                            .iter()
+                            .chain(&decl.namespace)
+                            .chain(decl.named.iter().map(|spec| spec.local()))
+                            .map(|name| name.name.clone()),
+                    );
+                    return;
+                };
+                if let Some(default) = &decl.default {
+                    self.imports.insert(
+                        default.name.clone(),
+                        Binding::Export(module.clone(), "default".to_string()),
+                    );
+                }
+                if let Some(namespace) = &decl.namespace {
+                    self.imports
+                        .insert(namespace.name.clone(), Binding::Module(module.clone()));
+                }
+                for spec in &decl.named {
+                    self.imports.insert(
+                        spec.local().name.clone(),
+                        Binding::Export(module.clone(), spec.name.name.clone()),
+                    );
+                }
+            }
+            ast::ItemKind::ImportAlias(decl) => {
+                let binding = Binding::Local(decl.target.to_dotted());
+                if item.export {
+                    self.exports.insert(decl.name.name.clone(), binding.clone());
+                }
+                self.imports.insert(decl.name.name.clone(), binding);
+            }
+            ast::ItemKind::Export(ast::ExportDecl::All { alias, module, .. }) => {
+                let Some(module) = module_ref(module) else {
+                    return;
+                };
+                match alias {
+                    Some(alias) => {
+                        self.exports
+                            .insert(alias.name.clone(), Binding::Module(module));
+                    }
+                    None => self.stars.push(module),
+                }
+            }
+            ast::ItemKind::Export(ast::ExportDecl::Named { specifiers, module }) => {
+                let module = match module {
+                    Some((module, _)) => match module_ref(module) {
+                        Some(module) => Some(module),
+                        None => return,
+                    },
+                    None => None,
+                };
+                for spec in specifiers {
+                    let binding = match &module {
+                        Some(module) => Binding::Export(module.clone(), spec.name.name.clone()),
+                        None => Binding::Local(spec.name.name.clone()),
+                    };
+                    self.exports.insert(spec.local().name.clone(), binding);
+                }
+            }
+            ast::ItemKind::Export(ast::ExportDecl::Default(name)) => {
+                self.exports
+                    .insert("default".to_string(), Binding::Local(name.name.clone()));
+            }
+            // Default imports of a CommonJS module get the whole module.
+            ast::ItemKind::Export(ast::ExportDecl::Assign(name)) => {
+                self.assigned = Some(name.to_dotted());
+                self.exports
+                    .insert("default".to_string(), Binding::Local(name.to_dotted()));
+            }
+            ast::ItemKind::Export(ast::ExportDecl::AsNamespace(name)) => {
+                self.umd = Some(name.name.clone());
+            }
+            _ if item.default => {
+                if let Some(name) = item.kind.name() {
+                    self.exports
+                        .insert("default".to_string(), Binding::Local(name.name.clone()));
+                }
+            }
+            _ => {}
+        }
+    }
+}
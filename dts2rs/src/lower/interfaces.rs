@@ -0,0 +1,314 @@
+//! Lowering of interfaces to opaque extern types with structural accessors.
+
+use dts_parser::ast::{self, MemberKind};
+
+use super::{merging, Lowerer};
+use crate::ir::{self, FunctionKind, RustType};
+use crate::names;
+
+/// Where a member binding is attached.
+pub(super) struct MemberTarget<'t> {
+    /// The Rust path of the type.
+    pub this: &'t str,
+    /// The qualified TypeScript name of the type.
+    pub js_this: &'t str,
+    /// The type's instances, which is the typed wrapper for generic types.
+    pub instance: RustType,
+    pub structural: bool,
+    /// Whether members are attached to the class itself rather than to its instances.
+    pub is_static: bool,
+}
+
+impl MemberTarget<'_> {
+    /// Prefix of override keys of static members.
+    fn static_prefix(&self) -> &'static str {
+        if self.is_static {
+            "static "
+        } else {
+            ""
+        }
+    }
+
+    fn method_kind(&self) -> FunctionKind {
+        if self.is_static {
+            FunctionKind::StaticMethod {
+                class: self.this.to_string(),
+            }
+        } else {
+            FunctionKind::Method {
+                this: self.this.to_string(),
+                structural: self.structural,
+            }
+        }
+    }
+
+    fn getter_kind(&self) -> FunctionKind {
+        if self.is_static {
+            FunctionKind::StaticGetter {
+                class: self.this.to_string(),
+            }
+        } else {
+            FunctionKind::Getter {
+                this: self.this.to_string(),
+                structural: self.structural,
+            }
+        }
+    }
+
+    fn setter_kind(&self) -> FunctionKind {
+        if self.is_static {
+            FunctionKind::StaticSetter {
+                class: self.this.to_string(),
+            }
+        } else {
+            FunctionKind::Setter {
+                this: self.this.to_string(),
+                structural: self.structural,
+            }
+        }
+    }
+}
+
+impl<'a> Lowerer<'a> {
+    pub(super) fn lower_interface(
+        &mut self,
+        decl: &ast::InterfaceDecl,
+        item: &ast::Item,
+        out: &mut ir::Module,
+    ) {
+        let qualified = self.scope.qualify(&decl.name.name);
+        if !self.is_primary(&qualified, item) {
+            return;
+        }
+        if let Some(target) = self.extensions.get(&qualified).cloned() {
+            self.lower_extension(&qualified, &target, out);
+            return;
+        }
+        let doc = item.doc.as_deref();
+        let start = out.externs.len();
+        let rust_name = self.types[&qualified].clone();
+        let extends = self.ancestors(&qualified, decl.name.span);
+        let ((), type_params) = self.with_type_params(&decl.type_params, |this| {
+            let (doc, instance) = this.generic_wrapper(&qualified, &decl.type_params, doc);
+            out.externs.push(ir::ExternItem::Type(ir::TypeDecl {
+                rust_name: rust_name.clone(),
+                js_name: decl.name.name.clone(),
+                extends,
+                // Interfaces have no class to check against with `instanceof`; any object may
+                // implement them.
+                is_type_of: Some("JsValue::is_object".to_string()),
+                doc,
+            }));
+            this.this_type = Some(rust_name.clone());
+            let mut target = MemberTarget {
+                this: &rust_name,
+                js_this: &qualified,
+                instance: instance.unwrap_or_else(|| RustType::path(rust_name.clone())),
+                structural: true,
+                is_static: false,
+            };
+            this.in_context(&decl.name.name, |this| {
+                for member in &decl.members {
+                    this.lower_member(member, &mut target, out);
+                }
+            });
+            this.lower_merged_members(&qualified, item, &mut target, out);
+            this.this_type = None;
+        });
+        merging::dedup_members(&mut out.externs, start);
+        self.push_generic(&qualified, type_params, doc, out);
+    }
+
+    pub(super) fn lower_member(
+        &mut self,
+        member: &ast::Member,
+        target: &mut MemberTarget,
+        out: &mut ir::Module,
+    ) {
+        let doc = member.doc.as_deref();
+        match &member.kind {
+            MemberKind::Property(prop) => {
+                let Some(js_name) = self.prop_js_name(&prop.name) else {
+                    return;
+                };
+                let ty = self.in_context(js_name, |this| this.map_param_type(prop.ty.as_ref()));
+                if let Some(prop_ty) = &prop.ty {
+                    self.report_defaults(prop_ty, &ty);
+                }
+                let ty = if prop.optional {
+                    RustType::option(ty)
+                } else {
+                    ty
+                };
+                self.push_getter(target, js_name, ty.clone(), doc, out);
+                if !prop.readonly {
+                    self.push_setter(target, js_name, ty, doc, out);
+                }
+            }
+            MemberKind::Method(method) => {
+                if let Some(js_name) = self.prop_js_name(&method.name) {
+                    self.push_method(target, js_name, &method.sig, doc, out);
+                }
+            }
+            MemberKind::Getter(getter) => {
+                let Some(js_name) = self.prop_js_name(&getter.name) else {
+                    return;
+                };
+                let ty = self.in_context(js_name, |this| this.map_param_type(getter.ty.as_ref()));
+                self.push_getter(target, js_name, ty, doc, out);
+            }
+            MemberKind::Setter(setter) => {
+                let Some(js_name) = self.prop_js_name(&setter.name) else {
+                    return;
+                };
+                let ty = self.in_context(js_name, |this| {
+                    this.map_param_type(setter.param.ty.as_ref())
+                });
+                self.push_setter(target, js_name, ty, doc, out);
+            }
+            MemberKind::Call(_) => self.diags.warn(
+                self.file,
+                member.span,
+                "call signatures are not supported yet; skipped",
+            ),
+            MemberKind::Construct(_) => self.diags.warn(
+                self.file,
+                member.span,
+                "construct signatures are not supported yet; skipped",
+            ),
+            MemberKind::Index(sig) => {
+                self.push_index_accessors(target, sig, member.span, doc, out);
+            }
+        }
+    }
+
+    /// The JavaScript name of a member, or `None` (with a warning) for computed names.
+    pub(super) fn prop_js_name<'n>(&mut self, name: &'n ast::PropName) -> Option<&'n str> {
+        let js_name = name.as_str();
+        if js_name.is_none() {
+            self.diags.warn(
+                self.file,
+                name.span(),
+                "members with computed names are skipped",
+            );
+        }
+        js_name
+    }
+
+    pub(super) fn push_method(
+        &mut self,
+        target: &mut MemberTarget,
+        js_name: &str,
+        sig: &ast::Signature,
+        doc: Option<&str>,
+        out: &mut ir::Module,
+    ) {
+        let ((params, variadic, ret, resolves), type_params) =
+            self.with_type_params(&sig.type_params, |this| {
+                this.in_context(js_name, |this| {
+                    let (params, variadic) = this.lower_params(&sig.params);
+                    (
+                        params,
+                        variadic,
+                        this.map_return_type(sig.ret.as_ref()),
+                        this.map_resolved_type(sig.ret.as_ref()),
+                    )
+                })
+            });
+        out.externs.push(ir::ExternItem::Function(ir::Function {
+            kind: target.method_kind(),
+            rust_name: names::snake_case(js_name),
+            key: format!(
+                "{}{}.{}({})",
+                target.static_prefix(),
+                target.js_this,
+                js_name,
+                self.signature_key(&sig.params)
+            ),
+            js_name: js_name.to_string(),
+            type_params,
+            params,
+            ret,
+            variadic,
+            resolves,
+            async_name: None,
+            doc: doc.map(str::to_string),
+        }));
+    }
+
+    pub(super) fn push_getter(
+        &mut self,
+        target: &mut MemberTarget,
+        js_name: &str,
+        ty: RustType,
+        doc: Option<&str>,
+        out: &mut ir::Module,
+    ) {
+        out.externs.push(ir::ExternItem::Function(ir::Function {
+            kind: target.getter_kind(),
+            rust_name: match digits_first(js_name) {
+                Some(name) => format!("get_{}", name),
+                None => names::snake_case(js_name),
+            },
+            key: format!(
+                "get {}{}.{}",
+                target.static_prefix(),
+                target.js_this,
+                js_name
+            ),
+            js_name: js_name.to_string(),
+            type_params: Vec::new(),
+            params: Vec::new(),
+            ret: Some(ty),
+            variadic: false,
+            resolves: None,
+            async_name: None,
+            doc: doc.map(str::to_string),
+        }));
+    }
+
+    pub(super) fn push_setter(
+        &mut self,
+        target: &mut MemberTarget,
+        js_name: &str,
+        ty: RustType,
+        doc: Option<&str>,
+        out: &mut ir::Module,
+    ) {
+        out.externs.push(ir::ExternItem::Function(ir::Function {
+            kind: target.setter_kind(),
+            rust_name: match digits_first(js_name) {
+                Some(name) => format!("set_{}", name),
+                None => format!("set_{}", names::snake_case(js_name).trim_end_matches('_')),
+            },
+            key: format!(
+                "set {}{}.{}",
+                target.static_prefix(),
+                target.js_this,
+                js_name
+            ),
+            js_name: js_name.to_string(),
+            type_params: Vec::new(),
+            params: vec![ir::Param {
+                name: "value".to_string(),
+                descriptor: "value".to_string(),
+                ty,
+            }],
+            ret: None,
+            variadic: false,
+            resolves: None,
+            async_name: None,
+            doc: doc.map(str::to_string),
+        }));
+    }
+}
+
+/// The snake case name of a property starting with a digit, such as an element `0` of a
+/// tuple-like type, without the underscore escaping it. Its accessors are `get_0` and
+/// `set_0`.
+fn digits_first(js_name: &str) -> Option<String> {
+    let name = names::snake_case(js_name);
+    name.strip_prefix('_')
+        .filter(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+        .map(str::to_string)
+}
@@ -0,0 +1,273 @@
+//! Lowering of intersection types.
+//!
+//! `type Props = BaseProps & { extra: string }` is bound like an interface extending
+//! `BaseProps`: the binding of `Props` extends each declared interface or class and each
+//! built-in type the intersection includes, which gives it upcasts to them, and has the
+//! members of the other constituents, such as object literal types and evaluated utility
+//! types. Aliases of intersections and instances of generic aliases of them are flattened.
+//!
+//! A property that constituents declare with different types has the narrower one, and one
+//! declared with conflicting types, neither of which is assignable to the other, is reported
+//! and has the first. Methods declared more than once are overloads.
+//!
+//! Elsewhere, an intersection with a primitive type, such as the "branded"
+//! `string & { __brand: "id" }`, is bound as that primitive, and one of object types as
+//! `js_sys::Object`.
+
+use dts_parser::ast::{self, KeywordType, MemberKind, TypeKind};
+
+use super::computed::member_type;
+use super::evaluate::{member_name, Members, MAX_DEPTH};
+use super::namespaces::Scope;
+use super::Lowerer;
+use crate::ir::RustType;
+
+/// An evaluated intersection of object types.
+pub(super) struct Intersection {
+    /// The declared and built-in types it includes, by qualified name.
+    pub parents: Vec<String>,
+    /// The members of the declared types it includes.
+    pub inherited: Members,
+    /// The members of its other constituents, merged.
+    pub members: Members,
+    /// The names of properties declared with conflicting types.
+    pub conflicts: Vec<String>,
+}
+
+impl Intersection {
+    /// All members of the intersection, with those of the declared types it includes unless
+    /// another constituent redeclares them.
+    pub(super) fn all_members(self) -> Members {
+        let own: Vec<String> = self
+            .members
+            .iter()
+            .filter_map(|(_, m)| member_name(m).map(str::to_string))
+            .collect();
+        self.inherited
+            .into_iter()
+            .filter(|(_, m)| member_name(m).is_none_or(|n| !own.iter().any(|o| o == n)))
+            .chain(self.members)
+            .collect()
+    }
+}
+
+impl<'a> Lowerer<'a> {
+    /// Maps an intersection that isn't the whole body of a type alias.
+    pub(super) fn map_intersection(&mut self, ty: &ast::Type, types: &[ast::Type]) -> RustType {
+        if let Some(primitive) = types.iter().find(|t| {
+            matches!(
+                t.kind,
+                TypeKind::Keyword(_) | TypeKind::Literal(_) | TypeKind::Template { .. }
+            ) && !matches!(t.kind, TypeKind::Keyword(KeywordType::Object))
+        }) {
+            return self.map_type(primitive);
+        }
+        match self.intersection(ty, 0) {
+            Some(_) => RustType::path("js_sys::Object"),
+            None => RustType::JsValue,
+        }
+    }
+
+    /// Evaluates intersection `ty`, an instance of a generic alias of one, or `None` if it
+    /// isn't an intersection of object types.
+    pub(super) fn intersection(&mut self, ty: &ast::Type, depth: usize) -> Option<Intersection> {
+        let mut parts = Vec::new();
+        match &ty.kind {
+            TypeKind::Intersection(_) => self.intersection_parts(ty, &mut parts, depth + 1)?,
+            TypeKind::Reference(r) if !r.type_args.is_empty() => {
+                let (scope, ty) = self.alias_type(r, depth + 1)?;
+                if !matches!(ty.kind, TypeKind::Intersection(_)) {
+                    return None;
+                }
+                self.in_scope(scope, |this| {
+                    this.intersection_parts(&ty, &mut parts, depth + 1)
+                })?;
+            }
+            _ => return None,
+        }
+        let mut out = Intersection {
+            parents: Vec::new(),
+            inherited: Vec::new(),
+            members: Vec::new(),
+            conflicts: Vec::new(),
+        };
+        for (scope, part) in parts {
+            self.in_scope(scope, |this| this.add_part(&part, &mut out, depth + 1))?;
+        }
+        Some(out)
+    }
+
+    /// Collects the constituents of intersection `ty`, with those of intersections it
+    /// includes and of instances of generic aliases of them.
+    fn intersection_parts(
+        &mut self,
+        ty: &ast::Type,
+        out: &mut Vec<(Scope, ast::Type)>,
+        depth: usize,
+    ) -> Option<()> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let TypeKind::Intersection(types) = &ty.kind else {
+            out.push((self.scope.clone(), ty.clone()));
+            return Some(());
+        };
+        for ty in types {
+            if let TypeKind::Reference(r) = &ty.kind {
+                let declared = self
+                    .lookup(&self.declarations, &r.name.to_dotted())
+                    .is_some();
+                let instance = !declared && !r.type_args.is_empty();
+                if let (true, Some((scope, aliased))) = (instance, self.alias_type(r, depth + 1)) {
+                    if matches!(aliased.kind, TypeKind::Intersection(_)) {
+                        self.in_scope(scope, |this| {
+                            this.intersection_parts(&aliased, out, depth + 1)
+                        })?;
+                        continue;
+                    }
+                }
+            }
+            self.intersection_parts(ty, out, depth + 1)?;
+        }
+        Some(())
+    }
+
+    /// Adds constituent `part` of an intersection to `out`: a declared or built-in type, or an
+    /// alias bound like one, as a parent, another object type with its members.
+    fn add_part(&mut self, part: &ast::Type, out: &mut Intersection, depth: usize) -> Option<()> {
+        match &part.kind {
+            TypeKind::Keyword(KeywordType::Object) => return Some(()),
+            TypeKind::Keyword(_) | TypeKind::Literal(_) | TypeKind::Template { .. } => return None,
+            TypeKind::Reference(r) => {
+                let name = r.name.to_dotted();
+                if let Some((qualified, _)) = self.lookup(&self.declarations, &name) {
+                    let qualified = qualified.clone();
+                    let inherited = self.declared_members(&qualified, &r.type_args, depth + 1)?;
+                    for member in inherited {
+                        self.merge(&mut out.inherited, member, &mut out.conflicts, depth);
+                    }
+                    if !out.parents.contains(&qualified) {
+                        out.parents.push(qualified);
+                    }
+                    return Some(());
+                }
+                if let Some((qualified, inherited)) = self.object_alias(r, depth + 1) {
+                    for member in inherited {
+                        self.merge(&mut out.inherited, member, &mut out.conflicts, depth);
+                    }
+                    if !out.parents.contains(&qualified) {
+                        out.parents.push(qualified);
+                    }
+                    return Some(());
+                }
+                if !self.is_declared(&name) && self.is_builtin(&name) {
+                    if !out.parents.contains(&name) {
+                        out.parents.push(name);
+                    }
+                    return Some(());
+                }
+            }
+            _ => {}
+        }
+        for member in self.members_of(part, depth + 1)? {
+            // Properties redeclared by other constituents are compared with the declared ones.
+            let declared = member_name(&member.1).and_then(|name| {
+                out.inherited
+                    .iter()
+                    .find(|(_, m)| member_name(m) == Some(name))
+                    .cloned()
+            });
+            if let Some(declared) = declared.filter(|(scope, _)| *scope == member.0) {
+                let mut earlier = vec![declared];
+                if !self.merge(&mut earlier, member.clone(), &mut out.conflicts, depth) {
+                    continue;
+                }
+            }
+            self.merge(&mut out.members, member, &mut out.conflicts, depth);
+        }
+        Some(())
+    }
+
+    /// The qualified name and members of the type alias `r` refers to, if it is bound like an
+    /// interface: if it evaluates to an object type of its own. Aliases are only collected as
+    /// such in order of name, so this doesn't depend on whether it has been yet.
+    fn object_alias(&mut self, r: &ast::TypeRef, depth: usize) -> Option<(String, Members)> {
+        if !r.type_args.is_empty() || depth > MAX_DEPTH {
+            return None;
+        }
+        let (qualified, (scope, item)) = self.lookup(&self.aliases, &r.name.to_dotted())?;
+        let (qualified, scope) = (qualified.clone(), scope.clone());
+        let ast::ItemKind::TypeAlias(decl) = &item.kind else {
+            return None;
+        };
+        if !decl.type_params.is_empty() || self.declarations.contains_key(&qualified) {
+            return None;
+        }
+        let ty = decl.ty.clone();
+        let members = self.in_scope(scope, |this| {
+            if let Some(intersection) = this.intersection(&ty, depth + 1) {
+                return Some(intersection.all_members());
+            }
+            if this.is_computed_object(&ty, false, depth + 1) {
+                this.members_of(&ty, depth + 1)
+            } else {
+                None
+            }
+        })?;
+        Some((qualified, members))
+    }
+
+    /// Adds `member` to `members` unless a property of the same name is already there with
+    /// the same or a narrower type, replacing one with a wider type. Properties with
+    /// conflicting types are added to `conflicts`. Returns whether `member` was added.
+    fn merge(
+        &mut self,
+        members: &mut Members,
+        member: (Scope, ast::Member),
+        conflicts: &mut Vec<String>,
+        depth: usize,
+    ) -> bool {
+        let property = matches!(
+            member.1.kind,
+            MemberKind::Property(_) | MemberKind::Getter(_)
+        );
+        let name = member_name(&member.1).map(str::to_string);
+        let earlier = members.iter().position(|(_, m)| {
+            matches!(m.kind, MemberKind::Property(_) | MemberKind::Getter(_))
+                && member_name(m).map(str::to_string) == name
+        });
+        let (Some(i), true, Some(name)) = (earlier, property, name) else {
+            members.push(member);
+            return true;
+        };
+        let (scope, m) = &members[i];
+        // Types written in different scopes can't be compared here.
+        if *scope != member.0 {
+            return false;
+        }
+        let (Some(a), Some(b)) = (member_type(m), member_type(&member.1)) else {
+            return false;
+        };
+        let scope = scope.clone();
+        let (earlier_fits, member_fits) = self.in_scope(scope, |this| {
+            let mut bindings = Default::default();
+            (
+                this.extends(&a, &b, &mut bindings, depth + 1),
+                this.extends(&b, &a, &mut bindings, depth + 1),
+            )
+        });
+        match (earlier_fits, member_fits) {
+            (Some(false), Some(true)) => {
+                members[i] = member;
+                true
+            }
+            (Some(false), Some(false)) => {
+                if !conflicts.contains(&name) {
+                    conflicts.push(name);
+                }
+                false
+            }
+            _ => false,
+        }
+    }
+}
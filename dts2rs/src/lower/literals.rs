@@ -0,0 +1,351 @@
+//! Naming of object literal types.
+//!
+//! An object literal type with properties or methods, such as the parameter type of
+//! `init(opts: { root: string; debug?: boolean })`, is bound like an interface with those
+//! members, named after where it is written: `InitOpts` for parameter `opts` of `init`,
+//! `FooBarReturn` for what method `bar` of `Foo` returns.
+//!
+//! Literals with the same bindings share one type, named after the literal whose position
+//! sorts first by its key. Each literal is named after its own position while lowering, and
+//! the types are merged once all are lowered, so a name only depends on the literals of the
+//! same shape and not on the order declarations are lowered in.
+//!
+//! [`Config::type_names`](crate::Config::type_names) overrides names, keyed like union
+//! strategies, and the names chosen are listed in [`Lowered`](super::Lowered) to be pinned
+//! that way. Literals are only merged with those given the same name, and then share it.
+
+use std::collections::{BTreeMap, HashMap};
+
+use dts_parser::ast::{self, MemberKind};
+
+use super::computed::mentions;
+use super::evaluate::{member_name, Members};
+use super::interfaces::MemberTarget;
+use super::Lowerer;
+use crate::ir::{self, RustType};
+use crate::names;
+
+/// The name that a literal's own type is replaced by to compare it, which no type has.
+const PLACEHOLDER: &str = "<literal>";
+
+impl<'a> Lowerer<'a> {
+    /// Maps an object literal type with `members`: a record if it is nothing but an index
+    /// signature, its named type if it has members of its own, or a plain object.
+    pub(super) fn map_object_literal(
+        &mut self,
+        ty: &ast::Type,
+        members: &[ast::Member],
+    ) -> RustType {
+        if let Some(record) = self.map_index_object(members) {
+            return record;
+        }
+        let named = members.iter().any(|m| {
+            matches!(
+                m.kind,
+                MemberKind::Property(_)
+                    | MemberKind::Method(_)
+                    | MemberKind::Getter(_)
+                    | MemberKind::Setter(_)
+            )
+        });
+        let params: Vec<String> = self
+            .type_params
+            .iter()
+            .chain(&self.alias_params)
+            .cloned()
+            .collect();
+        // Literals referring to type parameters differ with each instance.
+        if !named || mentions(ty, &params) {
+            return RustType::path("js_sys::Object");
+        }
+        let members: Members = members
+            .iter()
+            .map(|m| (self.scope.clone(), m.clone()))
+            .collect();
+        RustType::path(self.named_literal(ty, &members))
+    }
+
+    /// Maps type `ty`, which evaluates to an object type with `members`, like an object
+    /// literal type with those members written in its place.
+    pub(super) fn map_evaluated_object(&mut self, ty: &ast::Type, members: &Members) -> RustType {
+        let named = members.iter().any(|(_, m)| member_name(m).is_some());
+        if !named {
+            return RustType::path("js_sys::Object");
+        }
+        RustType::path(self.named_literal(ty, members))
+    }
+
+    /// The Rust path of the type bound for object literal type `ty`, if it has been named,
+    /// which it is before its members are lowered, so that they can refer to it.
+    pub(super) fn literal_type_of(&self, ty: &ast::Type) -> Option<String> {
+        self.literal_names.get(&self.written(ty)).cloned()
+    }
+
+    /// Identifies object literal type `ty`: the same literal, with the same type arguments
+    /// substituted, is the same type.
+    fn written(&self, ty: &ast::Type) -> String {
+        format!("{} {:?}", self.file.name, ty)
+    }
+
+    /// The Rust path of the type bound for object literal type `ty`, or a type evaluating to
+    /// one, defining it the first time `ty` is mapped.
+    fn named_literal(&mut self, ty: &ast::Type, members: &Members) -> String {
+        if let Some(rust_name) = self.literal_type_of(ty) {
+            return rust_name;
+        }
+        let written = self.written(ty);
+        let site = match self.context.as_slice() {
+            [] => None,
+            context => Some(self.scope.qualify(&context.join("."))),
+        };
+        let configured = site
+            .as_ref()
+            .and_then(|site| self.config.type_names.get(site))
+            .cloned();
+        if let Some(rust_name) = configured
+            .as_deref()
+            .and_then(|name| self.pinned_literal(name, site.as_deref(), members))
+        {
+            self.literal_names.insert(written, rust_name.clone());
+            self.record_literal_name(site, &rust_name);
+            return rust_name;
+        }
+        let base = configured.clone().unwrap_or_else(|| {
+            let base: String = self.context.iter().map(|p| names::pascal_case(p)).collect();
+            if base.is_empty() {
+                "Anonymous".to_string()
+            } else {
+                base
+            }
+        });
+        let rust_name = self.fresh_type_name(&base);
+        self.literal_names.insert(written, rust_name.clone());
+        self.record_literal_name(site.clone(), &rust_name);
+        let members = self.lower_literal(&rust_name, members);
+        let text = ty
+            .span
+            .text(&self.file.src)
+            .split_whitespace()
+            .collect::<Vec<_>>();
+        let decl = ir::TypeDecl {
+            rust_name: rust_name.clone(),
+            js_name: short_name(&rust_name).to_string(),
+            extends: vec!["js_sys::Object".to_string()],
+            is_type_of: Some("JsValue::is_object".to_string()),
+            doc: Some(format!("`{}`", text.join(" "))),
+        };
+        self.interfaces.insert(rust_name.clone());
+        self.literal_types.push(Literal {
+            site,
+            configured,
+            decl,
+            members,
+        });
+        rust_name
+    }
+
+    /// The type of an earlier literal pinned to `name` if `members` have the same bindings,
+    /// which it then shares under the name it was pinned to.
+    fn pinned_literal(
+        &mut self,
+        name: &str,
+        site: Option<&str>,
+        members: &Members,
+    ) -> Option<String> {
+        let placeholder = self.scope.rust_path(PLACEHOLDER);
+        let literal = Literal {
+            site: site.map(str::to_string),
+            configured: Some(name.to_string()),
+            decl: ir::TypeDecl {
+                rust_name: placeholder.clone(),
+                js_name: PLACEHOLDER.to_string(),
+                extends: Vec::new(),
+                is_type_of: None,
+                doc: None,
+            },
+            members: self.lower_literal(&placeholder, members),
+        };
+        let own = shape(&literal, &HashMap::new());
+        self.literal_types
+            .iter()
+            .find(|l| l.configured == literal.configured && shape(l, &HashMap::new()) == own)
+            .map(|l| l.decl.rust_name.clone())
+    }
+
+    /// Lowers `members`, each in the scope it is written in, as those of the type at Rust
+    /// path `rust_name`.
+    fn lower_literal(&mut self, rust_name: &str, members: &Members) -> ir::Module {
+        let mut out = ir::Module::default();
+        let js_this = self.scope.qualify(short_name(rust_name));
+        let this_type = self.this_type.replace(rust_name.to_string());
+        let mut target = MemberTarget {
+            this: rust_name,
+            js_this: &js_this,
+            instance: RustType::path(rust_name),
+            structural: true,
+            is_static: false,
+        };
+        for (scope, member) in members {
+            self.in_scope(scope.clone(), |this| {
+                this.lower_member(member, &mut target, &mut out)
+            });
+        }
+        self.this_type = this_type;
+        out
+    }
+
+    /// Lists the name of the literal type written at `site`, so that it can be pinned.
+    /// Overloads share their sites, and the first of them names it.
+    fn record_literal_name(&mut self, site: Option<String>, rust_name: &str) {
+        if let Some(site) = site {
+            self.literal_sites
+                .entry(site)
+                .or_insert_with(|| short_name(rust_name).to_string());
+        }
+    }
+
+    /// Adds the literal types to the modules of the scopes they were written in, with those
+    /// of the same bindings merged.
+    pub(super) fn push_literal_types(&mut self, out: &mut ir::Module) {
+        let literals = std::mem::take(&mut self.literal_types);
+        let merged = merge(&literals);
+        for literal in literals {
+            let rust_name = literal.decl.rust_name.clone();
+            if let Some(kept) = merged.get(&rust_name) {
+                if let Some(site) = &literal.site {
+                    if self.literal_sites.get(site).map(String::as_str)
+                        == Some(short_name(&rust_name))
+                    {
+                        self.literal_sites
+                            .insert(site.clone(), short_name(kept).to_string());
+                    }
+                }
+                continue;
+            }
+            let mut module = &mut *out;
+            if let Some((path, _)) = rust_name.rsplit_once("::") {
+                for name in path.split("::") {
+                    module = module.submodule_mut(name);
+                }
+            }
+            module.externs.push(ir::ExternItem::Type(literal.decl));
+            module.externs.extend(literal.members.externs);
+            module.entries.extend(literal.members.entries);
+        }
+        if !merged.is_empty() {
+            rename(out, &|path| {
+                merged
+                    .get(path)
+                    .cloned()
+                    .unwrap_or_else(|| path.to_string())
+            });
+        }
+    }
+}
+
+/// A named object literal type.
+pub(super) struct Literal {
+    /// The key of the position it is written at, if it has one.
+    site: Option<String>,
+    /// The name configured for it.
+    configured: Option<String>,
+    decl: ir::TypeDecl,
+    /// The bindings of its members.
+    members: ir::Module,
+}
+
+/// Merges literals with the same bindings, returning the type each merged one is replaced by.
+/// Literals containing merged ones are compared again, as their bindings now match.
+fn merge(literals: &[Literal]) -> HashMap<String, String> {
+    let mut merged: HashMap<String, String> = HashMap::new();
+    loop {
+        let mut shapes: BTreeMap<String, Vec<&Literal>> = BTreeMap::new();
+        for literal in literals {
+            if !merged.contains_key(&literal.decl.rust_name) {
+                shapes
+                    .entry(shape(literal, &merged))
+                    .or_default()
+                    .push(literal);
+            }
+        }
+        let mut changed = false;
+        for group in shapes.values().filter(|g| g.len() > 1) {
+            let kept = group.iter().min_by_key(|l| rank(l)).unwrap();
+            for literal in group {
+                if literal.decl.rust_name != kept.decl.rust_name {
+                    merged.insert(literal.decl.rust_name.clone(), kept.decl.rust_name.clone());
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    // A type kept in one round may be merged in a later one.
+    merged
+        .keys()
+        .map(|name| (name.clone(), resolve(&merged, name)))
+        .collect()
+}
+
+/// Orders the literals of one shape: the one given its configured name, then the one written
+/// at the position whose key sorts first, names the type.
+fn rank(literal: &Literal) -> (bool, bool, Option<&str>, &str) {
+    let rust_name = literal.decl.rust_name.as_str();
+    (
+        literal.configured.as_deref() != Some(short_name(rust_name)),
+        literal.site.is_none(),
+        literal.site.as_deref(),
+        rust_name,
+    )
+}
+
+/// What literals with the same bindings have in common: their configured name and the
+/// bindings of their members, without documentation or keys, as members of one type.
+fn shape(literal: &Literal, merged: &HashMap<String, String>) -> String {
+    let own = &literal.decl.rust_name;
+    let members = literal.members.map_paths(&|path| {
+        if path == own {
+            PLACEHOLDER.to_string()
+        } else {
+            resolve(merged, path)
+        }
+    });
+    let externs: Vec<ir::ExternItem> = members
+        .externs
+        .into_iter()
+        .map(|mut item| {
+            if let ir::ExternItem::Function(f) = &mut item {
+                f.doc = None;
+                f.key.clear();
+            }
+            item
+        })
+        .collect();
+    format!(
+        "{:?} {:?} {:?}",
+        literal.configured, externs, members.entries
+    )
+}
+
+/// The type that `path` is replaced by, following merges.
+fn resolve(merged: &HashMap<String, String>, path: &str) -> String {
+    let mut path = path;
+    while let Some(kept) = merged.get(path) {
+        path = kept;
+    }
+    path.to_string()
+}
+
+/// Replaces the paths in `module` and its submodules by `f` of them.
+fn rename(module: &mut ir::Module, f: &dyn Fn(&str) -> String) {
+    *module = module.map_paths(f);
+    for submodule in &mut module.modules {
+        rename(submodule, f);
+    }
+}
+
+fn short_name(rust_path: &str) -> &str {
+    rust_path.rsplit("::").next().unwrap_or(rust_path)
+}
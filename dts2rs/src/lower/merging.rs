@@ -0,0 +1,219 @@
+//! Declaration merging.
+//!
+//! TypeScript merges declarations that share a qualified name. Repeated interfaces, and an
+//! interface with a class, make up one type: it is lowered once, at the class or else at the
+//! first interface, with the members of every declaration. A namespace merged with a class
+//! adds static members to it, and so does a namespace merged with a function, whose statics
+//! go to a type standing for the function object: `function jQuery(...)` with
+//! `namespace jQuery { function ajax(...) }` binds `JQuery::ajax(...)`. The type also has the
+//! call, as method `call` of values such as `$` in `declare const $: typeof jQuery`, which is
+//! bound as that type; `j_query(...)` still calls the global function.
+//! Types declared in such a namespace stay in its module.
+
+use std::collections::HashSet;
+
+use dts_parser::ast;
+
+use super::interfaces::MemberTarget;
+use super::namespaces::Scope;
+use super::Lowerer;
+use crate::ir::{self, RustType};
+use crate::names;
+
+impl<'a> Lowerer<'a> {
+    /// Whether `item`, declaring type `qualified` in the current scope, is the declaration
+    /// that its merged type is lowered at.
+    pub(super) fn is_primary(&self, qualified: &str, item: &ast::Item) -> bool {
+        let declarations = &self.declarations[qualified];
+        let primary = declarations
+            .iter()
+            .find(|(_, d)| matches!(d.kind, ast::ItemKind::Class(_)))
+            .unwrap_or(&declarations[0]);
+        primary.0.file == self.scope.file && primary.1.span == item.span
+    }
+
+    /// Lowers the members of the interfaces merged into type `qualified`, other than
+    /// `primary`, each in the scope it is declared in.
+    pub(super) fn lower_merged_members(
+        &mut self,
+        qualified: &str,
+        primary: &ast::Item,
+        target: &mut MemberTarget,
+        out: &mut ir::Module,
+    ) {
+        let declarations = self.declarations[qualified].clone();
+        let structural = std::mem::replace(&mut target.structural, true);
+        for (scope, item) in declarations {
+            let ast::ItemKind::Interface(decl) = &item.kind else {
+                continue;
+            };
+            if scope.file == self.scope.file && item.span == primary.span {
+                continue;
+            }
+            self.in_scope(scope, |this| {
+                this.with_type_params(&decl.type_params, |this| {
+                    this.in_context(&decl.name.name, |this| {
+                        for member in &decl.members {
+                            this.lower_member(member, target, out);
+                        }
+                    })
+                })
+            });
+        }
+        target.structural = structural;
+    }
+
+    /// Finds the namespaces merged with a class or function, whose functions and variables
+    /// become static members.
+    pub(super) fn collect_static_owners(&mut self) {
+        let project = self.project;
+        for (i, file) in project.files.iter().enumerate() {
+            let scope = self.file_scope(i);
+            self.in_scope(scope, |this| this.find_static_owners(&file.module));
+        }
+        let objects: Vec<String> = self.function_objects.values().cloned().collect();
+        self.type_names.extend(objects);
+    }
+
+    fn find_static_owners(&mut self, module: &ast::Module) {
+        let functions: HashSet<&str> = module
+            .items
+            .iter()
+            .filter_map(|item| match &item.kind {
+                ast::ItemKind::Function(ast::FunctionDecl {
+                    name: Some(name), ..
+                }) => Some(name.name.as_str()),
+                _ => None,
+            })
+            .collect();
+        for item in &module.items {
+            if let ast::ItemKind::Namespace(decl) = &item.kind {
+                if let [name] = decl.name.parts.as_slice() {
+                    let qualified = self.scope.qualify(&name.name);
+                    if let Some(rust_name) = self.types.get(&qualified) {
+                        if !self.interfaces.contains(rust_name) {
+                            self.static_owners.insert(qualified, rust_name.clone());
+                        }
+                    } else if functions.contains(name.name.as_str())
+                        && !self.static_owners.contains_key(&qualified)
+                    {
+                        let mut rust_name = self.scope.rust_path(&names::type_name(&name.name));
+                        if self.type_names.contains(&rust_name) {
+                            rust_name.push_str("Static");
+                        }
+                        self.static_owners
+                            .insert(qualified.clone(), rust_name.clone());
+                        self.function_types
+                            .insert(qualified.clone(), rust_name.clone());
+                        self.function_objects.insert(qualified, rust_name);
+                    }
+                }
+            }
+            if let Some((scope, body)) = self.nested_scope(item) {
+                self.in_scope(scope, |this| this.find_static_owners(body));
+            }
+        }
+    }
+
+    /// The call of function `func`, declared by `decl`, on the type standing for the function
+    /// object if a namespace is merged with it.
+    pub(super) fn object_call(
+        &self,
+        func: &ir::Function,
+        decl: &ast::FunctionDecl,
+    ) -> Option<ir::Function> {
+        let name = &decl.name.as_ref()?.name;
+        let object = self.function_types.get(&self.scope.qualify(name))?;
+        Some(ir::Function {
+            kind: ir::FunctionKind::Call {
+                this: object.clone(),
+            },
+            rust_name: "call".to_string(),
+            key: format!("call {}", func.key),
+            ..func.clone()
+        })
+    }
+
+    /// The type standing for the function object that `typeof name` refers to, if a namespace
+    /// is merged with the function.
+    pub(super) fn function_type(&self, name: &str) -> Option<RustType> {
+        let (_, rust_name) = self.lookup(&self.function_types, name)?;
+        Some(RustType::path(rust_name))
+    }
+
+    /// Lowers the functions and variables of namespace `qualified`, declared in `scope`, as
+    /// static members of `owner`, returning the items left for the namespace's module.
+    pub(super) fn lower_statics<'i>(
+        &mut self,
+        qualified: &str,
+        owner: &str,
+        scope: Scope,
+        item: &ast::Item,
+        body: &'i ast::Module,
+        out: &mut ir::Module,
+    ) -> Vec<&'i ast::Item> {
+        let js_name = qualified.rsplit('.').next().unwrap_or(qualified);
+        if let Some(object) = self.function_objects.remove(qualified) {
+            out.externs.push(ir::ExternItem::Type(ir::TypeDecl {
+                rust_name: object,
+                js_name: self.js_name(js_name),
+                extends: vec!["js_sys::Function".to_string(), "js_sys::Object".to_string()],
+                is_type_of: Some("JsValue::is_function".to_string()),
+                doc: Some(match &item.doc {
+                    Some(doc) => doc.clone(),
+                    None => format!("The `{}` function, with its static members.", js_name),
+                }),
+            }));
+        }
+        let mut target = MemberTarget {
+            this: owner,
+            js_this: qualified,
+            instance: RustType::path(owner),
+            structural: false,
+            is_static: true,
+        };
+        let mut rest = Vec::new();
+        self.in_scope(scope, |this| {
+            this.in_context(js_name, |this| {
+                for item in &body.items {
+                    let doc = item.doc.as_deref();
+                    match &item.kind {
+                        ast::ItemKind::Function(ast::FunctionDecl {
+                            name: Some(name),
+                            sig,
+                        }) => this.push_method(&mut target, &name.name, sig, doc, out),
+                        ast::ItemKind::Variable(decl) => {
+                            for declarator in &decl.declarators {
+                                let name = &declarator.name.name;
+                                let ty = this.in_context(name, |this| match &declarator.ty {
+                                    Some(ty) => this.map_type(ty),
+                                    None => this.map_initializer_type(declarator.init.as_ref()),
+                                });
+                                this.push_getter(&mut target, name, ty.clone(), doc, out);
+                                if decl.kind != ast::VariableKind::Const {
+                                    this.push_setter(&mut target, name, ty, doc, out);
+                                }
+                            }
+                        }
+                        _ => rest.push(item),
+                    }
+                }
+            })
+        });
+        rest
+    }
+}
+
+/// Drops the functions in `externs` that repeat the key of an earlier one, as members
+/// declared by several merged interfaces do.
+pub(super) fn dedup_members(externs: &mut Vec<ir::ExternItem>, start: usize) {
+    let mut seen = HashSet::new();
+    let mut i = 0;
+    externs.retain(|item| {
+        i += 1;
+        match item {
+            ir::ExternItem::Function(f) if i > start => seen.insert(f.key.clone()),
+            _ => true,
+        }
+    });
+}
@@ -0,0 +1,719 @@
+//! Lowering of declaration syntax trees into the binding model.
+
+mod arrays;
+mod builtins;
+mod callbacks;
+mod classes;
+mod computed;
+mod enums;
+mod evaluate;
+mod exports;
+mod extensions;
+mod generics;
+mod imports;
+mod interfaces;
+mod intersections;
+mod literals;
+mod merging;
+mod namespaces;
+mod overloads;
+mod promises;
+mod records;
+mod typed_arrays;
+mod types;
+mod unions;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+use dts_parser::{ast, Span};
+
+use crate::config::Config;
+use crate::diagnostics::{Diagnostics, SourceFile};
+use crate::ir;
+use crate::names;
+use crate::project::Project;
+
+use self::imports::{FunctionReexport, Links};
+use self::namespaces::Scope;
+
+pub struct Lowerer<'a> {
+    project: &'a Project,
+    /// The file being lowered.
+    file: &'a SourceFile,
+    config: &'a Config,
+    diags: &'a mut Diagnostics,
+    /// The imports and exports of each file.
+    links: Vec<Links>,
+    /// Types declared in the project, by qualified TypeScript name, with their Rust paths.
+    types: HashMap<String, String>,
+    /// The typed wrappers of generic types, by qualified TypeScript name. Their untyped
+    /// bindings are in `types`.
+    generic_types: HashMap<String, String>,
+    /// The resolved `extends` clauses of declared classes and interfaces, by qualified
+    /// TypeScript name.
+    parents: HashMap<String, Vec<String>>,
+    /// The Rust paths of declared interfaces, which have no class to check values against.
+    interfaces: HashSet<String>,
+    /// The interface and class declarations of each declared type, by qualified name, with
+    /// the scope they are declared in. Types declared more than once are merged.
+    declarations: HashMap<String, Vec<(Scope, ast::Item)>>,
+    /// Global interfaces that add members to types of other crates, by name, with the paths
+    /// of those types.
+    extensions: HashMap<String, String>,
+    /// The Rust paths of the types that namespaces merged with a class or function add static
+    /// members to, by the namespace's qualified name.
+    static_owners: HashMap<String, String>,
+    /// The types standing for functions merged with a namespace that are yet to be declared,
+    /// by qualified name.
+    function_objects: HashMap<String, String>,
+    /// The types standing for functions merged with a namespace, by qualified name. Calls of
+    /// the function and `typeof` it are bound on them.
+    function_types: HashMap<String, String>,
+    /// Type aliases declared in the project, by qualified name, with the scope they are
+    /// declared in.
+    aliases: HashMap<String, (Scope, ast::Item)>,
+    /// Type aliases that evaluate to object types, by qualified name, with their members.
+    /// They are also in `types`.
+    object_aliases: HashMap<String, evaluate::Members>,
+    /// Declared functions, by qualified name, with the scope they are declared in. Only the
+    /// first of overloads is kept.
+    functions: HashMap<String, (Scope, ast::Item)>,
+    /// What each type alias lowers to, once resolved, or `None` while it is being resolved.
+    alias_types: HashMap<String, Option<ir::RustType>>,
+    /// What each declared enum maps to, and the values of its members.
+    enum_types: HashMap<String, ir::RustType>,
+    enum_values: HashMap<String, HashMap<String, enums::Const>>,
+    /// Rust type paths in use, including those of generated enums.
+    type_names: HashSet<String>,
+    /// Generated enums, and the enum for each distinct set of union members.
+    enums: Vec<ir::Enum>,
+    enum_names: HashMap<Vec<ir::VariantKind>, String>,
+    /// Named object literal types, the type of each literal by its syntax tree, and the
+    /// name of the one written at each position, by the position's key.
+    literal_types: Vec<literals::Literal>,
+    literal_names: HashMap<String, String>,
+    literal_sites: BTreeMap<String, String>,
+    /// The JavaScript names leading to the type being lowered, such as `["foo", "options"]`
+    /// for the `options` parameter of function `foo`. Unions are configured and named by it.
+    context: Vec<String>,
+    /// The bodies of `declare global` and of module augmentations, with the scopes they
+    /// reopen, to be lowered once all files are.
+    reopened: Vec<(Scope, ast::Module)>,
+    /// The JavaScript names of declarations that differ from their TypeScript ones, by
+    /// qualified name.
+    js_aliases: HashMap<String, String>,
+    /// The ES modules that files of the entry file's package are imported from instead of
+    /// [`Config::module`], which doesn't export all of their declarations, by file.
+    js_modules: HashMap<usize, String>,
+    /// The functions that modules re-export, once they are named.
+    function_reexports: Vec<FunctionReexport>,
+    /// The namespace or module being lowered.
+    scope: Scope,
+    /// The type parameters in scope.
+    type_params: Vec<String>,
+    /// The type parameters of the type alias being resolved, which only its instances give
+    /// arguments to.
+    alias_params: Vec<String>,
+    /// How many instances of generic type aliases are being mapped, one inside the other.
+    instances: usize,
+    /// How many evaluated types are being mapped, one inside the other, in the instance of a
+    /// generic type alias being mapped.
+    reductions: usize,
+    /// The Rust type `this` refers to inside the class or interface being lowered.
+    this_type: Option<String>,
+    /// The `web-sys` types referred to, which are also the cargo features they need.
+    web_sys_features: BTreeSet<String>,
+    /// Whether any array is bound as `JsArray<T>`.
+    js_array: bool,
+    /// Whether any record is bound as `JsRecord<T>`.
+    js_record: bool,
+    /// Whether any record is bound as `JsPrimitiveRecord<T>`.
+    js_primitive_record: bool,
+}
+
+/// The bindings of a project.
+#[derive(Debug)]
+pub struct Lowered {
+    pub module: ir::Module,
+    /// The final Rust name of every function, by key.
+    pub names: BTreeMap<String, String>,
+    /// The Rust name of every named object literal type, by the key of where it is written.
+    pub type_names: BTreeMap<String, String>,
+    /// The `web-sys` cargo features the bindings need.
+    pub web_sys_features: BTreeSet<String>,
+    /// Whether the bindings await promises with `wasm-bindgen-futures`.
+    pub futures: bool,
+}
+
+impl<'a> Lowerer<'a> {
+    pub fn new(
+        project: &'a Project,
+        config: &'a Config,
+        diags: &'a mut Diagnostics,
+    ) -> Lowerer<'a> {
+        Lowerer {
+            project,
+            file: &project.files[0].source,
+            config,
+            diags,
+            links: Vec::new(),
+            types: HashMap::new(),
+            generic_types: HashMap::new(),
+            parents: HashMap::new(),
+            interfaces: HashSet::new(),
+            declarations: HashMap::new(),
+            extensions: HashMap::new(),
+            static_owners: HashMap::new(),
+            function_objects: HashMap::new(),
+            function_types: HashMap::new(),
+            aliases: HashMap::new(),
+            object_aliases: HashMap::new(),
+            functions: HashMap::new(),
+            alias_types: HashMap::new(),
+            enum_types: HashMap::new(),
+            enum_values: HashMap::new(),
+            type_names: HashSet::new(),
+            enums: Vec::new(),
+            enum_names: HashMap::new(),
+            literal_types: Vec::new(),
+            literal_names: HashMap::new(),
+            literal_sites: BTreeMap::new(),
+            context: Vec::new(),
+            reopened: Vec::new(),
+            js_aliases: HashMap::new(),
+            js_modules: HashMap::new(),
+            function_reexports: Vec::new(),
+            scope: Scope::default(),
+            type_params: Vec::new(),
+            alias_params: Vec::new(),
+            instances: 0,
+            reductions: 0,
+            this_type: None,
+            web_sys_features: BTreeSet::new(),
+            js_array: false,
+            js_record: false,
+            js_primitive_record: false,
+        }
+    }
+
+    /// Lowers all files of the project.
+    pub fn lower_project(&mut self) -> Lowered {
+        self.link_files();
+        self.collect_js_aliases();
+        self.collect_types();
+        self.collect_object_aliases();
+        self.collect_static_owners();
+        // Enums and aliases go first, so that unions they name get their names before any
+        // identical unnamed union is lowered.
+        self.for_each_project_item(&mut |this, item| {
+            if let ast::ItemKind::Enum(decl) = &item.kind {
+                this.lower_enum(decl, item.doc.as_deref());
+            }
+        });
+        self.for_each_project_item(&mut |this, item| {
+            if let ast::ItemKind::TypeAlias(decl) = &item.kind {
+                let name = this.scope.qualify(&decl.name.name);
+                this.resolve_alias(&name);
+            }
+        });
+        let mut out = ir::Module::default();
+        let project = self.project;
+        for (i, file) in project.files.iter().enumerate() {
+            let scope = self.file_scope(i);
+            let mut module = &mut out;
+            for name in &scope.rust {
+                module = module.submodule_mut(name);
+            }
+            module.js_module = scope.js_module.clone();
+            module.js_namespace = scope.js_namespace.clone();
+            self.in_scope(scope, |this| {
+                for item in &file.module.items {
+                    this.lower_item(item, module);
+                }
+                let reexports = this.reexports(&file.module);
+                module.reexports.extend(reexports);
+            });
+        }
+        // Declarations reopening another scope go into its module, which the files have set
+        // up.
+        while !self.reopened.is_empty() {
+            for (scope, body) in std::mem::take(&mut self.reopened) {
+                let mut module = &mut out;
+                for name in &scope.rust {
+                    module = module.submodule_mut(name);
+                }
+                self.in_scope(scope, |this| {
+                    for item in &body.items {
+                        this.lower_item(item, module);
+                    }
+                });
+            }
+        }
+        // Enums go into the module of the scope they were generated in.
+        for e in std::mem::take(&mut self.enums) {
+            let mut module = &mut out;
+            if let Some((path, _)) = e.rust_name.rsplit_once("::") {
+                for name in path.split("::") {
+                    module = module.submodule_mut(name);
+                }
+            }
+            module.enums.push(e);
+        }
+        self.push_literal_types(&mut out);
+        self.push_js_array(&mut out);
+        self.push_js_record(&mut out);
+        let names = overloads::assign_names(&mut out, self.config, self.diags);
+        self.push_function_reexports(&mut out);
+        Lowered {
+            futures: has_async_wrappers(&out),
+            module: out,
+            names,
+            type_names: std::mem::take(&mut self.literal_sites),
+            web_sys_features: std::mem::take(&mut self.web_sys_features),
+        }
+    }
+
+    /// The scope of the top level of a file.
+    fn file_scope(&self, file: usize) -> Scope {
+        let project_file = &self.project.files[file];
+        if !project_file.is_module {
+            return self.global_scope(file);
+        }
+        let mut scope = Scope {
+            file,
+            module: project_file.name.clone(),
+            js_module: project_file
+                .js_module
+                .clone()
+                .or_else(|| self.js_modules.get(&file).cloned())
+                .or_else(|| self.config.module.clone()),
+            namespace: Vec::new(),
+            js_namespace: Vec::new(),
+            rust: project_file.rust.clone(),
+            global: false,
+        };
+        if let Some(global) = self.umd_namespace(file) {
+            scope.js_module = None;
+            // What `export =` names is bound as the global itself; see `exports.rs`.
+            if self.export_assignment(file).is_none() {
+                scope.js_namespace.push(global);
+            }
+        }
+        scope
+    }
+
+    /// The global scope, for declarations written in `file`.
+    fn global_scope(&self, file: usize) -> Scope {
+        let mut scope = Scope {
+            file,
+            global: true,
+            ..Scope::default()
+        };
+        if self.project.files[0].is_module && self.config.module.is_some() {
+            // The root module imports from the ES module, so globals need a module of their
+            // own.
+            scope.rust.push("global".to_string());
+        }
+        scope
+    }
+
+    /// Calls `f` with every item of every file, as [`for_each_item`](Self::for_each_item)
+    /// does for one.
+    fn for_each_project_item(&mut self, f: &mut dyn FnMut(&mut Self, &ast::Item)) {
+        let project = self.project;
+        for (i, file) in project.files.iter().enumerate() {
+            let scope = self.file_scope(i);
+            self.in_scope(scope, |this| this.for_each_item(&file.module, f));
+        }
+    }
+
+    /// Registers the names of declared types, so that references to them can be resolved
+    /// regardless of declaration order and file.
+    fn collect_types(&mut self) {
+        let mut classes = Vec::new();
+        let mut parents = Vec::new();
+        self.for_each_project_item(&mut |this, item| {
+            let (name, type_params, extends): (&str, &[ast::TypeParam], Vec<&ast::TypeRef>) =
+                match &item.kind {
+                    ast::ItemKind::Interface(decl) => (
+                        &decl.name.name,
+                        &decl.type_params,
+                        decl.extends.iter().collect(),
+                    ),
+                    ast::ItemKind::Class(ast::ClassDecl {
+                        name: Some(name),
+                        type_params,
+                        extends,
+                        ..
+                    }) => (&name.name, type_params, extends.iter().collect()),
+                    ast::ItemKind::TypeAlias(decl) => {
+                        this.aliases
+                            .entry(this.scope.qualify(&decl.name.name))
+                            .or_insert_with(|| (this.scope.clone(), item.clone()));
+                        return;
+                    }
+                    ast::ItemKind::Function(ast::FunctionDecl {
+                        name: Some(name), ..
+                    }) => {
+                        this.functions
+                            .entry(this.scope.qualify(&name.name))
+                            .or_insert_with(|| (this.scope.clone(), item.clone()));
+                        return;
+                    }
+                    _ => return,
+                };
+            let qualified = this.scope.qualify(name);
+            this.declarations
+                .entry(qualified.clone())
+                .or_default()
+                .push((this.scope.clone(), item.clone()));
+            if !this.types.contains_key(&qualified) {
+                let rust_name = if this.typed_generics() && !type_params.is_empty() {
+                    this.register_generic(&qualified, name)
+                } else {
+                    this.scope.rust_path(&names::type_name(name))
+                };
+                this.types.insert(qualified.clone(), rust_name);
+            }
+            match item.kind {
+                ast::ItemKind::Interface(_) => {
+                    this.interfaces.insert(this.types[&qualified].clone());
+                }
+                _ => classes.push(this.types[&qualified].clone()),
+            }
+            for parent in extends {
+                parents.push((
+                    qualified.clone(),
+                    this.scope.clone(),
+                    parent.name.to_dotted(),
+                ));
+            }
+        });
+        self.collect_extensions();
+        // A class merged with an interface of the same name still has a class to check
+        // values against.
+        for class in &classes {
+            self.interfaces.remove(class);
+        }
+        // Parents are resolved in the scope of the declaration extending them. Unresolved
+        // ones may still be built-in classes.
+        for (name, scope, parent) in parents {
+            let parent = self.in_scope(scope, |this| match this.lookup(&this.types, &parent) {
+                Some((qualified, _)) => qualified.clone(),
+                None => parent,
+            });
+            self.parents.entry(name).or_default().push(parent);
+        }
+        self.type_names = self.types.values().cloned().collect();
+        self.type_names.extend(self.generic_types.values().cloned());
+    }
+
+    /// Runs `f` with `part` appended to the context path.
+    pub(super) fn in_context<R>(&mut self, part: &str, f: impl FnOnce(&mut Self) -> R) -> R {
+        self.context.push(part.to_string());
+        let result = f(self);
+        self.context.pop();
+        result
+    }
+
+    /// What a type alias lowers to. Aliases are transparent, except that a union they name is
+    /// lowered as a union of that name.
+    pub(super) fn resolve_alias(&mut self, name: &str) -> ir::RustType {
+        match self.alias_types.get(name) {
+            Some(Some(ty)) => return ty.clone(),
+            Some(None) => {
+                // An alias referring to itself, which can only be bound as itself if it names
+                // an object literal type.
+                let (scope, item) = self.aliases[name].clone();
+                let ast::ItemKind::TypeAlias(decl) = &item.kind else {
+                    return ir::RustType::JsValue;
+                };
+                return self.in_scope(scope, |this| {
+                    if let Some(rust_name) = this.literal_type_of(&decl.ty) {
+                        return ir::RustType::path(rust_name);
+                    }
+                    this.diags.warn(
+                        this.file,
+                        decl.name.span,
+                        format!(
+                            "`{}` refers to itself; the reference is bound as `JsValue`",
+                            decl.name.name
+                        ),
+                    );
+                    ir::RustType::JsValue
+                });
+            }
+            None => {}
+        }
+        if let Some(rust_name) = self.types.get(name) {
+            return ir::RustType::path(rust_name.clone());
+        }
+        let Some((scope, ast::ItemKind::TypeAlias(decl))) = self
+            .aliases
+            .get(name)
+            .map(|(scope, item)| (scope.clone(), item.kind.clone()))
+        else {
+            return ir::RustType::JsValue;
+        };
+        self.alias_types.insert(name.to_string(), None);
+        let context = std::mem::replace(&mut self.context, vec![decl.name.name.clone()]);
+        let this_type = self.this_type.take();
+        let params = decl
+            .type_params
+            .iter()
+            .map(|p| p.name.name.clone())
+            .collect();
+        let alias_params = std::mem::replace(&mut self.alias_params, params);
+        let ty = self.in_scope(scope, |this| match &decl.ty.kind {
+            ast::TypeKind::Union(types) => this.map_union(types, decl.ty.span, Some(name)),
+            _ => this.map_type(&decl.ty),
+        });
+        self.context = context;
+        self.this_type = this_type;
+        self.alias_params = alias_params;
+        self.alias_types.insert(name.to_string(), Some(ty.clone()));
+        ty
+    }
+
+    /// The Rust paths of all ancestors of a declared type, nearest first and always ending in
+    /// `js_sys::Object`. Each becomes an `extends` attribute, which gives the binding
+    /// `Deref`/`AsRef` upcasts to all of them.
+    fn ancestors(&mut self, name: &str, span: Span) -> Vec<String> {
+        let mut out = Vec::new();
+        let mut seen = HashSet::new();
+        seen.insert(name.to_string());
+        let mut queue: VecDeque<String> =
+            self.parents.get(name).cloned().unwrap_or_default().into();
+        while let Some(parent) = queue.pop_front() {
+            if !seen.insert(parent.clone()) {
+                continue;
+            }
+            if let Some(rust_name) = self.types.get(&parent) {
+                out.push(rust_name.clone());
+                if let Some(grandparents) = self.parents.get(&parent) {
+                    queue.extend(grandparents.iter().cloned());
+                }
+            } else if let Some(path) = self.builtin_type(&parent) {
+                // Upcasts to the ancestors of built-in classes come with their bindings.
+                out.push(path);
+            } else {
+                self.diags.warn(
+                    self.file,
+                    span,
+                    format!(
+                        "unknown base type `{}`; upcasts to it are not generated",
+                        parent
+                    ),
+                );
+            }
+        }
+        let object = "js_sys::Object".to_string();
+        out.retain(|p| *p != object);
+        let mut deduped = Vec::new();
+        for path in out {
+            if !deduped.contains(&path) {
+                deduped.push(path);
+            }
+        }
+        deduped.push(object);
+        deduped
+    }
+
+    fn lower_item(&mut self, item: &ast::Item, out: &mut ir::Module) {
+        match &item.kind {
+            ast::ItemKind::Interface(decl) => self.lower_interface(decl, item, out),
+            ast::ItemKind::Class(decl) => self.lower_class(decl, item, out),
+            ast::ItemKind::Function(decl) => {
+                if let Some(func) = self.lower_function(decl, item.doc.as_deref()) {
+                    let call = self.object_call(&func, decl);
+                    out.externs.push(ir::ExternItem::Function(func));
+                    if let Some(call) = call {
+                        out.externs.push(ir::ExternItem::Function(call));
+                    }
+                }
+            }
+            ast::ItemKind::Variable(decl) => {
+                for declarator in &decl.declarators {
+                    let stat = self.lower_variable(declarator, item.doc.as_deref());
+                    out.externs.push(ir::ExternItem::Static(stat));
+                }
+            }
+            ast::ItemKind::Namespace(_) | ast::ItemKind::Module(_) | ast::ItemKind::Global(_) => {
+                self.lower_namespace(item, out)
+            }
+            // Aliases of object types are bound like interfaces.
+            ast::ItemKind::TypeAlias(decl) => self.lower_object_alias(decl, item, out),
+            // Enums and other aliases are lowered up front, see `lower_project`.
+            ast::ItemKind::Enum(_) => {}
+            // Imports and exports only matter to name resolution, see `imports.rs`.
+            ast::ItemKind::Import(_) | ast::ItemKind::ImportAlias(_) | ast::ItemKind::Export(_) => {
+            }
+        }
+    }
+
+    fn lower_function(
+        &mut self,
+        decl: &ast::FunctionDecl,
+        doc: Option<&str>,
+    ) -> Option<ir::Function> {
+        let name = match &decl.name {
+            Some(name) => name,
+            None => {
+                self.diags
+                    .warn(self.file, decl.sig.span, "anonymous default export skipped");
+                return None;
+            }
+        };
+        let ((params, variadic, ret, resolves), type_params) =
+            self.with_type_params(&decl.sig.type_params, |this| {
+                this.in_context(&name.name, |this| {
+                    let (params, variadic) = this.lower_params(&decl.sig.params);
+                    (
+                        params,
+                        variadic,
+                        this.map_return_type(decl.sig.ret.as_ref()),
+                        this.map_resolved_type(decl.sig.ret.as_ref()),
+                    )
+                })
+            });
+        Some(ir::Function {
+            kind: ir::FunctionKind::Free,
+            rust_name: names::snake_case(&name.name),
+            key: format!(
+                "{}({})",
+                self.scope.qualify(&name.name),
+                self.signature_key(&decl.sig.params)
+            ),
+            js_name: self.js_name(&name.name),
+            type_params,
+            params,
+            ret,
+            variadic,
+            resolves,
+            async_name: None,
+            doc: doc.map(str::to_string),
+        })
+    }
+
+    /// Lowers a parameter list, returning whether the last parameter is a rest parameter.
+    pub(super) fn lower_params(&mut self, params: &[ast::Param]) -> (Vec<ir::Param>, bool) {
+        let mut out: Vec<ir::Param> = Vec::new();
+        let mut variadic = false;
+        for param in params {
+            if param.name.name() == "this" {
+                continue;
+            }
+            let mut name = names::snake_case(param.name.name());
+            while out.iter().any(|p| p.name == name) {
+                name.push('_');
+            }
+            let ty = self.in_context(param.name.name(), |this| {
+                if param.rest {
+                    this.map_rest_type(param.ty.as_ref())
+                } else {
+                    let ty = match param.ty.as_ref().and_then(|ty| this.map_callback(ty)) {
+                        Some(closure) => closure,
+                        None => this.map_param_type(param.ty.as_ref()),
+                    };
+                    if param.optional {
+                        ir::RustType::option(ty)
+                    } else {
+                        ty
+                    }
+                }
+            });
+            variadic |= param.rest;
+            let descriptor = self.param_descriptor(param.ty.as_ref(), &ty, &name);
+            out.push(ir::Param {
+                name,
+                ty,
+                descriptor,
+            });
+        }
+        (out, variadic)
+    }
+
+    /// Renders parameter types for a function's override key: `string, Options?, ...any[]`.
+    fn signature_key(&self, params: &[ast::Param]) -> String {
+        let types: Vec<String> = params
+            .iter()
+            .filter(|p| p.name.name() != "this")
+            .map(|p| {
+                let ty = match &p.ty {
+                    Some(ty) => ty
+                        .span
+                        .text(&self.file.src)
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    None => "any".to_string(),
+                };
+                let rest = if p.rest { "..." } else { "" };
+                let optional = if p.optional { "?" } else { "" };
+                format!("{}{}{}", rest, ty, optional)
+            })
+            .collect();
+        types.join(", ")
+    }
+
+    /// Describes a parameter for overload naming: string literal types by their value, other
+    /// types by their Rust type, and untyped or catch-all parameters by their name.
+    fn param_descriptor(
+        &self,
+        ty: Option<&ast::Type>,
+        mapped: &ir::RustType,
+        name: &str,
+    ) -> String {
+        if let Some(ast::TypeKind::Literal(ast::LiteralType::Str(value))) = ty.map(|t| &t.kind) {
+            let word = names::snake_case(value);
+            if word.chars().any(|c| c.is_ascii_alphanumeric()) {
+                return word.trim_matches('_').to_string();
+            }
+        }
+        let mapped = match mapped {
+            ir::RustType::Option(inner) => inner,
+            ty => ty,
+        };
+        match mapped {
+            ir::RustType::String => "str".to_string(),
+            ir::RustType::F64 => "f64".to_string(),
+            ir::RustType::Bool => "bool".to_string(),
+            ir::RustType::Closure(..) => "function".to_string(),
+            ir::RustType::Vec(_) | ir::RustType::Tuple(..) => "array".to_string(),
+            ir::RustType::Map(_) => "record".to_string(),
+            ir::RustType::TypedArray(t) if t.kind == ir::TypedArrayKind::Buffer => {
+                "array_buffer".to_string()
+            }
+            ir::RustType::TypedArray(t) => format!("{}_array", t.elem),
+            ir::RustType::Value(path) => {
+                names::snake_case(path.rsplit("::").next().unwrap_or(path))
+            }
+            ir::RustType::Path(path, _) if path != "js_sys::Object" => {
+                let last = path.rsplit("::").next().unwrap_or(path);
+                names::snake_case(last).trim_end_matches('_').to_string()
+            }
+            _ => name.trim_end_matches('_').to_string(),
+        }
+    }
+
+    fn lower_variable(&mut self, decl: &ast::VariableDeclarator, doc: Option<&str>) -> ir::Static {
+        let ty = self.in_context(&decl.name.name, |this| match &decl.ty {
+            Some(ty) => this.map_type(ty),
+            None => this.map_initializer_type(decl.init.as_ref()),
+        });
+        ir::Static {
+            rust_name: names::upper_snake_case(&decl.name.name),
+            js_name: self.js_name(&decl.name.name),
+            ty: typed_arrays::js_sys_array(self.value_type(ty)),
+            doc: doc.map(str::to_string),
+        }
+    }
+}
+
+/// Whether `module` or one of its submodules has functions with async wrappers.
+fn has_async_wrappers(module: &ir::Module) -> bool {
+    module.externs.iter().any(|item| match item {
+        ir::ExternItem::Function(f) => f.async_name.is_some(),
+        _ => false,
+    }) || module.modules.iter().any(has_async_wrappers)
+}
@@ -0,0 +1,211 @@
+//! Lowering of namespaces and ambient modules to nested Rust modules.
+//!
+//! `declare namespace Foo.Bar { ... }` becomes `pub mod foo { pub mod bar { ... } }` with the
+//! items imported from `js_namespace = ["Foo", "Bar"]`, and `declare module "pkg" { ... }`
+//! becomes `pub mod pkg { ... }` with the items imported from `module = "pkg"`. Declarations
+//! are known by their qualified TypeScript name, such as `Foo.Bar.Baz`, and by their Rust path
+//! from the root module, such as `foo::bar::Baz`.
+
+use dts_parser::ast;
+
+use super::Lowerer;
+use crate::ir;
+use crate::names;
+
+/// A file, namespace or ambient module that declarations are lowered in. The default is the
+/// entry file itself.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(super) struct Scope {
+    /// The index of the file in the project.
+    pub file: usize,
+    /// The module qualifying declarations: the name of an ES module file, such as
+    /// `lib/events`, or of an ambient module inside `declare module "pkg"`.
+    pub module: Option<String>,
+    /// The ES module the declarations are imported from.
+    pub js_module: Option<String>,
+    /// The namespaces, outermost first.
+    pub namespace: Vec<String>,
+    /// The JavaScript object the declarations are properties of, outermost first: that of
+    /// the namespaces, under the global of a UMD library bound through it.
+    pub js_namespace: Vec<String>,
+    /// The Rust modules, outermost first.
+    pub rust: Vec<String>,
+    /// Whether declarations are global: at the top level of a script or of `declare global`.
+    pub global: bool,
+}
+
+impl Scope {
+    /// The TypeScript names leading to the scope: the module, then the namespaces.
+    fn js_path(&self) -> Vec<&str> {
+        self.module
+            .iter()
+            .chain(&self.namespace)
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// The qualified TypeScript name of declaration `name` in this scope.
+    pub fn qualify(&self, name: &str) -> String {
+        let mut path = self.js_path();
+        path.push(name);
+        path.join(".")
+    }
+
+    /// The Rust path of item `name` in this scope.
+    pub fn rust_path(&self, name: &str) -> String {
+        let mut path: Vec<&str> = self.rust.iter().map(String::as_str).collect();
+        path.push(name);
+        path.join("::")
+    }
+
+    /// The qualified names a possibly qualified `name` written in this scope may refer to,
+    /// the way TypeScript looks it up: in the scope itself first, then in each enclosing one,
+    /// ending with the global scope.
+    pub fn candidates(&self, name: &str) -> Vec<String> {
+        let path = self.js_path();
+        (0..=path.len())
+            .rev()
+            .map(|i| {
+                let mut key = path[..i].to_vec();
+                key.push(name);
+                key.join(".")
+            })
+            .collect()
+    }
+
+    /// The scope of a namespace or module declaration in this scope, or `None` for a
+    /// shorthand `declare module "pkg";` without a body.
+    pub(super) fn nested<'i>(&self, item: &'i ast::Item) -> Option<(Scope, &'i ast::Module)> {
+        let mut scope = self.clone();
+        scope.global = false;
+        match &item.kind {
+            ast::ItemKind::Namespace(decl) => {
+                for part in &decl.name.parts {
+                    scope.namespace.push(part.name.clone());
+                    scope.js_namespace.push(part.name.clone());
+                    scope.rust.push(names::snake_case(&part.name));
+                }
+                Some((scope, &decl.body))
+            }
+            ast::ItemKind::Module(decl) => {
+                let body = decl.body.as_ref()?;
+                scope.module = Some(decl.name.clone());
+                scope.js_module = Some(decl.name.clone());
+                scope.namespace.clear();
+                scope.js_namespace.clear();
+                scope.rust.push(names::snake_case(&decl.name));
+                Some((scope, body))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Lowerer<'a> {
+    /// Runs `f` in `scope`.
+    pub(super) fn in_scope<R>(&mut self, scope: Scope, f: impl FnOnce(&mut Self) -> R) -> R {
+        let outer_file = std::mem::replace(&mut self.file, &self.project.files[scope.file].source);
+        let outer = std::mem::replace(&mut self.scope, scope);
+        let result = f(self);
+        self.scope = outer;
+        self.file = outer_file;
+        result
+    }
+
+    /// The scope of a namespace, ambient module or `declare global` declared in the current
+    /// scope, or `None` for a shorthand `declare module "pkg";`.
+    pub(super) fn nested_scope<'i>(&self, item: &'i ast::Item) -> Option<(Scope, &'i ast::Module)> {
+        match &item.kind {
+            ast::ItemKind::Global(body) => Some((self.global_scope(self.scope.file), body)),
+            ast::ItemKind::Module(decl) => match self.augmented_file(item) {
+                Some(target) => {
+                    let scope = Scope {
+                        file: self.scope.file,
+                        ..self.file_scope(target)
+                    };
+                    Some((scope, decl.body.as_ref()?))
+                }
+                None => self.scope.nested(item),
+            },
+            ast::ItemKind::Namespace(decl) => {
+                let (mut scope, body) = self.scope.nested(item)?;
+                // The namespace that `export =` names is the module, which is known to
+                // JavaScript by another name.
+                let name = &decl.name.parts[0].name;
+                if let Some(alias) = self.js_aliases.get(&self.scope.qualify(name)) {
+                    scope.js_namespace[self.scope.js_namespace.len()] = alias.clone();
+                }
+                Some((scope, body))
+            }
+            _ => self.scope.nested(item),
+        }
+    }
+
+    /// The file that `item` augments: in an ES module, `declare module "m" { ... }` adds to
+    /// module `m` when the project has it, rather than declaring an ambient module.
+    pub(super) fn augmented_file(&self, item: &ast::Item) -> Option<usize> {
+        let ast::ItemKind::Module(decl) = &item.kind else {
+            return None;
+        };
+        let file = &self.project.files[self.scope.file];
+        let top_level = self.scope.module == file.name && self.scope.namespace.is_empty();
+        if !file.is_module || !top_level {
+            return None;
+        }
+        file.resolved.get(&decl.name).copied()
+    }
+
+    /// Calls `f` with every item in `module` and, in their own scopes, in the namespaces and
+    /// modules it declares.
+    pub(super) fn for_each_item(
+        &mut self,
+        module: &ast::Module,
+        f: &mut dyn FnMut(&mut Self, &ast::Item),
+    ) {
+        for item in &module.items {
+            match self.nested_scope(item) {
+                Some((scope, body)) => self.in_scope(scope, |this| this.for_each_item(body, f)),
+                None => f(self, item),
+            }
+        }
+    }
+
+    /// Lowers a namespace or module declaration into the nested module for it. Repeated
+    /// declarations of the same namespace share one module. A namespace merged with a class
+    /// or function adds its functions and variables to that as static members instead; see
+    /// `merging.rs`. The declarations of `declare global` and of module augmentations go
+    /// into the modules of the scopes they reopen, once all files are lowered.
+    pub(super) fn lower_namespace(&mut self, item: &ast::Item, out: &mut ir::Module) {
+        let Some((scope, body)) = self.nested_scope(item) else {
+            // `declare module "pkg";` only says that the module exists.
+            return;
+        };
+        if matches!(item.kind, ast::ItemKind::Global(_)) || self.augmented_file(item).is_some() {
+            self.reopened.push((scope, body.clone()));
+            return;
+        }
+        let qualified = scope.js_path().join(".");
+        let items: Vec<&ast::Item> = match self.static_owners.get(&qualified).cloned() {
+            Some(owner) => self.lower_statics(&qualified, &owner, scope.clone(), item, body, out),
+            None => body.items.iter().collect(),
+        };
+        if items.is_empty() && !body.items.is_empty() {
+            return;
+        }
+        let mut module = &mut *out;
+        for (depth, name) in scope.rust.iter().enumerate().skip(self.scope.rust.len()) {
+            module = module.submodule_mut(name);
+            module.js_module = scope.js_module.clone();
+            let namespace_len = depth + 1 - (scope.rust.len() - scope.js_namespace.len());
+            module.js_namespace = scope.js_namespace[..namespace_len].to_vec();
+        }
+        if module.doc.is_none() {
+            module.doc = item.doc.clone();
+        }
+        self.in_scope(scope, |this| {
+            for item in items {
+                this.lower_item(item, module);
+            }
+        });
+    }
+}
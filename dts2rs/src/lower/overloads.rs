@@ -0,0 +1,169 @@
+//! Naming of overloaded functions.
+//!
+//! Rust has no overloading, so each TypeScript overload becomes its own binding, all with the
+//! same `js_name`. The overload with the fewest parameters keeps the plain name and the others
+//! are named after their parameters: `foo`, `foo_with_str`, `foo_with_str_and_options`. Among
+//! overloads with equally few parameters the last one wins, since declaration files list the
+//! most general overload last.
+//!
+//! Names can be pinned or overridden by key through [`Config::names`]. A function with an
+//! async wrapper gives its name to the wrapper, and its binding is named `foo_promise`.
+//! Names that are taken get a number, `foo2`, which is reported for indexing accessors, since
+//! `get2` next to a method `get` is easy to mistake for it.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::config::Config;
+use crate::diagnostics::Diagnostics;
+use crate::ir::{ExternItem, Function, FunctionKind, IndexOp, Module};
+
+/// The Rust namespace a function's name lives in: the module for free functions, the type for
+/// everything else.
+fn scope(f: &Function) -> &str {
+    f.owner().unwrap_or("")
+}
+
+/// Functions are overloads of each other if they share scope, kind and JavaScript name.
+fn overload_group(f: &Function) -> (&str, &'static str, &str) {
+    let kind = match &f.kind {
+        FunctionKind::Free => "fn",
+        FunctionKind::Method { .. } => "method",
+        FunctionKind::Getter { .. } | FunctionKind::StaticGetter { .. } => "get",
+        FunctionKind::Setter { .. } | FunctionKind::StaticSetter { .. } => "set",
+        FunctionKind::Constructor { .. } => "new",
+        FunctionKind::StaticMethod { .. } => "static",
+        FunctionKind::Index { op, .. } => match op {
+            IndexOp::Get => "index get",
+            IndexOp::Set => "index set",
+            IndexOp::Delete => "index delete",
+        },
+        FunctionKind::Call { .. } => "call",
+    };
+    (scope(f), kind, &f.js_name)
+}
+
+/// The key without its parameter list, which names a function regardless of overloads.
+fn bare_key(key: &str) -> &str {
+    key.split('(').next().unwrap_or(key)
+}
+
+/// Gives every function in `module` and its submodules its final Rust name and returns all
+/// names by key.
+pub(super) fn assign_names(
+    module: &mut Module,
+    config: &Config,
+    diags: &mut Diagnostics,
+) -> BTreeMap<String, String> {
+    let mut functions: Vec<&mut Function> = module
+        .externs
+        .iter_mut()
+        .filter_map(|item| match item {
+            ExternItem::Function(f) => Some(f),
+            _ => None,
+        })
+        .collect();
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut group_index: HashMap<(&str, &str, &str), usize> = HashMap::new();
+    for (i, f) in functions.iter().enumerate() {
+        let idx = *group_index.entry(overload_group(f)).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[idx].push(i);
+    }
+
+    // Decide each function's preferred name; `true` marks names the user pinned.
+    let mut chosen: Vec<(String, bool)> = vec![(String::new(), false); functions.len()];
+    for group in &groups {
+        let base_idx = *group
+            .iter()
+            .rev()
+            .min_by_key(|&&i| functions[i].params.len())
+            .unwrap();
+        for &i in group {
+            let f = &functions[i];
+            let pinned = config.names.get(&f.key).or_else(|| {
+                if group.len() == 1 {
+                    config.names.get(bare_key(&f.key))
+                } else {
+                    None
+                }
+            });
+            chosen[i] = match pinned {
+                Some(name) => (name.clone(), true),
+                None if i == base_idx || f.params.is_empty() => (f.rust_name.clone(), false),
+                None => {
+                    let words: Vec<&str> = f.params.iter().map(|p| p.descriptor.as_str()).collect();
+                    (
+                        format!("{}_with_{}", f.rust_name, words.join("_and_")),
+                        false,
+                    )
+                }
+            };
+        }
+    }
+
+    // Make names unique within each scope. Pinned names are reserved first so that generated
+    // names never take them.
+    let mut used: HashMap<String, HashSet<String>> = HashMap::new();
+    for (i, f) in functions.iter().enumerate() {
+        if chosen[i].1 {
+            used.entry(scope(f).to_string())
+                .or_default()
+                .insert(chosen[i].0.clone());
+        }
+    }
+    let mut names = BTreeMap::new();
+    // Indexing accessors are named last, so that members keep their names.
+    let mut order: Vec<usize> = (0..functions.len()).collect();
+    order.sort_by_key(|&i| matches!(functions[i].kind, FunctionKind::Index { .. }));
+    for i in order {
+        let f = &mut *functions[i];
+        let (name, pinned) = std::mem::take(&mut chosen[i]);
+        let used = used.entry(scope(f).to_string()).or_default();
+        f.rust_name = if pinned {
+            name
+        } else {
+            let rust_name = unique(used, name.clone());
+            if rust_name != name && matches!(f.kind, FunctionKind::Index { .. }) {
+                diags.warn_global(format!(
+                    "`{}` is bound as `{}`, since a member takes `{}`; its name can be set in `[names]`",
+                    f.key, rust_name, name
+                ));
+            }
+            rust_name
+        };
+        names
+            .entry(f.key.clone())
+            .or_insert_with(|| f.rust_name.clone());
+        if f.resolves.is_some() {
+            let promise = unique(used, format!("{}_promise", f.rust_name));
+            f.async_name = Some(std::mem::replace(&mut f.rust_name, promise));
+        }
+    }
+    // Statics share the module's namespace with free functions.
+    let used = used.entry(String::new()).or_default();
+    for item in &mut module.externs {
+        if let ExternItem::Static(s) = item {
+            s.rust_name = unique(used, std::mem::take(&mut s.rust_name));
+        }
+    }
+    for child in &mut module.modules {
+        for (key, name) in assign_names(child, config, diags) {
+            names.entry(key).or_insert(name);
+        }
+    }
+    names
+}
+
+/// `name`, or `name2`, `name3` and so on if it is taken, recorded in `used`.
+fn unique(used: &mut HashSet<String>, name: String) -> String {
+    let mut candidate = name.clone();
+    let mut n = 2;
+    while !used.insert(candidate.clone()) {
+        candidate = format!("{}{}", name, n);
+        n += 1;
+    }
+    candidate
+}
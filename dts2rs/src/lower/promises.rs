@@ -0,0 +1,36 @@
+//! Async wrappers of functions returning promises.
+//!
+//! With [`PromiseMode::Async`], `fetchUser(id: string): Promise<User>` is bound as
+//! `fetch_user_promise`, returning `js_sys::Promise`, and as
+//! `async fn fetch_user(id: &str) -> Result<User, JsValue>`, which awaits the promise and
+//! casts its value to `User`. Only free functions and methods get async wrappers; accessors
+//! and the members of extension traits return the promise.
+
+use dts_parser::ast::{self, TypeKind};
+
+use super::Lowerer;
+use crate::config::PromiseMode;
+use crate::ir::RustType;
+
+impl<'a> Lowerer<'a> {
+    /// The type that a function returning `ret` resolves to, if it returns a built-in
+    /// `Promise` and functions returning promises get async wrappers.
+    pub(super) fn map_resolved_type(&mut self, ret: Option<&ast::Type>) -> Option<RustType> {
+        if self.config.promises != PromiseMode::Async {
+            return None;
+        }
+        let ret = ret?;
+        let TypeKind::Reference(r) = &ret.kind else {
+            return None;
+        };
+        // A project declaring its own `Promise` shadows the built-in one.
+        if self.map_type(ret) != RustType::path("js_sys::Promise") {
+            return None;
+        }
+        match r.type_args.as_slice() {
+            [] => Some(RustType::JsValue),
+            [arg] => Some(self.map_return_type(Some(arg)).unwrap_or(RustType::Unit)),
+            _ => None,
+        }
+    }
+}
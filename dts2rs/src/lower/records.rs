@@ -0,0 +1,213 @@
+//! Lowering of records and index signatures.
+//!
+//! `Record<string, T>` and object types with nothing but an index signature, such as
+//! `{ [name: string]: T }`, are records. With [`RecordMode::Typed`] they are `JsRecord<T>`, a
+//! typed view of the JavaScript object with `get`, `set`, `keys` and `entries`, and with
+//! [`RecordMode::Map`] they are copied to and from `HashMap<String, T>`. Records of numbers,
+//! booleans and strings are `JsPrimitiveRecord<f64>`, `<bool>` and `<String>`, which convert
+//! values instead of casting them.
+//!
+//! The index signature of an interface or class gives its type indexing accessors: `get`,
+//! `set` and `delete` for string keys, `get_index`, `set_index` and `delete_index` for number
+//! keys, without `set` and `delete` for readonly signatures. A string index signature also
+//! gives it `keys`, `entries` and `to_map`.
+
+use dts_parser::ast::{self, KeywordType, TypeKind};
+use dts_parser::Span;
+
+use super::generics::js_cast_type;
+use super::interfaces::MemberTarget;
+use super::typed_arrays::js_sys_array;
+use super::Lowerer;
+use crate::config::RecordMode;
+use crate::ir::{self, FunctionKind, IndexOp, RustType};
+
+/// The typed view of records, defined in the root module when used.
+pub(super) const JS_RECORD: &str = "JsRecord";
+
+/// The view of records of numbers, booleans and strings, defined in the root module when used.
+pub(super) const JS_PRIMITIVE_RECORD: &str = "JsPrimitiveRecord";
+
+/// The type of the keys of an index signature.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum IndexKey {
+    String,
+    Number,
+}
+
+impl<'a> Lowerer<'a> {
+    /// Maps a record with keys of type `key` and values of type `value`. Records with symbol
+    /// keys are plain objects.
+    pub(super) fn map_record(&mut self, key: &ast::Type, value: Option<&ast::Type>) -> RustType {
+        if matches!(key.kind, TypeKind::Keyword(KeywordType::Symbol)) {
+            return RustType::path("js_sys::Object");
+        }
+        match self.config.records {
+            RecordMode::Typed => {
+                let value = match value {
+                    Some(value) => self.map_type(value),
+                    None => RustType::JsValue,
+                };
+                if matches!(value, RustType::F64 | RustType::Bool | RustType::String) {
+                    self.js_primitive_record = true;
+                    return RustType::Path(JS_PRIMITIVE_RECORD.to_string(), vec![value]);
+                }
+                self.js_record = true;
+                RustType::Path(JS_RECORD.to_string(), vec![js_cast_type(value)])
+            }
+            RecordMode::Map => {
+                let value = self.map_param_type(value);
+                RustType::Map(Box::new(js_sys_array(value)))
+            }
+        }
+    }
+
+    /// Maps an object type that is nothing but an index signature, as a record.
+    pub(super) fn map_index_object(&mut self, members: &[ast::Member]) -> Option<RustType> {
+        let [ast::Member {
+            kind: ast::MemberKind::Index(sig),
+            ..
+        }] = members
+        else {
+            return None;
+        };
+        let key = sig.params.first()?.ty.as_ref()?;
+        Some(self.map_record(key, sig.ty.as_ref()))
+    }
+
+    /// Adds `JsRecord<T>` and `JsPrimitiveRecord<T>` to the root module if any record is
+    /// bound as one.
+    pub(super) fn push_js_record(&self, out: &mut ir::Module) {
+        let records = [
+            (
+                self.js_record,
+                JS_RECORD,
+                ir::GenericKind::Record,
+                "A JavaScript object used as a record of `T` by string key.",
+            ),
+            (
+                self.js_primitive_record,
+                JS_PRIMITIVE_RECORD,
+                ir::GenericKind::PrimitiveRecord,
+                "A JavaScript object used as a record of `T`, a number, boolean or string, by \
+                 string key.",
+            ),
+        ];
+        for (used, rust_name, kind, doc) in records {
+            if !used {
+                continue;
+            }
+            out.generics.push(ir::GenericType {
+                rust_name: rust_name.to_string(),
+                erased: "js_sys::Object".to_string(),
+                type_params: vec![ir::TypeParam {
+                    name: "T".to_string(),
+                    default: None,
+                }],
+                kind,
+                doc: Some(doc.to_string()),
+            });
+        }
+    }
+
+    /// Adds the accessors of index signature `sig` of `target`.
+    pub(super) fn push_index_accessors(
+        &mut self,
+        target: &MemberTarget,
+        sig: &ast::IndexSig,
+        span: Span,
+        doc: Option<&str>,
+        out: &mut ir::Module,
+    ) {
+        let key = match sig
+            .params
+            .first()
+            .and_then(|p| p.ty.as_ref())
+            .map(|t| &t.kind)
+        {
+            Some(TypeKind::Keyword(KeywordType::String) | TypeKind::Template { .. }) => {
+                IndexKey::String
+            }
+            Some(TypeKind::Keyword(KeywordType::Number)) => IndexKey::Number,
+            _ => {
+                self.diags.warn(
+                    self.file,
+                    span,
+                    "index signatures with keys other than strings or numbers are skipped",
+                );
+                return;
+            }
+        };
+        if target.is_static {
+            self.diags
+                .warn(self.file, span, "static index signatures are skipped");
+            return;
+        }
+        let value = self.in_context("index", |this| this.map_param_type(sig.ty.as_ref()));
+        let (key_param, js_key, suffix) = match key {
+            IndexKey::String => (
+                ir::Param {
+                    name: "key".to_string(),
+                    descriptor: "key".to_string(),
+                    ty: RustType::String,
+                },
+                "[string]",
+                "",
+            ),
+            IndexKey::Number => (
+                ir::Param {
+                    name: "index".to_string(),
+                    descriptor: "index".to_string(),
+                    ty: RustType::F64,
+                },
+                "[number]",
+                "_index",
+            ),
+        };
+        let mut push = |op: IndexOp, prefix: &str, params: Vec<ir::Param>, ret| {
+            out.externs.push(ir::ExternItem::Function(ir::Function {
+                kind: FunctionKind::Index {
+                    this: target.this.to_string(),
+                    op,
+                },
+                rust_name: format!("{}{}", prefix, suffix),
+                key: format!("{} {}{}", prefix, target.js_this, js_key),
+                js_name: js_key.to_string(),
+                type_params: Vec::new(),
+                params,
+                ret,
+                variadic: false,
+                resolves: None,
+                async_name: None,
+                doc: doc.map(str::to_string),
+            }));
+        };
+        push(
+            IndexOp::Get,
+            "get",
+            vec![key_param.clone()],
+            Some(RustType::option(value.clone())),
+        );
+        if !sig.readonly {
+            let value_param = ir::Param {
+                name: "value".to_string(),
+                descriptor: "value".to_string(),
+                ty: value.clone(),
+            };
+            push(
+                IndexOp::Set,
+                "set",
+                vec![key_param.clone(), value_param],
+                None,
+            );
+            push(IndexOp::Delete, "delete", vec![key_param], None);
+        }
+        // Merged declarations may repeat the signature.
+        if key == IndexKey::String && !out.entries.iter().any(|e| e.this == target.this) {
+            out.entries.push(ir::Entries {
+                this: target.this.to_string(),
+                value: js_sys_array(value),
+            });
+        }
+    }
+}
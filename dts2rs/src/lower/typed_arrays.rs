@@ -0,0 +1,78 @@
+//! Lowering of typed arrays and buffers to Rust slices.
+//!
+//! With [`TypedArrayMode::Slice`], a `Float32Array` parameter takes `&[f32]`, which
+//! wasm-bindgen copies to a new `Float32Array` for the call, and a `Float32Array` result is
+//! copied to a `Vec<f32>`. Parameters listed in
+//! [`Config::mutable_slices`](crate::Config::mutable_slices) take `&mut [f32]` instead, which
+//! gets what JavaScript writes to the array copied back. An `ArrayBuffer` is copied from
+//! `&[u8]` and to `Vec<u8>` by a wrapper, and `ArrayBufferView` and `BufferSource`, which
+//! any typed array can be passed as, take `&[u8]` and return the bytes of the view.
+//!
+//! Where a slice can't be, in unions, callbacks, type arguments and statics, the js-sys type
+//! is used.
+
+use super::Lowerer;
+use crate::config::TypedArrayMode;
+use crate::ir::{RustType, TypedArray, TypedArrayKind};
+
+/// The element types of typed arrays, by TypeScript name.
+const TYPED_ARRAYS: &[(&str, &str)] = &[
+    ("Int8Array", "i8"),
+    ("Uint8Array", "u8"),
+    ("Int16Array", "i16"),
+    ("Uint16Array", "u16"),
+    ("Int32Array", "i32"),
+    ("Uint32Array", "u32"),
+    ("Float32Array", "f32"),
+    ("Float64Array", "f64"),
+    ("BigInt64Array", "i64"),
+    ("BigUint64Array", "u64"),
+];
+
+/// Types that any typed array can be passed as.
+const VIEWS: &[&str] = &["ArrayBufferView", "BufferSource", "AllowSharedBufferSource"];
+
+impl<'a> Lowerer<'a> {
+    /// Maps global type `name` if it is a typed array, buffer or view bound as a slice.
+    pub(super) fn map_typed_array(&self, name: &str) -> Option<RustType> {
+        if self.config.typed_arrays != TypedArrayMode::Slice
+            || self.config.globals.contains_key(name)
+        {
+            return None;
+        }
+        let (elem, kind) = match TYPED_ARRAYS.iter().find(|(ts, _)| *ts == name) {
+            Some((_, elem)) => (*elem, TypedArrayKind::Array),
+            None if name == "ArrayBuffer" => ("u8", TypedArrayKind::Buffer),
+            None if VIEWS.contains(&name) => ("u8", TypedArrayKind::View),
+            None => return None,
+        };
+        let mutable = kind != TypedArrayKind::Buffer && self.is_mutable_slice();
+        Some(RustType::TypedArray(TypedArray {
+            elem: elem.to_string(),
+            // wasm-bindgen copies `&mut [u8]` to a `Uint8Array` and back by itself.
+            kind: match kind {
+                TypedArrayKind::View if mutable => TypedArrayKind::Array,
+                kind => kind,
+            },
+            mutable,
+        }))
+    }
+
+    /// Whether the parameter being lowered is listed in `mutable_slices`.
+    fn is_mutable_slice(&self) -> bool {
+        if self.context.last().is_none_or(|last| last == "return") {
+            return false;
+        }
+        let key = self.scope.qualify(&self.context.join("."));
+        self.config.mutable_slices.contains(&key)
+    }
+}
+
+/// The js-sys type of a typed array, for places that a slice can't be.
+pub(super) fn js_sys_array(ty: RustType) -> RustType {
+    match ty {
+        RustType::TypedArray(t) => RustType::path(t.js_type()),
+        RustType::Option(inner) => RustType::option(js_sys_array(*inner)),
+        ty => ty,
+    }
+}
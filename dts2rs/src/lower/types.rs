@@ -0,0 +1,256 @@
+//! Mapping of TypeScript types to Rust types.
+
+use dts_parser::ast::{self, KeywordType, LiteralType, TypeKind};
+use dts_parser::Span;
+
+use super::Lowerer;
+use crate::ir::RustType;
+
+impl<'a> Lowerer<'a> {
+    pub(super) fn map_type(&mut self, ty: &ast::Type) -> RustType {
+        match &ty.kind {
+            TypeKind::Keyword(KeywordType::This) => match &self.this_type {
+                Some(this) => RustType::path(this.clone()),
+                None => RustType::JsValue,
+            },
+            TypeKind::Keyword(kw) => keyword_type(*kw),
+            TypeKind::Literal(lit) => literal_type(lit),
+            TypeKind::Reference(r) => self.map_type_ref(r),
+            TypeKind::Import {
+                module,
+                qualifier: Some(qualifier),
+                type_args,
+            } => self.map_import_type(module, qualifier, type_args, ty.span),
+            TypeKind::Array(elem) => self.map_array(elem),
+            TypeKind::Tuple(elems) => self.map_tuple(elems),
+            TypeKind::Operator(ast::TypeOperator::Readonly, inner) => self.map_type(inner),
+            TypeKind::Function(_) => RustType::path("js_sys::Function"),
+            TypeKind::Object(members) => self.map_object_literal(ty, members),
+            TypeKind::Union(types) => self.map_union(types, ty.span, None),
+            TypeKind::Intersection(types) => self.map_intersection(ty, types),
+            TypeKind::Mapped(_)
+            | TypeKind::Conditional(_)
+            | TypeKind::Indexed { .. }
+            | TypeKind::Operator(ast::TypeOperator::Keyof, _) => self.map_computed(ty),
+            TypeKind::Template { .. } => RustType::String,
+            TypeKind::Query(name) => self
+                .function_type(&name.to_dotted())
+                .unwrap_or(RustType::JsValue),
+            TypeKind::Predicate { asserts: true, .. } => RustType::Unit,
+            TypeKind::Predicate { .. } => RustType::Bool,
+            _ => RustType::JsValue,
+        }
+    }
+
+    fn map_type_ref(&mut self, r: &ast::TypeRef) -> RustType {
+        let name = r.name.to_dotted();
+        if let Some(ty) = self.map_type_param(&name) {
+            return ty;
+        }
+        let candidates = self.candidates(&name);
+        let enum_candidates = match name.rsplit_once('.') {
+            Some((enum_name, _)) => self.candidates(enum_name),
+            None => Vec::new(),
+        };
+        if let Some(ty) = self.map_declared(r, &candidates, &enum_candidates) {
+            return ty;
+        }
+        if let Some(ty) = self.map_typed_array(&name) {
+            return ty;
+        }
+        if let ("Record", [key, value]) = (name.as_str(), r.type_args.as_slice()) {
+            return self.map_record(key, Some(value));
+        }
+        if let Some(ty) = self.map_utility(r) {
+            return ty;
+        }
+        if let ("Exclude" | "Extract", [_, _]) | ("NonNullable", [_]) =
+            (name.as_str(), r.type_args.as_slice())
+        {
+            let ty = ast::Type {
+                kind: TypeKind::Reference(r.clone()),
+                span: r.span,
+            };
+            return self.map_computed(&ty);
+        }
+        if let Some(path) = self.builtin_type(&name) {
+            // `Array<T>` and `ReadonlyArray<T>` are `T[]`.
+            if let ("js_sys::Array", [elem]) = (path.as_str(), r.type_args.as_slice()) {
+                return self.map_array(elem);
+            }
+            return RustType::path(path);
+        }
+        if !self.is_unresolved_import(&name) {
+            self.diags.warn(
+                self.file,
+                r.span,
+                format!("unknown type `{}`; it is bound as `JsValue`", name),
+            );
+        }
+        RustType::JsValue
+    }
+
+    /// Maps `import("./module").Name`, a reference to an export of another module.
+    fn map_import_type(
+        &mut self,
+        module: &str,
+        qualifier: &ast::EntityName,
+        type_args: &[ast::Type],
+        span: Span,
+    ) -> RustType {
+        // Modules that aren't found are reported where the project is loaded.
+        let Some(module) = self.module_ref(module) else {
+            return RustType::JsValue;
+        };
+        let path: Vec<&str> = qualifier.parts.iter().map(|p| p.name.as_str()).collect();
+        let candidates = self.export_candidates(&module, &path);
+        let enum_candidates = match path.split_last() {
+            Some((_, enum_path)) if !enum_path.is_empty() => {
+                self.export_candidates(&module, enum_path)
+            }
+            _ => Vec::new(),
+        };
+        let r = ast::TypeRef {
+            name: qualifier.clone(),
+            type_args: type_args.to_vec(),
+            span,
+        };
+        if let Some(ty) = self.map_declared(&r, &candidates, &enum_candidates) {
+            return ty;
+        }
+        self.diags.warn(
+            self.file,
+            span,
+            format!(
+                "unknown type `{}`; it is bound as `JsValue`",
+                qualifier.to_dotted()
+            ),
+        );
+        RustType::JsValue
+    }
+
+    /// Maps a reference to the first declaration among `candidates`. `enum_candidates` name
+    /// the enum for a reference to one of its members, as in `Direction.Up`.
+    fn map_declared(
+        &mut self,
+        r: &ast::TypeRef,
+        candidates: &[String],
+        enum_candidates: &[String],
+    ) -> Option<RustType> {
+        if let Some((_, rust_name)) = Self::find(&self.generic_types, candidates) {
+            return Some(self.map_generic_ref(r, rust_name.clone()));
+        }
+        if let Some((_, ty)) = Self::find(&self.enum_types, candidates)
+            .or_else(|| Self::find(&self.enum_types, enum_candidates))
+        {
+            return Some(ty.clone());
+        }
+        if let Some((_, rust_name)) = Self::find(&self.types, candidates) {
+            return Some(RustType::path(rust_name.clone()));
+        }
+        if let Some((qualified, (_, item))) = Self::find(&self.aliases, candidates) {
+            let qualified = qualified.clone();
+            // Instances of generic aliases are evaluated with their type arguments.
+            if let ast::ItemKind::TypeAlias(decl) = &item.kind {
+                if !decl.type_params.is_empty() {
+                    return Some(self.map_alias_instance(&qualified, &r.type_args));
+                }
+            }
+            return Some(self.resolve_alias(&qualified));
+        }
+        None
+    }
+
+    /// Maps the type of a parameter. A missing annotation means `any`.
+    pub(super) fn map_param_type(&mut self, ty: Option<&ast::Type>) -> RustType {
+        match ty {
+            Some(ty) => {
+                let mapped = self.map_type(ty);
+                self.value_type(mapped)
+            }
+            None => RustType::JsValue,
+        }
+    }
+
+    /// Maps the element type of a rest parameter `...args: T[]` to a slice.
+    pub(super) fn map_rest_type(&mut self, ty: Option<&ast::Type>) -> RustType {
+        let elem = match ty.map(|t| &t.kind) {
+            Some(TypeKind::Array(elem)) => self.map_type(elem),
+            _ => RustType::JsValue,
+        };
+        // Slices can only hold types with a fixed wasm ABI.
+        let elem = match elem {
+            RustType::F64 => RustType::F64,
+            _ => RustType::JsValue,
+        };
+        RustType::Slice(Box::new(elem))
+    }
+
+    /// Maps a return type; `None` means the function returns nothing.
+    pub(super) fn map_return_type(&mut self, ty: Option<&ast::Type>) -> Option<RustType> {
+        match ty {
+            Some(ty) => match self.in_context("return", |this| this.map_type(ty)) {
+                RustType::Unit => None,
+                mapped => {
+                    self.report_defaults(ty, &mapped);
+                    Some(mapped)
+                }
+            },
+            None => Some(RustType::JsValue),
+        }
+    }
+
+    /// Guesses the type of an unannotated `const x = <literal>`.
+    pub(super) fn map_initializer_type(&mut self, init: Option<&ast::Expr>) -> RustType {
+        match init.map(|e| &e.kind) {
+            Some(ast::ExprKind::Num(_)) => RustType::F64,
+            Some(ast::ExprKind::Unary(ast::UnaryOp::Neg, operand))
+                if matches!(operand.kind, ast::ExprKind::Num(_)) =>
+            {
+                RustType::F64
+            }
+            Some(ast::ExprKind::Str(_)) | Some(ast::ExprKind::Template(_)) => RustType::String,
+            Some(ast::ExprKind::Bool(_)) => RustType::Bool,
+            _ => RustType::JsValue,
+        }
+    }
+
+    /// `()` can't be passed or stored, so `void` and friends become `JsValue` there.
+    pub(super) fn value_type(&self, ty: RustType) -> RustType {
+        match ty {
+            RustType::Unit => RustType::JsValue,
+            ty => ty,
+        }
+    }
+}
+
+fn keyword_type(kw: KeywordType) -> RustType {
+    match kw {
+        KeywordType::Number => RustType::F64,
+        KeywordType::String => RustType::String,
+        KeywordType::Boolean => RustType::Bool,
+        KeywordType::Void | KeywordType::Undefined | KeywordType::Never => RustType::Unit,
+        KeywordType::Object => RustType::path("js_sys::Object"),
+        KeywordType::Symbol => RustType::path("js_sys::Symbol"),
+        KeywordType::BigInt => RustType::path("js_sys::BigInt"),
+        KeywordType::Any | KeywordType::Unknown | KeywordType::Null | KeywordType::This => {
+            RustType::JsValue
+        }
+    }
+}
+
+fn literal_type(lit: &LiteralType) -> RustType {
+    match lit {
+        LiteralType::Str(_) => RustType::String,
+        LiteralType::Num(_) => RustType::F64,
+        LiteralType::Bool(_) => RustType::Bool,
+        LiteralType::BigInt(_) => RustType::path("js_sys::BigInt"),
+    }
+}
+
+pub(super) fn is_nullish(ty: &ast::Type) -> bool {
+    matches!(
+        ty.kind,
+        TypeKind::Keyword(KeywordType::Null | KeywordType::Undefined | KeywordType::Void)
+    )
+}
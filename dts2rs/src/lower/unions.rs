@@ -0,0 +1,280 @@
+//! Lowering of union types.
+//!
+//! Depending on the [`UnionStrategy`] configured for it, a union becomes either `JsValue` or a
+//! `#[wasm_bindgen]` enum with a unit variant per string literal and a variant holding a value
+//! for every other member. `null` and `undefined` members make the result an `Option`.
+
+use dts_parser::ast::{self, KeywordType, LiteralType, TypeKind};
+use dts_parser::Span;
+
+use super::computed;
+use super::generics::without_type_params;
+use super::types::is_nullish;
+use super::Lowerer;
+use crate::config::UnionStrategy;
+use crate::ir::{self, RustType, VariantKind};
+use crate::names;
+
+/// How deeply unions of aliases of unions are flattened before giving up.
+pub(super) const MAX_ALIAS_DEPTH: usize = 16;
+
+impl<'a> Lowerer<'a> {
+    /// Maps a union. `alias` is the qualified name of the type alias the union is the whole
+    /// body of.
+    pub(super) fn map_union(
+        &mut self,
+        types: &[ast::Type],
+        span: Span,
+        alias: Option<&str>,
+    ) -> RustType {
+        let mut members = Vec::new();
+        let mut nullable = false;
+        let non_null: Vec<&ast::Type> = types.iter().filter(|t| !is_nullish(t)).collect();
+        let inner = if let [single] = non_null.as_slice() {
+            // `T | null`, where `T` keeps its own name even if it is an alias of a union.
+            nullable = non_null.len() < types.len();
+            self.map_type(single)
+        } else {
+            self.flatten_union(types, &mut members, &mut nullable, 0);
+            self.map_union_members(&members, span, alias)
+        };
+        if nullable {
+            RustType::option(inner)
+        } else {
+            inner
+        }
+    }
+
+    /// Collects the non-nullish members of a union, expanding nested unions, aliases of
+    /// unions and the types conditional types and lookups evaluate to, as TypeScript does.
+    fn flatten_union(
+        &mut self,
+        types: &[ast::Type],
+        out: &mut Vec<ast::Type>,
+        nullable: &mut bool,
+        depth: usize,
+    ) {
+        for ty in types {
+            if is_nullish(ty) {
+                *nullable = true;
+                continue;
+            }
+            match &ty.kind {
+                TypeKind::Union(inner) => self.flatten_union(inner, out, nullable, depth),
+                TypeKind::Reference(r) if depth < MAX_ALIAS_DEPTH && r.type_args.is_empty() => {
+                    match self.alias_union(r).map(<[ast::Type]>::to_vec) {
+                        Some(inner) => self.flatten_union(&inner, out, nullable, depth + 1),
+                        None => out.push(ty.clone()),
+                    }
+                }
+                _ if depth < MAX_ALIAS_DEPTH && computed::is_computed(ty) => {
+                    match self.reduce(ty, 0) {
+                        Some((scope, reduced)) if scope == self.scope => {
+                            self.flatten_union(&[reduced], out, nullable, depth + 1)
+                        }
+                        _ => out.push(ty.clone()),
+                    }
+                }
+                _ => out.push(ty.clone()),
+            }
+        }
+    }
+
+    /// The members of the union a reference names, if it names an alias of a union.
+    fn alias_union(&self, r: &ast::TypeRef) -> Option<&[ast::Type]> {
+        let (_, (_, item)) = self.lookup(&self.aliases, &r.name.to_dotted())?;
+        match &item.kind {
+            ast::ItemKind::TypeAlias(ast::TypeAliasDecl {
+                ty:
+                    ast::Type {
+                        kind: TypeKind::Union(types),
+                        ..
+                    },
+                ..
+            }) => Some(types),
+            _ => None,
+        }
+    }
+
+    fn map_union_members(
+        &mut self,
+        members: &[ast::Type],
+        span: Span,
+        alias: Option<&str>,
+    ) -> RustType {
+        // `any` and `unknown` absorb every other member, and so does `string` every string
+        // literal.
+        if members.iter().any(|t| {
+            matches!(
+                t.kind,
+                TypeKind::Keyword(KeywordType::Any | KeywordType::Unknown)
+            )
+        }) {
+            return RustType::JsValue;
+        }
+        let has_string = members
+            .iter()
+            .any(|t| matches!(t.kind, TypeKind::Keyword(KeywordType::String)));
+        let mut variants: Vec<VariantKind> = Vec::new();
+        for ty in members {
+            let variant = match &ty.kind {
+                TypeKind::Literal(LiteralType::Str(value)) if !has_string => {
+                    VariantKind::Str(value.clone())
+                }
+                _ => {
+                    let mapped = without_type_params(self.map_type(ty));
+                    VariantKind::Value(self.value_type(mapped))
+                }
+            };
+            if !variants.contains(&variant) {
+                variants.push(variant);
+            }
+        }
+        match variants.as_slice() {
+            [] => return RustType::JsValue,
+            [VariantKind::Value(ty)] => return ty.clone(),
+            // Distributed conditional types may give the same literal more than once.
+            [VariantKind::Str(_)] => return self.map_type(&members[0]),
+            _ => {}
+        }
+        let all_strings = variants.iter().all(|v| matches!(v, VariantKind::Str(_)));
+        let as_enum = match self.union_strategy() {
+            UnionStrategy::Auto => all_strings || alias.is_some(),
+            UnionStrategy::Enum => true,
+            UnionStrategy::StringEnum => all_strings,
+            UnionStrategy::JsValue => false,
+        };
+        if !as_enum {
+            return RustType::JsValue;
+        }
+        RustType::Value(self.union_enum(variants, span, alias))
+    }
+
+    /// The strategy for the union at the current position: the one configured for the
+    /// innermost enclosing declaration, or the default. Declarations in namespaces are keyed
+    /// by their qualified names.
+    fn union_strategy(&self) -> UnionStrategy {
+        let mut key = match self.context.as_slice() {
+            [] => String::new(),
+            context => self.scope.qualify(&context.join(".")),
+        };
+        loop {
+            if let Some(strategy) = self.config.unions.get(&key) {
+                return *strategy;
+            }
+            match key.rfind('.') {
+                Some(i) => key.truncate(i),
+                None => return self.config.union_strategy,
+            }
+        }
+    }
+
+    /// Defines the enum for a union with the given members and returns its name. Unnamed
+    /// unions with the same members share one enum.
+    fn union_enum(
+        &mut self,
+        mut kinds: Vec<VariantKind>,
+        span: Span,
+        alias: Option<&str>,
+    ) -> String {
+        // Conversion from JavaScript tries variants in order, so the more specific checks go
+        // first and plain objects, which match any object, last.
+        kinds.sort_by_key(|kind| self.variant_rank(kind));
+        if alias.is_none() {
+            if let Some(name) = self.enum_names.get(&kinds) {
+                return name.clone();
+            }
+        }
+        let (rust_name, doc) = match alias {
+            Some(alias) => {
+                let (_, item) = &self.aliases[alias];
+                let name = names::type_name(item.kind.name().map_or(alias, |n| &n.name));
+                let doc = item.doc.clone();
+                (self.fresh_type_name(&name), doc)
+            }
+            None => {
+                let base: String = self.context.iter().map(|p| names::pascal_case(p)).collect();
+                let base = if base.is_empty() {
+                    "Union".to_string()
+                } else {
+                    base
+                };
+                let text = span
+                    .text(&self.file.src)
+                    .split_whitespace()
+                    .collect::<Vec<_>>();
+                (
+                    self.fresh_type_name(&base),
+                    Some(format!("`{}`", text.join(" "))),
+                )
+            }
+        };
+        let mut variants: Vec<ir::Variant> = Vec::new();
+        for kind in &kinds {
+            let base = variant_name(kind);
+            let mut name = base.clone();
+            let mut n = 2;
+            while variants.iter().any(|v| v.name == name) {
+                name = format!("{}{}", base, n);
+                n += 1;
+            }
+            variants.push(ir::Variant {
+                name,
+                kind: kind.clone(),
+                doc: None,
+            });
+        }
+        self.enum_names
+            .entry(kinds)
+            .or_insert_with(|| rust_name.clone());
+        self.enums.push(ir::Enum {
+            rust_name: rust_name.clone(),
+            variants,
+            aliases: Vec::new(),
+            doc,
+        });
+        rust_name
+    }
+
+    fn variant_rank(&self, kind: &VariantKind) -> u8 {
+        match kind {
+            VariantKind::Int(_) | VariantKind::Str(_) => 0,
+            VariantKind::Value(RustType::Value(_)) => 1,
+            VariantKind::Value(RustType::String | RustType::F64 | RustType::Bool) => 2,
+            VariantKind::Value(RustType::Path(path, _))
+                if path != "js_sys::Object" && !self.interfaces.contains(path) =>
+            {
+                3
+            }
+            VariantKind::Value(RustType::JsValue) => 5,
+            VariantKind::Value(_) => 4,
+        }
+    }
+
+    /// The Rust path of a new type in the current scope named `base`, or `base` with the
+    /// first free number appended if another type already has that name.
+    pub(super) fn fresh_type_name(&mut self, base: &str) -> String {
+        let mut path = self.scope.rust_path(base);
+        let mut n = 2;
+        while !self.type_names.insert(path.clone()) {
+            path = self.scope.rust_path(&format!("{}{}", base, n));
+            n += 1;
+        }
+        path
+    }
+}
+
+fn variant_name(kind: &VariantKind) -> String {
+    match kind {
+        VariantKind::Int(value) => format!("V{}", value),
+        VariantKind::Str(value) => names::variant_name(value),
+        VariantKind::Value(ty) => match ty {
+            RustType::String => "String".to_string(),
+            RustType::F64 => "Number".to_string(),
+            RustType::Bool => "Boolean".to_string(),
+            RustType::Path(path, _) => path.rsplit("::").next().unwrap_or(path).to_string(),
+            RustType::Value(path) => path.rsplit("::").next().unwrap_or(path).to_string(),
+            _ => "Other".to_string(),
+        },
+    }
+}
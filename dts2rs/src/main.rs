@@ -0,0 +1,267 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use dts2rs::{
+    ArrayMode, CompilerOptions, Config, Generator, PromiseMode, RecordMode, TypedArrayMode, UmdMode,
+};
+
+const USAGE: &str = "\
+Usage: dts2rs [OPTIONS] <INPUT.d.ts>
+       dts2rs [OPTIONS] --package <NAME>
+
+Options:
+  -o, --output <FILE>      Write the bindings to FILE instead of stdout
+  -c, --config <FILE>      Read configuration from a TOML file
+  -p, --project <FILE>     Resolve modules and libraries with the compiler options of
+                           a tsconfig.json
+      --package <NAME>     Bind package NAME, found in node_modules from the current
+                           directory up
+      --module <NAME>      Import declarations of ES module files from module NAME
+      --umd <MODE>         Bind UMD libraries as modules (`module`, the default) or
+                           through their global (`global`)
+      --promises <MODE>    Bind functions returning promises as returning
+                           `js_sys::Promise` (`raw`, the default) or also as `async fn`
+                           (`async`)
+      --arrays <MODE>      Bind arrays and tuples as `js_sys::Array` (`array`, the
+                           default), copied to `Vec` and tuples (`vec`) or as typed
+                           `JsArray` views and tuples (`typed`)
+      --typed-arrays <MODE>
+                           Bind typed arrays and buffers as js-sys types (`js-sys`,
+                           the default) or copied from slices and to `Vec`s (`slice`)
+      --records <MODE>     Bind records as typed `JsRecord` views (`typed`, the
+                           default) or copied to and from `HashMap`s (`map`)
+      --cargo-toml <FILE>  Write a Cargo.toml with the dependencies and web-sys
+                           features the bindings need to FILE
+      --dump-names <FILE>  Write the generated function and type names to FILE as a
+                           config, so that they can be pinned
+  -h, --help               Print this help";
+
+enum Input {
+    File(PathBuf),
+    Package(String),
+}
+
+struct Args {
+    input: Input,
+    output: Option<PathBuf>,
+    config: Option<PathBuf>,
+    project: Option<PathBuf>,
+    module: Option<String>,
+    umd: Option<UmdMode>,
+    promises: Option<PromiseMode>,
+    arrays: Option<ArrayMode>,
+    typed_arrays: Option<TypedArrayMode>,
+    records: Option<RecordMode>,
+    cargo_toml: Option<PathBuf>,
+    dump_names: Option<PathBuf>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut input = None;
+    let mut package = None;
+    let mut output = None;
+    let mut config = None;
+    let mut project = None;
+    let mut module = None;
+    let mut umd = None;
+    let mut promises = None;
+    let mut arrays = None;
+    let mut typed_arrays = None;
+    let mut records = None;
+    let mut cargo_toml = None;
+    let mut dump_names = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            "-o" | "--output" => {
+                let path = args.next().ok_or("missing value for --output")?;
+                output = Some(PathBuf::from(path));
+            }
+            "-c" | "--config" => {
+                let path = args.next().ok_or("missing value for --config")?;
+                config = Some(PathBuf::from(path));
+            }
+            "-p" | "--project" => {
+                let path = args.next().ok_or("missing value for --project")?;
+                project = Some(PathBuf::from(path));
+            }
+            "--package" => {
+                package = Some(args.next().ok_or("missing value for --package")?);
+            }
+            "--module" => {
+                module = Some(args.next().ok_or("missing value for --module")?);
+            }
+            "--umd" => {
+                umd = Some(match args.next().as_deref() {
+                    Some("module") => UmdMode::Module,
+                    Some("global") => UmdMode::Global,
+                    Some(mode) => return Err(format!("unknown UMD mode `{}`", mode)),
+                    None => return Err("missing value for --umd".to_string()),
+                });
+            }
+            "--promises" => {
+                promises = Some(match args.next().as_deref() {
+                    Some("raw") => PromiseMode::Raw,
+                    Some("async") => PromiseMode::Async,
+                    Some(mode) => return Err(format!("unknown promise mode `{}`", mode)),
+                    None => return Err("missing value for --promises".to_string()),
+                });
+            }
+            "--arrays" => {
+                arrays = Some(match args.next().as_deref() {
+                    Some("array") => ArrayMode::Array,
+                    Some("vec") => ArrayMode::Vec,
+                    Some("typed") => ArrayMode::Typed,
+                    Some(mode) => return Err(format!("unknown array mode `{}`", mode)),
+                    None => return Err("missing value for --arrays".to_string()),
+                });
+            }
+            "--typed-arrays" => {
+                typed_arrays = Some(match args.next().as_deref() {
+                    Some("js-sys") => TypedArrayMode::JsSys,
+                    Some("slice") => TypedArrayMode::Slice,
+                    Some(mode) => return Err(format!("unknown typed array mode `{}`", mode)),
+                    None => return Err("missing value for --typed-arrays".to_string()),
+                });
+            }
+            "--records" => {
+                records = Some(match args.next().as_deref() {
+                    Some("typed") => RecordMode::Typed,
+                    Some("map") => RecordMode::Map,
+                    Some(mode) => return Err(format!("unknown record mode `{}`", mode)),
+                    None => return Err("missing value for --records".to_string()),
+                });
+            }
+            "--cargo-toml" => {
+                let path = args.next().ok_or("missing value for --cargo-toml")?;
+                cargo_toml = Some(PathBuf::from(path));
+            }
+            "--dump-names" => {
+                let path = args.next().ok_or("missing value for --dump-names")?;
+                dump_names = Some(PathBuf::from(path));
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ if input.is_none() => input = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument `{}`", arg)),
+        }
+    }
+    let input = match (input, package) {
+        (Some(path), None) => Input::File(path),
+        (None, Some(name)) => Input::Package(name),
+        (None, None) => return Err("no input file given".to_string()),
+        (Some(_), Some(_)) => return Err("give either an input file or --package".to_string()),
+    };
+    Ok(Args {
+        input,
+        output,
+        config,
+        project,
+        module,
+        umd,
+        promises,
+        arrays,
+        typed_arrays,
+        records,
+        cargo_toml,
+        dump_names,
+    })
+}
+
+fn main() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(msg) => {
+            eprintln!("error: {}\n\n{}", msg, USAGE);
+            process::exit(2);
+        }
+    };
+    let mut config = match &args.config {
+        Some(path) => match Config::from_file(path) {
+            Ok(config) => config,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                process::exit(1);
+            }
+        },
+        None => Config::default(),
+    };
+    if let Some(module) = &args.module {
+        config.module = Some(module.clone());
+    }
+    if let Some(umd) = args.umd {
+        config.umd = umd;
+    }
+    if let Some(promises) = args.promises {
+        config.promises = promises;
+    }
+    if let Some(arrays) = args.arrays {
+        config.arrays = arrays;
+    }
+    if let Some(typed_arrays) = args.typed_arrays {
+        config.typed_arrays = typed_arrays;
+    }
+    if let Some(records) = args.records {
+        config.records = records;
+    }
+    let mut generator = Generator::with_config(config);
+    if let Some(path) = &args.project {
+        match CompilerOptions::from_file(path) {
+            Ok(options) => generator = generator.compiler_options(options),
+            Err(err) => {
+                eprintln!("error: {}", err);
+                process::exit(1);
+            }
+        }
+    }
+    let result = match &args.input {
+        Input::File(path) => generator.generate_file(path),
+        Input::Package(name) => generator.generate_package(name, Path::new(".")),
+    };
+    let output = match result {
+        Ok(output) => output,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        }
+    };
+    for diag in &output.diagnostics {
+        eprintln!("{}", diag);
+    }
+    if let Some(path) = &args.cargo_toml {
+        write_or_exit(path, &output.cargo_toml(&crate_name(path)));
+    }
+    if let Some(path) = &args.dump_names {
+        let names = Config {
+            names: output.names.clone(),
+            type_names: output.type_names.clone(),
+            ..Config::default()
+        };
+        write_or_exit(path, &names.to_toml());
+    }
+    match &args.output {
+        Some(path) => write_or_exit(path, &output.code),
+        None => print!("{}", output.code),
+    }
+}
+
+/// The name of the crate whose manifest is at `path`: that of its directory.
+fn crate_name(path: &Path) -> String {
+    let dir = path.parent().filter(|d| !d.as_os_str().is_empty());
+    fs::canonicalize(dir.unwrap_or(Path::new(".")))
+        .ok()
+        .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "bindings".to_string())
+}
+
+fn write_or_exit(path: &Path, contents: &str) {
+    if let Err(err) = fs::write(path, contents) {
+        eprintln!("error: {}: {}", path.display(), err);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,175 @@
+//! Conversions from JavaScript names to Rust identifiers.
+
+/// Words that cannot be used as plain Rust identifiers.
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "do", "dyn", "else",
+    "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let", "loop",
+    "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "self",
+    "Self", "static", "struct", "super", "trait", "true", "try", "type", "typeof", "union",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield", "abstract", "become",
+];
+
+/// Types and traits that generated code names without a path, from the Rust and wasm-bindgen
+/// preludes and the helpers generated beside the bindings. Declared types must neither
+/// shadow them in the bindings nor in code glob-importing the bindings.
+const RESERVED_TYPES: &[&str] = &[
+    "AsRef",
+    "Box",
+    "Closure",
+    "Default",
+    "From",
+    "Iterator",
+    "JsArray",
+    "JsCast",
+    "JsPrimitive",
+    "JsPrimitiveRecord",
+    "JsRecord",
+    "JsValue",
+    "Option",
+    "Result",
+    "Sized",
+    "String",
+    "TryFrom",
+    "Vec",
+];
+
+pub fn is_rust_keyword(name: &str) -> bool {
+    RUST_KEYWORDS.contains(&name)
+}
+
+/// Whether `name` can be written as-is as a Rust identifier.
+pub fn is_rust_ident(name: &str) -> bool {
+    let mut chars = name.chars();
+    let first_ok = match chars.next() {
+        Some(c) => c == '_' || c.is_ascii_alphabetic(),
+        None => false,
+    };
+    first_ok
+        && name != "_"
+        && chars.all(|c| c == '_' || c.is_ascii_alphanumeric())
+        && !is_rust_keyword(name)
+}
+
+/// Splits a JavaScript name into lowercase words, e.g. `getHTMLElement2` into
+/// `["get", "html", "element2"]`. Characters that can't appear in Rust identifiers act as
+/// word separators, unless there is nothing else: `$` and `$$` are spelled out as
+/// `["dollar"]` and `["dollar", "dollar"]`.
+fn words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if c.is_ascii_uppercase() && !current.is_empty() {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_ascii_lowercase());
+            // Start a new word at `aB`, and at the last capital of an acronym: `HTMLElement`.
+            if prev.is_ascii_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_ascii_uppercase() && next_lower)
+            {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+        current.push(c.to_ascii_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    if words.is_empty() {
+        return name.chars().map(spelled_out).collect();
+    }
+    words
+}
+
+/// A word for a character that can't appear in Rust identifiers.
+fn spelled_out(c: char) -> String {
+    match c {
+        '$' => "dollar".to_string(),
+        '_' => "underscore".to_string(),
+        c => format!("u{:04x}", c as u32),
+    }
+}
+
+/// Makes `name` usable as an identifier: keywords get a trailing underscore, names that
+/// start with a digit get a leading one and the empty name is `empty`.
+pub fn escape_ident(name: String) -> String {
+    if name.is_empty() {
+        "empty".to_string()
+    } else if is_rust_keyword(&name) {
+        name + "_"
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name
+    }
+}
+
+/// `fooBar` -> `foo_bar`, for functions, methods and parameters.
+pub fn snake_case(name: &str) -> String {
+    escape_ident(words(name).join("_"))
+}
+
+/// `fooBar` -> `FOO_BAR`, for statics.
+pub fn upper_snake_case(name: &str) -> String {
+    escape_ident(words(name).join("_").to_ascii_uppercase())
+}
+
+/// `foo-bar` -> `FooBar`. Names that already are valid identifiers keep their casing, so that
+/// `HTMLElement` stays recognizable. Names of types that generated code refers to get a `Js`
+/// prefix: `Box` is `JsBox`.
+pub fn type_name(name: &str) -> String {
+    let name = if is_rust_ident(name) && name.starts_with(|c: char| c.is_ascii_uppercase()) {
+        name.to_string()
+    } else {
+        pascal_case(name)
+    };
+    if RESERVED_TYPES.contains(&name.as_str()) {
+        return format!("Js{}", name);
+    }
+    name
+}
+
+/// `foo_bar` -> `FooBar`.
+pub fn pascal_case(name: &str) -> String {
+    let mut out = String::new();
+    for word in words(name) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            out.push(first.to_ascii_uppercase());
+            out.extend(chars);
+        }
+    }
+    escape_ident(out)
+}
+
+/// `high-contrast` -> `HighContrast`, for enum variants named after JavaScript names or
+/// string values. Values without letters or digits get placeholder names.
+pub fn variant_name(name: &str) -> String {
+    if name.is_empty() {
+        return "Empty".to_string();
+    }
+    if !name.chars().any(|c| c.is_ascii_alphanumeric()) {
+        return "Value".to_string();
+    }
+    let name = pascal_case(name);
+    match name.strip_prefix('_') {
+        Some(digits) => format!("V{}", digits),
+        None => name,
+    }
+}
+
+/// Renders a JavaScript name for a `js_name = ...` attribute, quoting it when it is not a
+/// plain identifier.
+pub fn js_name_attr(name: &str) -> String {
+    if is_rust_ident(name) {
+        name.to_string()
+    } else {
+        format!("{:?}", name)
+    }
+}
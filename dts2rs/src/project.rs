@@ -0,0 +1,459 @@
+//! Loading of a declaration file together with the files it imports.
+//!
+//! Starting from an entry file, module specifiers in `import` and `export ... from`
+//! statements, module augmentations and `import("...")` types, and `/// <reference path="..." />` and
+//! `/// <reference types="..." />` directives are followed to the declaration files they
+//! name; see [`resolve`](crate::resolve). The type packages that the
+//! [compiler options](crate::tsconfig::CompilerOptions) include are loaded as if the entry
+//! file referenced them. Every file is parsed once, however many times it is imported.
+//!
+//! Files with top-level imports or exports are ES modules: their declarations are bound in a
+//! Rust module of their own, named after the file's path relative to the other files, such
+//! as `lib::events` for `lib/events.d.ts`, or after the package it belongs to, such as
+//! `react::jsx_runtime` for `react/jsx-runtime`. The entry file is bound in the root module.
+//! Files without imports or exports are scripts, whose declarations are global and also go
+//! into the root module.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use dts_parser::ast;
+use dts_parser::lexer::{Lexer, TokenKind};
+use dts_parser::Span;
+
+use crate::diagnostics::{Diagnostics, SourceFile};
+use crate::error::Error;
+use crate::libs::Libs;
+use crate::names;
+use crate::resolve;
+use crate::tsconfig::CompilerOptions;
+
+/// The files making up a set of bindings. The entry file comes first.
+#[derive(Debug)]
+pub struct Project {
+    pub files: Vec<ProjectFile>,
+    /// The built-in libraries whose globals the files may use.
+    pub libs: Libs,
+}
+
+#[derive(Debug)]
+pub struct ProjectFile {
+    pub source: SourceFile,
+    pub module: ast::Module,
+    /// Whether the file is an ES module rather than a script.
+    pub is_module: bool,
+    /// The path of the Rust module the file is bound in, from the root module.
+    pub rust: Vec<String>,
+    /// The name qualifying the file's declarations in signature keys and union keys, such as
+    /// `lib/events`. `None` for the entry file and for scripts.
+    pub name: Option<String>,
+    /// The ES module the file's declarations are imported from, for files of packages other
+    /// than the entry file's. Those of the entry file's package are imported from
+    /// [`Config::module`](crate::Config::module).
+    pub js_module: Option<String>,
+    /// The files that module specifiers and reference paths in the file resolve to.
+    pub resolved: HashMap<String, usize>,
+}
+
+/// A module specifier or reference directive to follow.
+struct Request {
+    specifier: String,
+    span: Span,
+    kind: RequestKind,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum RequestKind {
+    /// A module specifier.
+    Module,
+    /// `/// <reference path="..." />`.
+    Path,
+    /// `/// <reference types="..." />`.
+    Types,
+    /// A type package included by the compiler options.
+    Listed,
+}
+
+/// Where a file was found.
+struct Origin {
+    path: PathBuf,
+    /// The package the file belongs to, if it is inside `node_modules`, and its directory.
+    package: Option<(String, PathBuf)>,
+    /// The package name or subpath the file was imported by, if any.
+    specifier: Option<String>,
+}
+
+impl Project {
+    /// Loads the declaration file at `path`, the type packages the compiler options include
+    /// and every file they import, directly or not. Imports that can't be followed are
+    /// reported as warnings.
+    pub fn load(
+        path: &Path,
+        options: &CompilerOptions,
+        diags: &mut Diagnostics,
+    ) -> Result<Project, Error> {
+        let mut files: Vec<ProjectFile> = Vec::new();
+        let mut origins: Vec<Origin> = Vec::new();
+        let mut index: HashMap<PathBuf, usize> = HashMap::new();
+        let entry = fs::canonicalize(path).map_err(|e| Error::Io(path.to_path_buf(), e))?;
+        files.push(read_file(path, &path.display().to_string())?);
+        index.insert(entry.clone(), 0);
+        origins.push(Origin {
+            package: resolve::package_of(&entry),
+            path: entry.clone(),
+            specifier: None,
+        });
+        let mut listed: Vec<Request> =
+            resolve::automatic_types(options, entry.parent().unwrap_or(&entry))
+                .into_iter()
+                .map(|name| Request {
+                    specifier: name,
+                    span: Span::new(0, 0),
+                    kind: RequestKind::Listed,
+                })
+                .collect();
+        let mut queue = VecDeque::from([0]);
+        let mut unresolved = Vec::new();
+        while let Some(i) = queue.pop_front() {
+            let dir = origins[i]
+                .path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default();
+            let mut requests = requests(&files[i]);
+            if i == 0 {
+                requests.append(&mut listed);
+            }
+            for request in requests {
+                let bare =
+                    request.kind != RequestKind::Path && !resolve::is_relative(&request.specifier);
+                let found = match request.kind {
+                    RequestKind::Path => resolve::resolve_reference(&dir, &request.specifier),
+                    RequestKind::Types | RequestKind::Listed => options
+                        .type_roots
+                        .as_ref()
+                        .and_then(|roots| resolve::resolve_in_type_roots(roots, &request.specifier))
+                        .or_else(|| resolve::resolve_types_reference(&dir, &request.specifier)),
+                    RequestKind::Module if bare => {
+                        resolve::resolve_mapped(options, &request.specifier)
+                            .or_else(|| resolve::resolve_package(&dir, &request.specifier))
+                    }
+                    RequestKind::Module => resolve::resolve_relative(&dir, &request.specifier),
+                };
+                let Some(found) = found.and_then(|p| fs::canonicalize(p).ok()) else {
+                    // A package may be declared by `declare module "pkg"` instead, which is
+                    // only known once all files are loaded.
+                    if request.kind == RequestKind::Module && bare {
+                        unresolved.push((i, request));
+                    } else if request.kind == RequestKind::Listed {
+                        diags.warn_global(format!(
+                            "cannot find the type package `{}` that the compiler options \
+                             include",
+                            request.specifier
+                        ));
+                    } else {
+                        diags.warn(
+                            &files[i].source,
+                            request.span,
+                            format!(
+                                "cannot find a declaration file for `{}`; its types are bound \
+                                 as `JsValue`",
+                                request.specifier
+                            ),
+                        );
+                    }
+                    continue;
+                };
+                let j = match index.get(&found) {
+                    Some(&j) => j,
+                    None => {
+                        let name = display_path(path, &found);
+                        files.push(read_file(&found, &name)?);
+                        index.insert(found.clone(), files.len() - 1);
+                        origins.push(Origin {
+                            package: resolve::package_of(&found),
+                            path: found,
+                            specifier: bare.then(|| request.specifier.clone()),
+                        });
+                        queue.push_back(files.len() - 1);
+                        files.len() - 1
+                    }
+                };
+                files[i].resolved.insert(request.specifier, j);
+            }
+        }
+        let mut project = Project {
+            files,
+            libs: options.libs.clone(),
+        };
+        project.add_referenced_libs();
+        project.name_modules(&origins);
+        project.report_unresolved(unresolved, diags);
+        Ok(project)
+    }
+
+    /// Loads the declarations of package `name`, installed in a `node_modules` directory of
+    /// `dir` or one of its ancestors, and every file they import.
+    pub fn load_package(
+        name: &str,
+        dir: &Path,
+        options: &CompilerOptions,
+        diags: &mut Diagnostics,
+    ) -> Result<Project, Error> {
+        match resolve::resolve_package(dir, name) {
+            Some(path) => Project::load(&path, options, diags),
+            None => Err(Error::Package(name.to_string(), dir.to_path_buf())),
+        }
+    }
+
+    /// A project of a single file given as source text. Nothing it imports is loaded.
+    pub fn from_source(
+        file_name: &str,
+        src: &str,
+        diags: &mut Diagnostics,
+    ) -> Result<Project, Error> {
+        let file = parse_file(file_name, src)?;
+        let unresolved = requests(&file)
+            .into_iter()
+            .filter(|r| r.kind == RequestKind::Module)
+            .map(|r| (0, r))
+            .collect();
+        let mut project = Project {
+            files: vec![file],
+            libs: Libs::default(),
+        };
+        project.add_referenced_libs();
+        project.report_unresolved(unresolved, diags);
+        Ok(project)
+    }
+
+    /// The names of the ambient modules declared with `declare module "name" { ... }`.
+    pub fn ambient_modules(&self) -> HashSet<&str> {
+        self.files
+            .iter()
+            .flat_map(|file| &file.module.items)
+            .filter_map(|item| match &item.kind {
+                ast::ItemKind::Module(decl) => Some(decl.name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Adds the libraries named by `/// <reference lib="..." />` directives.
+    fn add_referenced_libs(&mut self) {
+        let references = self.files.iter().flat_map(|file| &file.module.references);
+        for reference in references {
+            if reference.kind == ast::ReferenceKind::Lib {
+                self.libs.add(&reference.value);
+            }
+        }
+    }
+
+    /// Gives every ES module but the entry file its Rust module path and name. Files of the
+    /// entry file's package are named after their path relative to the directory containing
+    /// them all, and files of other packages after the package. Files imported by a package
+    /// name, or a name that `paths` maps, are named after it.
+    fn name_modules(&mut self, origins: &[Origin]) {
+        let entry_package = origins[0].package.as_ref().map(|(_, dir)| dir);
+        let own: Vec<&PathBuf> = origins
+            .iter()
+            .filter(|o| o.package.as_ref().map(|(_, dir)| dir) == entry_package)
+            .filter(|o| o.specifier.is_none())
+            .map(|o| &o.path)
+            .collect();
+        let base = common_dir(&own);
+        let mut taken: HashSet<Vec<String>> = HashSet::new();
+        for (i, (file, origin)) in self.files.iter_mut().zip(origins).enumerate() {
+            let package = origin
+                .package
+                .as_ref()
+                .filter(|(_, dir)| Some(dir) != entry_package);
+            let mut parts: Vec<String> = match (package, &origin.specifier) {
+                (_, Some(specifier)) => specifier.split('/').map(str::to_string).collect(),
+                (None, None) => path_parts(origin.path.strip_prefix(&base).unwrap_or(&origin.path)),
+                (Some((name, dir)), None) => {
+                    let mut parts: Vec<String> = name.split('/').map(str::to_string).collect();
+                    parts.extend(path_parts(
+                        origin.path.strip_prefix(dir).unwrap_or(&origin.path),
+                    ));
+                    parts
+                }
+            };
+            match (package, &origin.specifier) {
+                // Files of other packages are imported from the package subpath that led to
+                // them, or else from the package itself.
+                (Some((name, _)), specifier) => {
+                    file.js_module = Some(specifier.clone().unwrap_or_else(|| name.clone()));
+                }
+                // Files that `paths` or `baseUrl` map a module name to are imported by that
+                // name.
+                (None, Some(specifier)) => file.js_module = Some(specifier.clone()),
+                (None, None) => {}
+            }
+            if i == 0 || !file.is_module {
+                continue;
+            }
+            // `events/index.d.ts` is imported as `./events`.
+            if parts.len() > 1 && parts.last().is_some_and(|p| p == "index") {
+                parts.pop();
+            }
+            let mut rust: Vec<String> = parts.iter().map(|p| names::snake_case(p)).collect();
+            while !taken.insert(rust.clone()) {
+                if let Some(last) = rust.last_mut() {
+                    last.push('_');
+                }
+            }
+            file.name = Some(parts.join("/"));
+            file.rust = rust;
+        }
+    }
+
+    /// Warns about package names that don't resolve and don't name an ambient module.
+    fn report_unresolved(&self, unresolved: Vec<(usize, Request)>, diags: &mut Diagnostics) {
+        let ambient = self.ambient_modules();
+        let mut reported = HashSet::new();
+        for (i, request) in unresolved {
+            if ambient.contains(request.specifier.as_str())
+                || !reported.insert(request.specifier.clone())
+            {
+                continue;
+            }
+            diags.warn(
+                &self.files[i].source,
+                request.span,
+                format!(
+                    "cannot resolve module `{}`; its types are bound as `JsValue`",
+                    request.specifier
+                ),
+            );
+        }
+    }
+}
+
+fn read_file(path: &Path, name: &str) -> Result<ProjectFile, Error> {
+    let src = fs::read_to_string(path).map_err(|e| Error::Io(path.to_path_buf(), e))?;
+    parse_file(name, &src)
+}
+
+fn parse_file(name: &str, src: &str) -> Result<ProjectFile, Error> {
+    let module = dts_parser::parse(src).map_err(|e| Error::parse(name, src, e))?;
+    Ok(ProjectFile {
+        source: SourceFile::new(name, src),
+        is_module: module.is_es_module(),
+        module,
+        rust: Vec::new(),
+        name: None,
+        js_module: None,
+        resolved: HashMap::new(),
+    })
+}
+
+/// The module specifiers and reference directives in a file. References to built-in
+/// libraries are left alone.
+fn requests(file: &ProjectFile) -> Vec<Request> {
+    let mut out: Vec<Request> = file
+        .module
+        .references
+        .iter()
+        .filter_map(|r| {
+            let kind = match r.kind {
+                ast::ReferenceKind::Path => RequestKind::Path,
+                ast::ReferenceKind::Types => RequestKind::Types,
+                ast::ReferenceKind::Lib => return None,
+            };
+            Some(Request {
+                specifier: r.value.clone(),
+                span: r.span,
+                kind,
+            })
+        })
+        .collect();
+    let mut push = |specifier: &str, span: Span| {
+        if !out
+            .iter()
+            .any(|r| r.kind == RequestKind::Module && r.specifier == specifier)
+        {
+            out.push(Request {
+                specifier: specifier.to_string(),
+                span,
+                kind: RequestKind::Module,
+            });
+        }
+    };
+    for item in &file.module.items {
+        match &item.kind {
+            ast::ItemKind::Import(decl) => push(&decl.module, decl.module_span),
+            ast::ItemKind::Export(ast::ExportDecl::All {
+                module,
+                module_span,
+                ..
+            }) => push(module, *module_span),
+            ast::ItemKind::Export(ast::ExportDecl::Named {
+                module: Some((module, span)),
+                ..
+            }) => push(module, *span),
+            // In an ES module, `declare module "m" { ... }` augments module `m`.
+            ast::ItemKind::Module(decl) if file.is_module => push(&decl.name, decl.name_span),
+            _ => {}
+        }
+    }
+    // `import("./x").Foo` types can appear anywhere, so they are found in the tokens rather
+    // than by walking every type in the syntax tree. The file already lexed once, so this
+    // can't fail.
+    let tokens = Lexer::new(&file.source.src).tokenize().unwrap_or_default();
+    for window in tokens.windows(3) {
+        if let [import, paren, token] = window {
+            if let TokenKind::Str(specifier) = &token.kind {
+                if import.is_ident("import") && paren.is_punct("(") {
+                    push(specifier, token.span);
+                }
+            }
+        }
+    }
+    out
+}
+
+/// The components of a relative path, without the declaration file extension.
+fn path_parts(path: &Path) -> Vec<String> {
+    let mut parts: Vec<String> = path
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect();
+    if let Some(last) = parts.last_mut() {
+        *last = resolve::strip_extension(last).to_string();
+    }
+    parts
+}
+
+/// The deepest directory containing all of `paths`.
+fn common_dir(paths: &[&PathBuf]) -> PathBuf {
+    let mut dir = paths[0].parent().map(Path::to_path_buf).unwrap_or_default();
+    for path in &paths[1..] {
+        while !path.starts_with(&dir) {
+            if !dir.pop() {
+                break;
+            }
+        }
+    }
+    dir
+}
+
+/// How to name `path` in messages: relative to the directory of the entry file, as given.
+fn display_path(entry: &Path, path: &Path) -> String {
+    let dir = entry.parent().and_then(|d| {
+        fs::canonicalize(if d.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            d
+        })
+        .ok()
+    });
+    match dir.and_then(|d| path.strip_prefix(&d).ok().map(Path::to_path_buf)) {
+        Some(relative) => entry.with_file_name(relative).display().to_string(),
+        None => path.display().to_string(),
+    }
+}
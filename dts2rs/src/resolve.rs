@@ -0,0 +1,587 @@
+//! Finding the declaration file a module specifier refers to, the way `tsc` does.
+//!
+//! Relative specifiers are looked up next to the importing file. Package names are looked up
+//! in the `node_modules` directories of the importing file's directory and its ancestors:
+//! first the package itself, then its `@types` package. Inside a package, the declaration
+//! entry comes from the `exports` conditions with `types`, or else from `typesVersions`
+//! redirects and the `types`, `typings` or `main` fields. Nothing is downloaded; packages must
+//! already be installed. The `paths`, `baseUrl` and `typeRoots` compiler options are tried
+//! before `node_modules`.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::tsconfig::CompilerOptions;
+
+/// The TypeScript version `typesVersions` ranges are matched against.
+const TS_VERSION: (u64, u64, u64) = (5, 6, 0);
+
+/// The `exports` conditions that apply, in order of preference.
+const CONDITIONS: &[&str] = &["types", "import", "require", "default"];
+
+pub(crate) fn is_relative(specifier: &str) -> bool {
+    specifier == "."
+        || specifier == ".."
+        || specifier.starts_with("./")
+        || specifier.starts_with("../")
+        || specifier.starts_with('/')
+}
+
+/// The declaration file for a relative module specifier: `./x` may be `x.d.ts` or
+/// `x/index.d.ts`, and `./x.js` is declared by `x.d.ts`.
+pub(crate) fn resolve_relative(dir: &Path, specifier: &str) -> Option<PathBuf> {
+    let base = dir.join(specifier);
+    let base = base.to_string_lossy();
+    let mut candidates = Vec::new();
+    for (js, dts) in [
+        (".js", ".d.ts"),
+        (".jsx", ".d.ts"),
+        (".mjs", ".d.mts"),
+        (".cjs", ".d.cts"),
+    ] {
+        if let Some(stem) = base.strip_suffix(js) {
+            candidates.push(format!("{}{}", stem, dts));
+        }
+    }
+    if strip_extension(&base).len() < base.len() {
+        candidates.push(base.to_string());
+    }
+    candidates.push(format!("{}.d.ts", base));
+    candidates.push(format!("{}/index.d.ts", base.trim_end_matches('/')));
+    candidates
+        .into_iter()
+        .map(PathBuf::from)
+        .find(|path| path.is_file())
+}
+
+/// The file a `/// <reference path="..." />` names. The path is relative to the file even
+/// without a leading `./`.
+pub(crate) fn resolve_reference(dir: &Path, path: &str) -> Option<PathBuf> {
+    let path = dir.join(path);
+    if path.is_file() {
+        return Some(path);
+    }
+    let with_extension = PathBuf::from(format!("{}.d.ts", path.display()));
+    with_extension.is_file().then_some(with_extension)
+}
+
+/// The declaration file for a package name, optionally followed by a subpath such as
+/// `pkg/sub`, imported from a file in `dir`.
+pub(crate) fn resolve_package(dir: &Path, specifier: &str) -> Option<PathBuf> {
+    let (name, subpath) = split_package_name(specifier)?;
+    node_modules(dir).find_map(|modules| {
+        resolve_in_package(&modules.join(name), subpath)
+            .or_else(|| resolve_in_package(&modules.join("@types").join(types_name(name)), subpath))
+    })
+}
+
+/// The declaration file for `/// <reference types="name" />`, which prefers `@types`.
+pub(crate) fn resolve_types_reference(dir: &Path, name: &str) -> Option<PathBuf> {
+    let (package, subpath) = split_package_name(name)?;
+    node_modules(dir).find_map(|modules| {
+        resolve_in_package(&modules.join("@types").join(types_name(package)), subpath)
+            .or_else(|| resolve_in_package(&modules.join(package), subpath))
+    })
+}
+
+/// The declaration file for a non-relative module name that the `paths` or `baseUrl`
+/// compiler options map to a file of the project.
+pub(crate) fn resolve_mapped(options: &CompilerOptions, specifier: &str) -> Option<PathBuf> {
+    options
+        .mapped_paths(specifier)
+        .into_iter()
+        .chain(options.base_url.iter().map(|base| base.join(specifier)))
+        .find_map(|path| resolve_path(&path))
+}
+
+/// The declaration file for a type package `name` in one of the `typeRoots` directories.
+pub(crate) fn resolve_in_type_roots(roots: &[PathBuf], name: &str) -> Option<PathBuf> {
+    let (package, subpath) = split_package_name(name)?;
+    roots.iter().find_map(|root| {
+        resolve_in_package(&root.join(package), subpath)
+            .or_else(|| resolve_in_package(&root.join(types_name(package)), subpath))
+    })
+}
+
+/// The type packages included without being imported, for a project whose entry file is in
+/// `dir`: those the `types` compiler option lists, or else every package in the type roots.
+pub(crate) fn automatic_types(options: &CompilerOptions, dir: &Path) -> Vec<String> {
+    if let Some(types) = &options.types {
+        return types.clone();
+    }
+    let roots: Vec<PathBuf> = match &options.type_roots {
+        Some(roots) => roots.clone(),
+        None => node_modules(dir).map(|d| d.join("@types")).collect(),
+    };
+    let mut out: Vec<String> = Vec::new();
+    for root in roots {
+        for name in subdirectories(&root) {
+            if name.starts_with('@') {
+                for scoped in subdirectories(&root.join(&name)) {
+                    out.push(format!("{}/{}", name, scoped));
+                }
+            } else {
+                out.push(name);
+            }
+        }
+    }
+    let mut seen = HashSet::new();
+    out.retain(|name| seen.insert(name.clone()));
+    out
+}
+
+/// The name JavaScript imports the package containing `path` by, and the package's
+/// directory, if `path` is inside `node_modules`. Declarations from `@types/scope__name` are
+/// imported from `@scope/name`.
+pub(crate) fn package_of(path: &Path) -> Option<(String, PathBuf)> {
+    let components: Vec<Component> = path.components().collect();
+    let i = components
+        .iter()
+        .rposition(|c| c.as_os_str() == "node_modules")?;
+    let first = components
+        .get(i + 1)?
+        .as_os_str()
+        .to_string_lossy()
+        .into_owned();
+    let (name, len) = if first.starts_with('@') {
+        let second = components.get(i + 2)?.as_os_str().to_string_lossy();
+        if first == "@types" {
+            (untypes_name(&second), 3)
+        } else {
+            (format!("{}/{}", first, second), 3)
+        }
+    } else {
+        (first, 2)
+    };
+    let root: PathBuf = components[..i + len].iter().collect();
+    Some((name, root))
+}
+
+/// `x.d.ts` -> `x`. Names without a declaration file extension are returned as they are.
+pub(crate) fn strip_extension(name: &str) -> &str {
+    [".d.ts", ".d.mts", ".d.cts", ".ts", ".mts", ".cts"]
+        .iter()
+        .find_map(|ext| name.strip_suffix(ext))
+        .unwrap_or(name)
+}
+
+/// The `node_modules` directories visible from `dir`, nearest first.
+pub(crate) fn node_modules(dir: &Path) -> impl Iterator<Item = PathBuf> + '_ {
+    dir.ancestors()
+        .filter(|d| d.file_name().is_none_or(|name| name != "node_modules"))
+        .map(|d| d.join("node_modules"))
+        .filter(|d| d.is_dir())
+}
+
+/// The declaration file at `path`, which may leave out the extension, or the declarations
+/// of the directory or package at `path`.
+fn resolve_path(path: &Path) -> Option<PathBuf> {
+    resolve_relative(Path::new(""), &path.to_string_lossy())
+        .or_else(|| resolve_in_package(path, ""))
+}
+
+/// The names of the directories in `dir`, sorted, leaving out hidden ones.
+fn subdirectories(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| !name.starts_with('.'))
+        .collect();
+    names.sort();
+    names
+}
+
+/// `@scope/name/sub` -> (`@scope/name`, `sub`).
+fn split_package_name(specifier: &str) -> Option<(&str, &str)> {
+    let mut slashes = specifier.match_indices('/').map(|(i, _)| i);
+    let end = if specifier.starts_with('@') {
+        slashes.nth(1)
+    } else {
+        slashes.next()
+    };
+    let (name, subpath) = match end {
+        Some(end) => (&specifier[..end], &specifier[end + 1..]),
+        None => (specifier, ""),
+    };
+    (!name.is_empty() && !name.ends_with('/')).then_some((name, subpath))
+}
+
+/// `@scope/name` -> `scope__name`, the name of its `@types` package.
+fn types_name(name: &str) -> String {
+    match name.strip_prefix('@') {
+        Some(scoped) => scoped.replacen('/', "__", 1),
+        None => name.to_string(),
+    }
+}
+
+/// `scope__name` -> `@scope/name`.
+fn untypes_name(name: &str) -> String {
+    match name.split_once("__") {
+        Some((scope, name)) => format!("@{}/{}", scope, name),
+        None => name.to_string(),
+    }
+}
+
+/// The declaration file for `subpath` of the package in `dir`, or for its entry point if
+/// `subpath` is empty.
+fn resolve_in_package(dir: &Path, subpath: &str) -> Option<PathBuf> {
+    if !dir.is_dir() {
+        return None;
+    }
+    let manifest: Value = fs::read_to_string(dir.join("package.json"))
+        .ok()
+        .and_then(|src| serde_json::from_str(&src).ok())
+        .unwrap_or(Value::Null);
+    // `exports` hides everything it doesn't list.
+    if let Some(exports) = manifest.get("exports") {
+        let key = if subpath.is_empty() {
+            ".".to_string()
+        } else {
+            format!("./{}", subpath)
+        };
+        return exports_target(exports, &key).and_then(|target| resolve_relative(dir, &target));
+    }
+    let path = if subpath.is_empty() {
+        ["types", "typings"]
+            .iter()
+            .find_map(|field| manifest.get(*field).and_then(Value::as_str))
+            .map(str::to_string)
+            .or_else(|| {
+                let main = manifest.get("main").and_then(Value::as_str)?;
+                Some(main.to_string())
+            })
+            .unwrap_or_else(|| "index.d.ts".to_string())
+    } else {
+        subpath.to_string()
+    };
+    let path = path.trim_start_matches("./");
+    if let Some(redirects) = manifest.get("typesVersions").and_then(types_versions) {
+        if let Some(found) = apply_paths(redirects, path, dir) {
+            return Some(found);
+        }
+    }
+    resolve_relative(dir, &format!("./{}", path))
+}
+
+/// The target of `key`, such as `.` or `./sub`, in an `exports` field.
+fn exports_target(exports: &Value, key: &str) -> Option<String> {
+    let subpaths = match exports {
+        Value::Object(map) if map.keys().all(|k| k.starts_with('.')) => map,
+        // A target or conditions for the entry point alone.
+        _ => {
+            return (key == ".")
+                .then(|| condition_target(exports, ""))
+                .flatten()
+        }
+    };
+    if let Some(target) = subpaths.get(key) {
+        return condition_target(target, "");
+    }
+    // The pattern with the longest prefix before its `*` wins.
+    subpaths
+        .iter()
+        .filter_map(|(pattern, target)| {
+            let (prefix, suffix) = pattern.split_once('*')?;
+            let star = key.strip_prefix(prefix)?.strip_suffix(suffix)?;
+            Some((prefix.len(), star, target))
+        })
+        .max_by_key(|(len, _, _)| *len)
+        .and_then(|(_, star, target)| condition_target(target, star))
+}
+
+/// Picks the target of the first applicable condition, substituting `star` for `*`.
+fn condition_target(target: &Value, star: &str) -> Option<String> {
+    match target {
+        Value::String(path) => Some(path.replace('*', star)),
+        Value::Array(fallbacks) => fallbacks.iter().find_map(|t| condition_target(t, star)),
+        Value::Object(conditions) => conditions
+            .iter()
+            .filter(|(condition, _)| CONDITIONS.contains(&condition.as_str()))
+            .find_map(|(_, t)| condition_target(t, star)),
+        _ => None,
+    }
+}
+
+/// The path mappings of the first `typesVersions` entry whose range includes
+/// [`TS_VERSION`].
+fn types_versions(field: &Value) -> Option<&serde_json::Map<String, Value>> {
+    field
+        .as_object()?
+        .iter()
+        .find(|(range, _)| version_matches(range))
+        .and_then(|(_, paths)| paths.as_object())
+}
+
+/// Maps `path` through `typesVersions` path mappings, such as `{ "*": ["ts4.0/*"] }`.
+fn apply_paths(paths: &serde_json::Map<String, Value>, path: &str, dir: &Path) -> Option<PathBuf> {
+    let (star, targets) =
+        paths
+            .iter()
+            .find_map(|(pattern, targets)| match pattern.split_once('*') {
+                Some((prefix, suffix)) => {
+                    let star = path.strip_prefix(prefix)?.strip_suffix(suffix)?;
+                    Some((star, targets))
+                }
+                None => (pattern == path).then_some(("", targets)),
+            })?;
+    targets
+        .as_array()?
+        .iter()
+        .filter_map(Value::as_str)
+        .find_map(|target| {
+            let target = target.replace('*', star);
+            resolve_relative(dir, &format!("./{}", target.trim_start_matches("./")))
+        })
+}
+
+/// Whether a range such as `>=4.1`, `>=3.1 <4` or `*` includes [`TS_VERSION`].
+fn version_matches(range: &str) -> bool {
+    range.split_whitespace().all(|comparator| {
+        if comparator == "*" {
+            return true;
+        }
+        let split = comparator
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(comparator.len());
+        let (op, version) = comparator.split_at(split);
+        let mut parts = version.split('.').map(|p| p.parse::<u64>().unwrap_or(0));
+        let version = (
+            parts.next().unwrap_or(0),
+            parts.next().unwrap_or(0),
+            parts.next().unwrap_or(0),
+        );
+        match op {
+            ">=" => TS_VERSION >= version,
+            ">" => TS_VERSION > version,
+            "<=" => TS_VERSION <= version,
+            "<" => TS_VERSION < version,
+            "" | "=" => TS_VERSION == version,
+            _ => false,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh directory holding `files`, removed when dropped.
+    struct Tree(PathBuf);
+
+    impl Tree {
+        fn new(name: &str, files: &[(&str, &str)]) -> Tree {
+            let root = std::env::temp_dir().join(format!(
+                "dts2rs-resolve-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&root);
+            for (path, src) in files {
+                let path = root.join(path);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, src).unwrap();
+            }
+            Tree(root)
+        }
+
+        fn path(&self, path: &str) -> PathBuf {
+            self.0.join(path)
+        }
+    }
+
+    impl Drop for Tree {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn relative_specifiers() {
+        let tree = Tree::new(
+            "relative",
+            &[("a.d.ts", ""), ("b/index.d.ts", ""), ("c.d.mts", "")],
+        );
+        let dir = tree.path("");
+        assert_eq!(resolve_relative(&dir, "./a"), Some(tree.path("a.d.ts")));
+        assert_eq!(resolve_relative(&dir, "./a.js"), Some(tree.path("a.d.ts")));
+        assert_eq!(
+            resolve_relative(&dir, "./b"),
+            Some(tree.path("b/index.d.ts"))
+        );
+        assert_eq!(
+            resolve_relative(&dir, "./c.mjs"),
+            Some(tree.path("c.d.mts"))
+        );
+        assert_eq!(resolve_relative(&dir, "./d"), None);
+    }
+
+    #[test]
+    fn package_entry_fields() {
+        let tree = Tree::new(
+            "fields",
+            &[
+                (
+                    "node_modules/t/package.json",
+                    r#"{ "types": "lib/t.d.ts", "typings": "x.d.ts" }"#,
+                ),
+                ("node_modules/t/lib/t.d.ts", ""),
+                ("node_modules/u/package.json", r#"{ "typings": "./u" }"#),
+                ("node_modules/u/u.d.ts", ""),
+                ("node_modules/m/package.json", r#"{ "main": "dist/m.js" }"#),
+                ("node_modules/m/dist/m.d.ts", ""),
+                ("node_modules/i/index.d.ts", ""),
+                ("node_modules/@types/j/index.d.ts", ""),
+                ("node_modules/@types/s__k/index.d.ts", ""),
+                ("src/main.d.ts", ""),
+            ],
+        );
+        let src = tree.path("src");
+        let found = |name: &str| resolve_package(&src, name);
+        assert_eq!(found("t"), Some(tree.path("node_modules/t/lib/t.d.ts")));
+        assert_eq!(found("u"), Some(tree.path("node_modules/u/u.d.ts")));
+        assert_eq!(found("m"), Some(tree.path("node_modules/m/dist/m.d.ts")));
+        assert_eq!(found("i"), Some(tree.path("node_modules/i/index.d.ts")));
+        assert_eq!(
+            found("j"),
+            Some(tree.path("node_modules/@types/j/index.d.ts"))
+        );
+        assert_eq!(
+            found("@s/k"),
+            Some(tree.path("node_modules/@types/s__k/index.d.ts"))
+        );
+        assert_eq!(found("missing"), None);
+    }
+
+    #[test]
+    fn package_exports() {
+        let tree = Tree::new(
+            "exports",
+            &[
+                (
+                    "node_modules/e/package.json",
+                    r#"{
+                        "types": "ignored.d.ts",
+                        "exports": {
+                            ".": { "node": "./node.js", "types": "./types/index.d.ts" },
+                            "./sub": ["./types/sub.d.ts"],
+                            "./features/*": { "default": "./types/features/*.d.ts" },
+                            "./features/internal/*": null
+                        }
+                    }"#,
+                ),
+                ("node_modules/e/ignored.d.ts", ""),
+                ("node_modules/e/types/index.d.ts", ""),
+                ("node_modules/e/types/sub.d.ts", ""),
+                ("node_modules/e/types/hidden.d.ts", ""),
+                ("node_modules/e/types/features/a.d.ts", ""),
+                (
+                    "node_modules/f/package.json",
+                    r#"{ "exports": { "types": "./f.d.ts" } }"#,
+                ),
+                ("node_modules/f/f.d.ts", ""),
+            ],
+        );
+        let dir = tree.path("");
+        let found = |name: &str| resolve_package(&dir, name);
+        assert_eq!(
+            found("e"),
+            Some(tree.path("node_modules/e/types/index.d.ts"))
+        );
+        assert_eq!(
+            found("e/sub"),
+            Some(tree.path("node_modules/e/types/sub.d.ts"))
+        );
+        assert_eq!(
+            found("e/features/a"),
+            Some(tree.path("node_modules/e/types/features/a.d.ts"))
+        );
+        // The longer pattern wins, and it has no target.
+        assert_eq!(found("e/features/internal/a"), None);
+        // `exports` hides what it doesn't list.
+        assert_eq!(found("e/types/hidden"), None);
+        assert_eq!(found("f"), Some(tree.path("node_modules/f/f.d.ts")));
+        assert_eq!(found("f/f"), None);
+    }
+
+    #[test]
+    fn types_versions_redirects() {
+        let tree = Tree::new(
+            "versions",
+            &[
+                (
+                    "node_modules/v/package.json",
+                    r#"{
+                        "types": "index.d.ts",
+                        "typesVersions": {
+                            "<4.0": { "*": ["ts3/*"] },
+                            ">=4.1": { "*": ["ts4/*"] }
+                        }
+                    }"#,
+                ),
+                ("node_modules/v/index.d.ts", ""),
+                ("node_modules/v/ts3/index.d.ts", ""),
+                ("node_modules/v/ts4/index.d.ts", ""),
+            ],
+        );
+        assert_eq!(
+            resolve_package(&tree.path(""), "v"),
+            Some(tree.path("node_modules/v/ts4/index.d.ts"))
+        );
+    }
+
+    #[test]
+    fn types_references_prefer_types_packages() {
+        let tree = Tree::new(
+            "references",
+            &[
+                ("node_modules/p/index.d.ts", ""),
+                ("node_modules/@types/p/index.d.ts", ""),
+            ],
+        );
+        let dir = tree.path("");
+        assert_eq!(
+            resolve_types_reference(&dir, "p"),
+            Some(tree.path("node_modules/@types/p/index.d.ts"))
+        );
+        assert_eq!(
+            resolve_package(&dir, "p"),
+            Some(tree.path("node_modules/p/index.d.ts"))
+        );
+    }
+
+    #[test]
+    fn packages_of_paths() {
+        let path = Path::new("/app/node_modules/@types/scope__name/index.d.ts");
+        assert_eq!(
+            package_of(path),
+            Some((
+                "@scope/name".to_string(),
+                PathBuf::from("/app/node_modules/@types/scope__name")
+            ))
+        );
+        let path = Path::new("/app/node_modules/a/node_modules/@s/b/lib/b.d.ts");
+        assert_eq!(
+            package_of(path),
+            Some((
+                "@s/b".to_string(),
+                PathBuf::from("/app/node_modules/a/node_modules/@s/b")
+            ))
+        );
+        assert_eq!(package_of(Path::new("/app/src/index.d.ts")), None);
+    }
+
+    #[test]
+    fn version_ranges() {
+        assert!(version_matches("*"));
+        assert!(version_matches(">=4.1"));
+        assert!(version_matches(">=5 <6"));
+        assert!(!version_matches("<4"));
+        assert!(!version_matches(">=3.1 <5.6"));
+    }
+}
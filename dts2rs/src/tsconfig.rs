@@ -0,0 +1,462 @@
+//! Compiler options read from a `tsconfig.json`.
+//!
+//! Only the options that change which declarations are seen are read: `baseUrl` and `paths`
+//! for module resolution, `typeRoots` and `types` for the packages whose global declarations
+//! are included, and `lib`, `target` and `noLib` for the built-in libraries. A config file may
+//! `extends` others, each overriding the options of those it extends. Paths are relative to
+//! the config file that sets them.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+
+use crate::error::Error;
+use crate::libs::Libs;
+use crate::resolve;
+
+/// The compiler options of a project.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompilerOptions {
+    /// The directory non-relative module names are looked up in before `node_modules`.
+    pub base_url: Option<PathBuf>,
+    /// Module name patterns, such as `@app/*`, and the paths they are looked up at, in which
+    /// `*` stands for what the pattern's `*` matched.
+    pub paths: Vec<(String, Vec<PathBuf>)>,
+    /// The directories `/// <reference types="..." />` and [`types`](Self::types) are looked
+    /// up in before `node_modules`. `None` means the `node_modules/@types` directories.
+    pub type_roots: Option<Vec<PathBuf>>,
+    /// The type packages whose declarations are included without being imported. `None`
+    /// means every package in the type roots, as `tsc` does. Without a `tsconfig.json`, none
+    /// are.
+    pub types: Option<Vec<String>>,
+    /// The built-in libraries available.
+    pub libs: Libs,
+}
+
+impl Default for CompilerOptions {
+    fn default() -> CompilerOptions {
+        CompilerOptions {
+            base_url: None,
+            paths: Vec::new(),
+            type_roots: None,
+            types: Some(Vec::new()),
+            libs: Libs::default(),
+        }
+    }
+}
+
+/// The options set by one config file and those it extends, with paths made absolute.
+#[derive(Default)]
+struct Options {
+    base_url: Option<PathBuf>,
+    /// The patterns, and the directory their paths are relative to when there is no
+    /// `baseUrl`.
+    paths: Option<(Map<String, Value>, PathBuf)>,
+    type_roots: Option<Vec<PathBuf>>,
+    types: Option<Vec<String>>,
+    lib: Option<Vec<String>>,
+    target: Option<String>,
+    no_lib: Option<bool>,
+}
+
+impl CompilerOptions {
+    /// Reads the compiler options of the `tsconfig.json` at `path`.
+    pub fn from_file(path: &Path) -> Result<CompilerOptions, Error> {
+        let options = read_options(path, &mut HashSet::new())?;
+        let base_url = options.base_url;
+        let paths = match options.paths {
+            Some((patterns, dir)) => {
+                let base = base_url.clone().unwrap_or(dir);
+                patterns
+                    .into_iter()
+                    .map(|(pattern, targets)| {
+                        let targets = strings(&targets)
+                            .into_iter()
+                            .map(|target| base.join(target))
+                            .collect();
+                        (pattern, targets)
+                    })
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        let libs = if options.no_lib == Some(true) {
+            Libs::none()
+        } else if let Some(lib) = &options.lib {
+            Libs::new(lib.iter().map(String::as_str))
+        } else {
+            Libs::for_target(options.target.as_deref().unwrap_or("es5"))
+        };
+        Ok(CompilerOptions {
+            base_url,
+            paths,
+            type_roots: options.type_roots,
+            types: options.types,
+            libs,
+        })
+    }
+
+    /// The paths a non-relative module name maps to through [`paths`](Self::paths), most
+    /// likely first. An exact pattern wins over the one with the longest prefix before `*`.
+    pub(crate) fn mapped_paths(&self, specifier: &str) -> Vec<PathBuf> {
+        if let Some((_, targets)) = self.paths.iter().find(|(pattern, _)| pattern == specifier) {
+            return targets.clone();
+        }
+        self.paths
+            .iter()
+            .filter_map(|(pattern, targets)| {
+                let (prefix, suffix) = pattern.split_once('*')?;
+                let star = specifier.strip_prefix(prefix)?.strip_suffix(suffix)?;
+                Some((prefix.len(), star, targets))
+            })
+            .max_by_key(|(len, _, _)| *len)
+            .map(|(_, star, targets)| {
+                targets
+                    .iter()
+                    .map(|t| PathBuf::from(t.to_string_lossy().replace('*', star)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Reads the options of the config file at `path`, on top of those of the files it extends.
+/// `seen` holds the files being read, to catch `extends` cycles.
+fn read_options(path: &Path, seen: &mut HashSet<PathBuf>) -> Result<Options, Error> {
+    let config_error = |msg: String| Error::Config(path.to_path_buf(), msg);
+    let src = fs::read_to_string(path).map_err(|e| Error::Io(path.to_path_buf(), e))?;
+    let json: Value =
+        serde_json::from_str(&strip_jsonc(&src)).map_err(|e| config_error(e.to_string()))?;
+    let canonical = fs::canonicalize(path).map_err(|e| Error::Io(path.to_path_buf(), e))?;
+    if !seen.insert(canonical.clone()) {
+        return Err(config_error("`extends` forms a cycle".to_string()));
+    }
+    let dir = canonical.parent().unwrap_or(Path::new("")).to_path_buf();
+    // Of several extended files, later ones override earlier ones.
+    let mut options = Options::default();
+    for base in json.get("extends").map(strings).unwrap_or_default() {
+        let base_path = resolve_extends(&dir, &base)
+            .ok_or_else(|| config_error(format!("cannot find `{}`, which it extends", base)))?;
+        options = read_options(&base_path, seen)?.or(options);
+    }
+    seen.remove(&canonical);
+    let Some(compiler) = json.get("compilerOptions").and_then(Value::as_object) else {
+        return Ok(options);
+    };
+    let own = Options {
+        base_url: compiler
+            .get("baseUrl")
+            .and_then(Value::as_str)
+            .map(|p| dir.join(p)),
+        paths: compiler
+            .get("paths")
+            .and_then(Value::as_object)
+            .map(|paths| (paths.clone(), dir.clone())),
+        type_roots: compiler
+            .get("typeRoots")
+            .map(|roots| strings(roots).iter().map(|p| dir.join(p)).collect()),
+        types: compiler.get("types").map(strings),
+        lib: compiler.get("lib").map(strings),
+        target: compiler
+            .get("target")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        no_lib: compiler.get("noLib").and_then(Value::as_bool),
+    };
+    Ok(own.or(options))
+}
+
+impl Options {
+    /// These options, with those they don't set taken from `base`.
+    fn or(self, base: Options) -> Options {
+        Options {
+            base_url: self.base_url.or(base.base_url),
+            paths: self.paths.or(base.paths),
+            type_roots: self.type_roots.or(base.type_roots),
+            types: self.types.or(base.types),
+            lib: self.lib.or(base.lib),
+            target: self.target.or(base.target),
+            no_lib: self.no_lib.or(base.no_lib),
+        }
+    }
+}
+
+/// The config file an `extends` entry names: a path, or a package name such as
+/// `@tsconfig/node20/tsconfig.json` looked up in `node_modules`.
+fn resolve_extends(dir: &Path, name: &str) -> Option<PathBuf> {
+    let candidates = |base: PathBuf| {
+        [
+            base.clone(),
+            PathBuf::from(format!("{}.json", base.display())),
+            base.join("tsconfig.json"),
+        ]
+    };
+    if resolve::is_relative(name) || Path::new(name).is_absolute() {
+        return candidates(dir.join(name)).into_iter().find(|p| p.is_file());
+    }
+    resolve::node_modules(dir)
+        .flat_map(|modules| candidates(modules.join(name)))
+        .find(|p| p.is_file())
+}
+
+/// A string, or the strings of an array.
+fn strings(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(s) => vec![s.clone()],
+        Value::Array(values) => values
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Turns the JSON with comments and trailing commas that `tsconfig.json` allows into JSON.
+fn strip_jsonc(src: &str) -> String {
+    let mut out = String::with_capacity(src.len());
+    let mut chars = src.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                out.push(c);
+                while let Some(c) = chars.next() {
+                    out.push(c);
+                    match c {
+                        '\\' => out.extend(chars.next()),
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => while chars.next_if(|&c| c != '\n').is_some() {},
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+                out.push(' ');
+            }
+            // Comments before the bracket are stripped already, so a trailing comma is the
+            // last thing before it but whitespace.
+            '}' | ']' => {
+                let trimmed = out.trim_end().len();
+                if out[..trimmed].ends_with(',') {
+                    out.truncate(trimmed - 1);
+                }
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh directory holding `files`, removed when dropped.
+    struct Tree(PathBuf);
+
+    impl Tree {
+        fn new(name: &str, files: &[(&str, &str)]) -> Tree {
+            let root = std::env::temp_dir().join(format!(
+                "dts2rs-tsconfig-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = fs::remove_dir_all(&root);
+            for (path, src) in files {
+                let path = root.join(path);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, src).unwrap();
+            }
+            Tree(fs::canonicalize(root).unwrap())
+        }
+
+        fn path(&self, path: &str) -> PathBuf {
+            self.0.join(path)
+        }
+
+        fn options(&self, path: &str) -> CompilerOptions {
+            CompilerOptions::from_file(&self.path(path)).unwrap()
+        }
+    }
+
+    impl Drop for Tree {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn comments_and_trailing_commas() {
+        let src = r#"{
+            // "lib": ["dom"],
+            "a": "// not a comment", /* gone */
+            "b": ["/* kept */", "\"//\"",],
+            "c": { "d": 1, /* trailing */ },
+        }"#;
+        let json: Value = serde_json::from_str(&strip_jsonc(src)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "a": "// not a comment",
+                "b": ["/* kept */", "\"//\""],
+                "c": { "d": 1 },
+            })
+        );
+    }
+
+    #[test]
+    fn extends_chains() {
+        let tree = Tree::new(
+            "extends",
+            &[
+                (
+                    "node_modules/@tsconfig/base/tsconfig.json",
+                    r#"{ "compilerOptions": { "types": ["node"], "lib": ["es2015"] } }"#,
+                ),
+                (
+                    "configs/base.json",
+                    r#"{
+                        "extends": "@tsconfig/base/tsconfig.json",
+                        "compilerOptions": { "baseUrl": "src", "lib": ["es2020"] }
+                    }"#,
+                ),
+                (
+                    "configs/roots.json",
+                    r#"{ "compilerOptions": { "typeRoots": ["./types"] } }"#,
+                ),
+                (
+                    "tsconfig.json",
+                    r#"{
+                        "extends": ["./configs/base", "./configs/roots.json"],
+                        "compilerOptions": { "types": ["jest"], },
+                    }"#,
+                ),
+            ],
+        );
+        let options = tree.options("tsconfig.json");
+        // Paths are relative to the file that sets them.
+        assert_eq!(options.base_url, Some(tree.path("configs/src")));
+        assert_eq!(options.type_roots, Some(vec![tree.path("configs/types")]));
+        assert_eq!(options.types, Some(vec!["jest".to_string()]));
+        assert_eq!(options.libs, Libs::new(["es2020"]));
+    }
+
+    #[test]
+    fn extends_errors() {
+        let tree = Tree::new(
+            "cycle",
+            &[
+                ("a.json", r#"{ "extends": "./b.json" }"#),
+                ("b.json", r#"{ "extends": "./a.json" }"#),
+                ("c.json", r#"{ "extends": "./missing" }"#),
+            ],
+        );
+        let error = CompilerOptions::from_file(&tree.path("a.json")).unwrap_err();
+        assert!(
+            error.to_string().contains("`extends` forms a cycle"),
+            "{}",
+            error
+        );
+        let error = CompilerOptions::from_file(&tree.path("c.json")).unwrap_err();
+        assert!(
+            error.to_string().contains("cannot find `./missing`"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn paths_and_base_url() {
+        let tree = Tree::new(
+            "paths",
+            &[
+                (
+                    "tsconfig.json",
+                    r#"{
+                        "compilerOptions": {
+                            "baseUrl": "./src",
+                            "paths": {
+                                "@app/*": ["app/*", "fallback/*"],
+                                "@app/special/*": ["special/*"],
+                                "config": ["settings/index"]
+                            }
+                        }
+                    }"#,
+                ),
+                ("src/app/a.d.ts", ""),
+                ("src/fallback/b.d.ts", ""),
+                ("src/special/c.d.ts", ""),
+                ("src/settings/index.d.ts", ""),
+                ("src/direct/index.d.ts", ""),
+                (
+                    "nobase.json",
+                    r#"{ "compilerOptions": { "paths": { "x": ["lib/x"] } } }"#,
+                ),
+            ],
+        );
+        let options = tree.options("tsconfig.json");
+        let src = tree.path("src");
+        assert_eq!(
+            options.mapped_paths("@app/a"),
+            vec![src.join("app/a"), src.join("fallback/a")]
+        );
+        assert_eq!(
+            options.mapped_paths("@app/special/c"),
+            vec![src.join("special/c")]
+        );
+        assert_eq!(options.mapped_paths("other"), Vec::<PathBuf>::new());
+        let found = |name: &str| resolve::resolve_mapped(&options, name);
+        assert_eq!(found("@app/a"), Some(src.join("app/a.d.ts")));
+        assert_eq!(found("@app/b"), Some(src.join("fallback/b.d.ts")));
+        assert_eq!(found("@app/special/c"), Some(src.join("special/c.d.ts")));
+        assert_eq!(found("config"), Some(src.join("settings/index.d.ts")));
+        assert_eq!(found("direct"), Some(src.join("direct/index.d.ts")));
+        assert_eq!(found("missing"), None);
+        // Without `baseUrl`, paths are relative to the config file.
+        let options = tree.options("nobase.json");
+        assert_eq!(options.mapped_paths("x"), vec![tree.path("lib/x")]);
+    }
+
+    #[test]
+    fn lib_selection() {
+        let tree = Tree::new(
+            "libs",
+            &[
+                ("none.json", "{}"),
+                (
+                    "target.json",
+                    r#"{ "compilerOptions": { "target": "ES2017" } }"#,
+                ),
+                (
+                    "lib.json",
+                    r#"{ "compilerOptions": { "target": "ES2017", "lib": ["ES2015", "DOM"] } }"#,
+                ),
+                (
+                    "nolib.json",
+                    r#"{ "compilerOptions": { "lib": ["es2015"], "noLib": true } }"#,
+                ),
+            ],
+        );
+        let libs = tree.options("none.json").libs;
+        assert!(libs.contains("es5") && libs.contains("dom"));
+        assert!(!libs.contains("es2015") && !libs.contains("dom.iterable"));
+        let libs = tree.options("target.json").libs;
+        assert!(libs.contains("es2017") && libs.contains("es2015.promise"));
+        assert!(libs.contains("dom.iterable") && !libs.contains("es2018"));
+        let libs = tree.options("lib.json").libs;
+        assert!(libs.contains("es2015") && libs.contains("dom"));
+        assert!(!libs.contains("es2017"));
+        assert_eq!(tree.options("nolib.json").libs, Libs::none());
+    }
+}
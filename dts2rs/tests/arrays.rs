@@ -0,0 +1,77 @@
+mod common;
+
+use common::{assert_contains, generate_with, messages};
+use dts2rs::{ArrayMode, Config, PromiseMode};
+
+fn copied() -> Config {
+    Config {
+        arrays: ArrayMode::Vec,
+        promises: PromiseMode::Async,
+        ..Config::default()
+    }
+}
+
+#[test]
+fn copied_arrays_and_tuples() {
+    let output = generate_with(
+        copied(),
+        "declare function bounds(points: Point[]): [Point, Point];\n\
+         declare function parse(input: string): [name: string, age?: number, ...tags: string[]];\n\
+         interface Point { x: number }",
+    );
+    assert_contains(
+        &output,
+        &[
+            "pub fn bounds(points: &[Point]) -> (Point, Point) {",
+            "pub fn parse(input: &str) -> (String, Option<f64>, Vec<String>) {",
+        ],
+    );
+}
+
+#[test]
+fn defaulted_results_are_documented() {
+    let output = generate_with(
+        copied(),
+        "/** Parses. */\n\
+         declare function parse(input: string): [string, number];\n\
+         declare function points(): Point[];\n\
+         declare function later(): Promise<boolean[]>;\n\
+         interface Point { x: number }\n\
+         interface Tags { [name: string]: string }",
+    );
+    let note =
+        "/// Numbers, booleans and strings in the result that have another type in JavaScript \
+                are read as `NaN`, `false` and `\"\"`.";
+    assert_contains(
+        &output,
+        &[
+            &format!("/// Parses.\n///\n{}\npub fn parse(", note),
+            &format!(
+                "{}\npub async fn later() -> Result<Vec<bool>, JsValue> {{",
+                note
+            ),
+            &format!("    {}\n    pub fn entries(&self)", note),
+            "}\n\npub fn points() -> Vec<Point> {",
+        ],
+    );
+    assert!(!output.code.contains(&format!("{}\n    pub fn keys", note)));
+}
+
+#[test]
+fn defaulted_copies_are_reported() {
+    let output = generate_with(
+        copied(),
+        "declare function names(ids: number[]): string[];\n\
+         declare function points(): Point[];\n\
+         interface Point { x: number; tags: [string, boolean] }",
+    );
+    let note = "values copied from this type aren't checked; numbers, booleans and strings of \
+                another type are read as `NaN`, `false` and `\"\"`";
+    assert_eq!(messages(&output), [note, note]);
+    let locations: Vec<_> = output
+        .diagnostics
+        .iter()
+        .map(|d| d.location.as_ref().unwrap().1.to_string())
+        .collect();
+    assert_eq!(locations, ["1:40", "3:36"]);
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use common::{assert_contains, generate, messages};
+
+#[test]
+fn callbacks_through_aliases() {
+    let output = generate(
+        "type Cb = (err: Error | null, data: string) => void;\n\
+         declare function read(path: string, cb: Cb | undefined): void;",
+    );
+    assert_contains(
+        &output,
+        &[
+            "fn __read(path: &str, cb: Option<&js_sys::Function>);",
+            "pub fn read(path: &str, cb: Option<&Closure<dyn FnMut(Option<js_sys::Error>, String)>>) {",
+        ],
+    );
+}
+
+#[test]
+fn aliases_referring_to_themselves() {
+    let output = generate("type L = L;\ndeclare function f(x: L): void;");
+    assert_contains(&output, &["pub fn f(x: &JsValue);"]);
+    assert_eq!(
+        messages(&output),
+        ["`L` refers to itself; the reference is bound as `JsValue`"]
+    );
+}
+
+#[test]
+fn unions_referring_to_themselves() {
+    let output = generate(
+        "type A = B | null;\n\
+         type B = A | undefined;\n\
+         declare function g(x: A): void;",
+    );
+    assert_contains(&output, &["pub fn g(x: &JsValue);"]);
+    assert_eq!(
+        messages(&output),
+        ["`A` refers to itself; the reference is bound as `JsValue`"]
+    );
+}
@@ -0,0 +1,53 @@
+//! Helpers shared by the lowering tests.
+
+#![allow(dead_code)]
+
+use dts2rs::{Config, Generator, Output};
+
+/// The bindings generated for declaration source `src` with the default config.
+pub fn generate(src: &str) -> Output {
+    generate_with(Config::default(), src)
+}
+
+/// The bindings generated for declaration source `src` with `config`.
+pub fn generate_with(config: Config, src: &str) -> Output {
+    Generator::with_config(config)
+        .generate_source("lib.d.ts", src)
+        .unwrap()
+}
+
+/// The messages of the diagnostics reported for `output`.
+pub fn messages(output: &Output) -> Vec<&str> {
+    output
+        .diagnostics
+        .iter()
+        .map(|d| d.message.as_str())
+        .collect()
+}
+
+/// Asserts that `output`'s code contains each of `lines`.
+#[track_caller]
+pub fn assert_contains(output: &Output, lines: &[&str]) {
+    for line in lines {
+        assert!(
+            output.code.contains(line),
+            "missing `{}` in:\n{}",
+            line,
+            output.code
+        );
+    }
+}
+
+/// The bindings generated with `config` for the files `files`, by relative path, starting from
+/// the first. The files are written to a directory of their own, named after `test`.
+pub fn generate_files(config: Config, test: &str, files: &[(&str, &str)]) -> Output {
+    let dir = std::env::temp_dir().join(format!("dts2rs-{}-{}", test, std::process::id()));
+    for (path, src) in files {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, src).unwrap();
+    }
+    let output = Generator::with_config(config).generate_file(&dir.join(files[0].0));
+    std::fs::remove_dir_all(&dir).unwrap();
+    output.unwrap()
+}
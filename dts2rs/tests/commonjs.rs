@@ -0,0 +1,73 @@
+mod common;
+
+use common::{assert_contains, generate_files, generate_with};
+use dts2rs::{Config, UmdMode};
+
+const JQUERY: &str = "export = jQuery;\n\
+     export as namespace $;\n\
+     declare function jQuery(selector: string): jQuery.JQuery;\n\
+     declare namespace jQuery {\n\
+       interface JQuery { length: number }\n\
+       const fn: number;\n\
+     }";
+
+#[test]
+fn export_assignments_are_the_default_export() {
+    let config = Config {
+        module: Some("jquery".to_string()),
+        ..Config::default()
+    };
+    let output = generate_with(config, JQUERY);
+    assert_contains(
+        &output,
+        &[
+            "#[wasm_bindgen(module = \"jquery\")]\nextern \"C\" {\n    #[wasm_bindgen(js_name = default)]\n    pub fn j_query(selector: &str) -> j_query::JQuery;",
+            "#[wasm_bindgen(static_method_of = JQuery, getter = \"fn\", js_class = \"default\")]\n    pub fn fn_() -> f64;",
+            "    #[wasm_bindgen(module = \"jquery\", js_namespace = default)]",
+        ],
+    );
+}
+
+#[test]
+fn umd_globals() {
+    let config = Config {
+        umd: UmdMode::Global,
+        ..Config::default()
+    };
+    let output = generate_with(config, JQUERY);
+    assert_contains(
+        &output,
+        &[
+            "    #[wasm_bindgen(js_name = \"$\")]\n    pub fn j_query(selector: &str) -> j_query::JQuery;",
+            "    #[wasm_bindgen(js_namespace = [\"$\"])]",
+        ],
+    );
+    assert!(!output.code.contains("module ="), "{}", output.code);
+}
+
+#[test]
+fn required_modules_are_the_declarations_they_export() {
+    let output = generate_files(
+        Config {
+            module: Some("app".to_string()),
+            ..Config::default()
+        },
+        "commonjs-require",
+        &[
+            (
+                "index.d.ts",
+                "import $ = require(\"jquery\");\n\
+                 import jq from \"jquery\";\n\
+                 export declare function select(q: $.JQuery, f: typeof jq): void;",
+            ),
+            ("node_modules/jquery/index.d.ts", JQUERY),
+        ],
+    );
+    assert_contains(
+        &output,
+        &[
+            "pub fn select(q: &jquery::j_query::JQuery, f: &jquery::JQuery);",
+            "pub mod jquery {\n    use super::*;\n\n    #[wasm_bindgen(module = \"jquery\")]",
+        ],
+    );
+}
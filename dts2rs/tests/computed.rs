@@ -0,0 +1,168 @@
+mod common;
+
+use common::{assert_contains, generate, messages};
+
+const CONFIG: &str = "interface Config { a: string; b: number; c?: boolean }\n";
+
+#[test]
+fn conditional_types_distribute_over_unions() {
+    let output = generate(&format!(
+        "{}type Dist<T> = T extends string ? 'S' : 'N';\n\
+         type SN = string | number;\n\
+         declare function written(): Dist<string | number>;\n\
+         declare function aliased(): Dist<SN>;\n\
+         declare function keys(): Dist<keyof Config>;\n\
+         type Wrap<T> = Dist<T>;\n\
+         declare function wrapped(x: Wrap<SN>): void;\n\
+         declare function plain(): Dist<string>;",
+        CONFIG
+    ));
+    assert_contains(
+        &output,
+        &[
+            "pub fn written() -> WrittenReturn;",
+            "pub fn aliased() -> WrittenReturn;",
+            "pub fn wrapped(x: WrittenReturn);",
+            "pub fn keys() -> String;",
+            "pub fn plain() -> String;",
+            "S = \"S\",",
+            "N = \"N\",",
+        ],
+    );
+}
+
+#[test]
+fn infer_and_utility_unions() {
+    let output = generate(&format!(
+        "{}type Unwrap<T> = T extends Promise<infer U> ? U : T;\n\
+         declare function load(): Unwrap<Promise<Config>>;\n\
+         declare function mode(): Config[\"b\"];\n\
+         declare function pick(key: Exclude<keyof Config, \"c\">): void;\n\
+         declare function nonNull(x: NonNullable<string | null>): void;",
+        CONFIG
+    ));
+    assert_contains(
+        &output,
+        &[
+            "pub fn load() -> Config;",
+            "pub fn mode() -> f64;",
+            "pub fn pick(key: PickKey);",
+            "pub fn non_null(x: &str);",
+        ],
+    );
+}
+
+#[test]
+fn mapped_types() {
+    let output = generate(&format!(
+        "{}type Getters<T> = {{ [K in keyof T as `get${{Capitalize<K & string>}}`]: () => T[K] }};\n\
+         type ConfigGetters = Getters<Config>;\n\
+         type Flags = {{ [K in \"x\" | \"y\"]?: boolean }};",
+        CONFIG
+    ));
+    assert_contains(
+        &output,
+        &[
+            "pub type ConfigGetters;",
+            "pub fn get_a(this: &ConfigGetters) -> js_sys::Function;",
+            "pub fn get_c(this: &ConfigGetters) -> Option<js_sys::Function>;",
+            "pub type Flags;",
+            "pub fn x(this: &Flags) -> Option<bool>;",
+        ],
+    );
+    assert!(messages(&output).is_empty(), "{:?}", messages(&output));
+}
+
+#[test]
+fn types_that_cannot_be_evaluated_are_reported() {
+    let output = generate("declare function f(x: Missing extends string ? 1 : 2): void;");
+    assert_contains(&output, &["pub fn f(x: &JsValue);"]);
+    assert_eq!(
+        messages(&output),
+        ["can't evaluate this conditional type; it is bound as `JsValue`"]
+    );
+}
+
+#[test]
+fn instances_nesting_too_deeply_are_reported() {
+    let output = generate(
+        "type Bad<T> = T extends infer U ? Bad<U> : never;\n\
+         declare function f(x: Bad<string>): void;",
+    );
+    assert_contains(&output, &["pub fn f(x: &JsValue);"]);
+    assert_eq!(
+        messages(&output),
+        ["instances of `Bad` nest too deeply to evaluate; they are bound as `JsValue`"]
+    );
+}
+
+#[test]
+fn aliases_referring_to_themselves() {
+    let output = generate(
+        "type R = { [k: string]: R };\n\
+         declare function r(x: R): void;\n\
+         type Node = { next?: Node; value: number };\n\
+         declare function n(x: Node): void;",
+    );
+    assert_contains(
+        &output,
+        &[
+            "pub fn r(x: &JsRecord<JsValue>)",
+            "pub fn next(this: &Node) -> Option<Node>;",
+        ],
+    );
+    assert_eq!(
+        messages(&output),
+        ["`R` refers to itself; the reference is bound as `JsValue`"]
+    );
+}
+
+#[test]
+fn each_type_is_reported_once() {
+    let output = generate(&format!(
+        "{}type Deep<T> = {{ [K in keyof T]: T[K] extends object ? Deep<T[K]> : T[K] }};\n\
+         type Wrapped = Deep<{{ list: string[]; other: number[] }}>;",
+        CONFIG
+    ));
+    assert_eq!(
+        messages(&output),
+        ["can't evaluate this mapped type; it is bound as `JsValue`"]
+    );
+}
+
+#[test]
+fn types_evaluating_to_themselves_are_reported() {
+    let output = generate("interface T { a: T[\"a\"] }");
+    assert_contains(&output, &["pub fn a(this: &T) -> JsValue;"]);
+    assert_eq!(
+        messages(&output),
+        ["this indexed access type refers to itself too deeply to evaluate; it is bound as `JsValue`"]
+    );
+}
+
+#[test]
+fn inline_mapped_types_are_bound_like_literals() {
+    let output = generate(&format!(
+        "{}declare function f(m: {{ [K in keyof Config]: string }}): void;",
+        CONFIG
+    ));
+    assert_contains(
+        &output,
+        &[
+            "pub fn f(m: &FM);",
+            "pub type FM;",
+            "pub fn a(this: &FM) -> String;",
+        ],
+    );
+    assert!(messages(&output).is_empty(), "{:?}", messages(&output));
+}
+
+#[test]
+fn types_depending_on_function_type_parameters_are_reported() {
+    let output = generate("declare function f<U>(x: U extends string ? 1 : 2): void;");
+    assert_contains(&output, &["pub fn f(x: &JsValue);"]);
+    assert_eq!(
+        messages(&output),
+        ["can't evaluate this conditional type for every `U`; it is bound as `JsValue`"]
+    );
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use common::{assert_contains, generate, messages};
+
+#[test]
+fn member_values_are_folded() {
+    let output = generate(
+        "declare enum Flags { None, Read = 1 << 0, Write = 1 << 1, ReadWrite = Read | Write, Default = 0 }\n\
+         declare const enum Other { X = Flags.Write + 1, Y }",
+    );
+    assert_contains(
+        &output,
+        &[
+            "pub enum Flags {\n    None = 0,\n    Read = 1,\n    Write = 2,\n    ReadWrite = 3,\n}",
+            "impl Flags {\n    pub const DEFAULT: Flags = Flags::None;\n}",
+            "pub enum Other {\n    X = 3,\n    Y = 4,\n}",
+        ],
+    );
+}
+
+#[test]
+fn string_enums() {
+    let output = generate("declare enum Direction { Up = \"up\", Down = \"down\" }");
+    assert_contains(
+        &output,
+        &[
+            "pub enum Direction {\n    Up = \"up\",\n    Down = \"down\",\n}",
+            "Direction::from_js_value(&value).ok_or(value)",
+        ],
+    );
+}
+
+#[test]
+fn enums_without_integer_values_are_reported() {
+    let output = generate(
+        "declare enum Ratio { Half = 0.5, One = 1 }\n\
+         declare enum Mixed { A = 1, B = \"b\" }\n\
+         declare function f(r: Ratio, m: Mixed): void;",
+    );
+    assert_contains(&output, &["pub fn f(r: f64, m: &JsValue);"]);
+    assert_eq!(
+        messages(&output),
+        [
+            "enum `Ratio` has values that are not 32-bit integers; it is bound as `f64`",
+            "enum `Mixed` mixes numbers and strings; it is bound as `JsValue`",
+        ]
+    );
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use common::{assert_contains, generate, generate_with};
+use dts2rs::Config;
+
+const WINDOW: &str = "declare global {\n\
+                          interface Window { myLib: string }\n\
+                          interface Array<T> { first(): T }\n\
+                      }\n\
+                      export {};";
+
+#[test]
+fn named_after_the_module() {
+    let config = Config {
+        module: Some("@acme/my-lib".to_string()),
+        ..Config::default()
+    };
+    let output = generate_with(config, WINDOW);
+    assert_contains(
+        &output,
+        &[
+            "/// `Window` with the members that `my-lib` adds to it; see [`WindowMyLibExt`].",
+            "#[wasm_bindgen(extends = web_sys::Window, extends = js_sys::Object, js_name = Window, is_type_of = JsValue::is_object)]",
+            "pub type WindowMyLib;",
+            "pub trait WindowMyLibExt {",
+            "impl WindowMyLibExt for web_sys::Window {",
+            "self.unchecked_ref::<WindowMyLib>().my_lib()",
+        ],
+    );
+}
+
+#[test]
+fn unnamed_without_a_module() {
+    let output = generate(WINDOW);
+    assert_contains(
+        &output,
+        &[
+            "/// `Window` with the members that this library adds to it; see [`WindowExt`].",
+            "pub type WindowMembers;",
+            "pub trait WindowExt {",
+            "impl WindowExt for web_sys::Window {",
+        ],
+    );
+}
+
+#[test]
+fn extended_types_wrapping_values_are_not_eq() {
+    let output = generate(WINDOW);
+    assert_contains(
+        &output,
+        &[
+            "#[wasm_bindgen(extends = js_sys::Array, extends = js_sys::Object, js_name = Array, is_type_of = JsValue::is_object)]\n    #[derive(Debug, Clone, PartialEq)]\n    pub type ArrayMembers;",
+            "impl ArrayExt for js_sys::Array {",
+        ],
+    );
+}
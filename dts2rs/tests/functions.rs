@@ -0,0 +1,81 @@
+mod common;
+
+use common::{assert_contains, generate, messages};
+
+#[test]
+fn functions_and_variables() {
+    let output = generate(
+        "declare function foo(a: number, b?: string): boolean;\n\
+         declare const VERSION: string;\n\
+         declare let count: number;",
+    );
+    assert_contains(
+        &output,
+        &[
+            "pub fn foo(a: f64, b: Option<&str>) -> bool;",
+            "pub static VERSION: String;",
+            "pub static COUNT: f64;",
+        ],
+    );
+}
+
+#[test]
+fn names_without_word_characters_are_spelled_out() {
+    let output = generate(
+        "declare function $(s: string): void;\n\
+         declare const $$: number;\n\
+         declare function _(): void;",
+    );
+    assert_contains(
+        &output,
+        &[
+            "pub fn dollar(s: &str);",
+            "pub static DOLLAR_DOLLAR: f64;",
+            "pub fn underscore();",
+        ],
+    );
+    assert!(!output.code.contains(" _("));
+}
+
+#[test]
+fn statics_with_the_same_rust_name_are_numbered() {
+    let output = generate("declare const $a: number;\ndeclare const a$: number;");
+    assert_contains(&output, &["pub static A: f64;", "pub static A2: f64;"]);
+}
+
+#[test]
+fn unknown_types_are_reported() {
+    let output = generate(
+        "declare module 'lib' { export interface Known {} }\n\
+         declare function f(a: Missing, b: Other.Thing): void;\n\
+         declare function g(a: import('lib').Gone): void;",
+    );
+    assert_contains(
+        &output,
+        &[
+            "pub fn f(a: &JsValue, b: &JsValue);",
+            "pub fn g(a: &JsValue);",
+        ],
+    );
+    assert_eq!(
+        messages(&output),
+        [
+            "unknown type `Missing`; it is bound as `JsValue`",
+            "unknown type `Other.Thing`; it is bound as `JsValue`",
+            "unknown type `Gone`; it is bound as `JsValue`",
+        ]
+    );
+}
+
+#[test]
+fn types_imported_from_missing_modules_are_reported_once() {
+    let output = generate(
+        "import { Thing } from 'missing';\n\
+         export declare function f(a: Thing, b: Thing): void;",
+    );
+    assert_contains(&output, &["pub fn f(a: &JsValue, b: &JsValue);"]);
+    assert_eq!(
+        messages(&output),
+        ["cannot resolve module `missing`; its types are bound as `JsValue`"]
+    );
+}
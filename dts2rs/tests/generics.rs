@@ -0,0 +1,49 @@
+mod common;
+
+use common::{assert_contains, generate, generate_with};
+use dts2rs::{Config, GenericsMode};
+
+const CELL: &str = "interface Cell<T = string> { get(): T; set(value: T): void }\n\
+     declare function wrap<T>(value: T): Cell<T>;\n\
+     declare function numbers(): Cell<number>;";
+
+#[test]
+fn typed_wrappers() {
+    let output = generate(CELL);
+    assert_contains(
+        &output,
+        &[
+            "#[wasm_bindgen(extends = js_sys::Object, js_name = Cell, is_type_of = JsValue::is_object)]",
+            "pub type CellErased;",
+            "pub fn get(this: &CellErased) -> JsValue;",
+            "#[wasm_bindgen(js_name = wrap)]\n    fn __wrap(value: &JsValue) -> CellErased;",
+            "pub struct Cell<T: JsCast = js_sys::JsString> {",
+            "    pub fn get(&self) -> T {\n        self.erased.get().unchecked_into()\n    }",
+            "    pub fn set(&self, value: &T) {\n        self.erased.set(value.as_ref())\n    }",
+            "impl<T: JsCast> std::ops::Deref for Cell<T> {",
+            "impl<T: JsCast> JsCast for Cell<T> {",
+            "pub fn wrap<T: JsCast>(value: &T) -> Cell<T> {\n    Cell::from_erased(__wrap(value.as_ref()))\n}",
+            "pub fn numbers() -> Cell<js_sys::Number> {",
+        ],
+    );
+}
+
+#[test]
+fn erased_bindings() {
+    let config = Config {
+        generics: GenericsMode::Erased,
+        ..Config::default()
+    };
+    let output = generate_with(config, CELL);
+    assert_contains(
+        &output,
+        &[
+            "pub type Cell;",
+            "pub fn get(this: &Cell) -> JsValue;",
+            "pub fn set(this: &Cell, value: &JsValue);",
+            "pub fn wrap(value: &JsValue) -> Cell;",
+            "pub fn numbers() -> Cell;",
+        ],
+    );
+    assert!(!output.code.contains("Erased"), "{}", output.code);
+}
@@ -0,0 +1,23 @@
+mod common;
+
+use common::{assert_contains, generate};
+
+#[test]
+fn properties_starting_with_digits() {
+    let output = generate(
+        "interface Pair { 0: string; 1: number; length: 2 }\n\
+         declare class T { '1x': number }",
+    );
+    assert_contains(
+        &output,
+        &[
+            "#[wasm_bindgen(method, structural, getter = \"0\")]\n    pub fn get_0(this: &Pair) -> String;",
+            "#[wasm_bindgen(method, structural, setter)]\n    pub fn set_0(this: &Pair, value: &str);",
+            "pub fn get_1(this: &Pair) -> f64;",
+            "pub fn set_1(this: &Pair, value: f64);",
+            "pub fn length(this: &Pair) -> f64;",
+            "#[wasm_bindgen(method, getter = \"1x\")]\n    pub fn get_1x(this: &T) -> f64;",
+            "pub fn set_1x(this: &T, value: f64);",
+        ],
+    );
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use common::{assert_contains, generate, messages};
+
+const BASE: &str = "interface BaseProps { id: string; size: number }\n\
+                    interface Other { label: string }\n";
+
+#[test]
+fn extends_nominal_constituents() {
+    let output = generate(&format!(
+        "{}type Props = BaseProps & Other & {{ extra: string }};",
+        BASE
+    ));
+    assert_contains(
+        &output,
+        &[
+            "#[wasm_bindgen(extends = BaseProps, extends = Other, extends = js_sys::Object, is_type_of = JsValue::is_object)]\n    #[derive(Debug, Clone, PartialEq, Eq)]\n    pub type Props;",
+            "pub fn extra(this: &Props) -> String;",
+            "pub fn set_extra(this: &Props, value: &str);",
+        ],
+    );
+    // Inherited members are reached through the upcasts.
+    assert!(!output.code.contains("pub fn id(this: &Props)"));
+    assert!(messages(&output).is_empty());
+}
+
+#[test]
+fn conflicting_members_are_reported() {
+    let output = generate(&format!(
+        "{}type Clash = BaseProps & {{ size: string }};",
+        BASE
+    ));
+    assert_contains(
+        &output,
+        &["#[wasm_bindgen(extends = BaseProps, extends = js_sys::Object, is_type_of = JsValue::is_object)]"],
+    );
+    assert!(!output.code.contains("pub fn size(this: &Clash)"));
+    assert_eq!(
+        messages(&output),
+        ["member `size` has conflicting types in this intersection; the first is bound"]
+    );
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use common::{assert_contains, generate, generate_with};
+use dts2rs::Config;
+
+const INIT: &str = "declare function init(opts: { root: string; debug?: boolean }): void;\n\
+                    interface Foo { bar(): { x: number; y: number } }";
+
+#[test]
+fn literals_are_named_after_where_they_are_written() {
+    let output = generate(INIT);
+    assert_contains(
+        &output,
+        &[
+            "pub fn init(opts: &InitOpts);",
+            "pub type InitOpts;",
+            "pub fn root(this: &InitOpts) -> String;",
+            "pub fn debug(this: &InitOpts) -> Option<bool>;",
+            "pub fn bar(this: &Foo) -> FooBarReturn;",
+        ],
+    );
+    assert_eq!(output.type_names["init.opts"], "InitOpts");
+    assert_eq!(output.type_names["Foo.bar.return"], "FooBarReturn");
+}
+
+#[test]
+fn names_survive_unrelated_literals() {
+    let src = format!(
+        "declare function alpha(cfg: {{ root: number }}): void;\n{}",
+        INIT
+    );
+    let output = generate(&src);
+    assert_contains(
+        &output,
+        &[
+            "pub fn alpha(cfg: &AlphaCfg);",
+            "pub fn init(opts: &InitOpts);",
+            "pub fn root(this: &InitOpts) -> String;",
+        ],
+    );
+    assert!(output.names.contains_key("get InitOpts.root"));
+}
+
+#[test]
+fn identical_literals_share_a_type() {
+    let src = "declare function start(o: { port: number; host?: string }): void;\n\
+               declare function listen(options: { port: number; host?: string }): void;\n\
+               declare function stop(o: { force: boolean }): void;";
+    let output = generate(src);
+    assert_contains(
+        &output,
+        &[
+            "pub fn start(o: &ListenOptions);",
+            "pub fn listen(options: &ListenOptions);",
+            "pub fn stop(o: &StopO);",
+            "pub fn port(this: &ListenOptions) -> f64;",
+        ],
+    );
+    assert!(!output.code.contains("StartO"));
+    assert_eq!(output.code.matches("pub type ListenOptions;").count(), 1);
+    assert_eq!(output.type_names["start.o"], "ListenOptions");
+    assert_eq!(output.type_names["listen.options"], "ListenOptions");
+
+    // The shared name doesn't depend on the order of the declarations.
+    let reordered = generate(
+        "declare function listen(options: { port: number; host?: string }): void;\n\
+         declare function start(o: { port: number; host?: string }): void;",
+    );
+    assert_contains(&reordered, &["pub fn start(o: &ListenOptions);"]);
+}
+
+#[test]
+fn literals_containing_identical_literals_share_a_type() {
+    let output = generate(
+        "declare function a(o: { inner: { depth: number } }): void;\n\
+         declare function b(o: { inner: { depth: number } }): void;",
+    );
+    assert_contains(
+        &output,
+        &[
+            "pub fn a(o: &AO);",
+            "pub fn b(o: &AO);",
+            "pub fn inner(this: &AO) -> AOInner;",
+        ],
+    );
+    assert!(!output.code.contains("BO"));
+}
+
+#[test]
+fn nested_literals() {
+    let output = generate("declare function configure(opts: { nested: { depth: number } }): void;");
+    assert_contains(
+        &output,
+        &[
+            "pub fn nested(this: &ConfigureOpts) -> ConfigureOptsNested;",
+            "pub fn depth(this: &ConfigureOptsNested) -> f64;",
+        ],
+    );
+}
+
+#[test]
+fn configured_names() {
+    let mut config = Config::default();
+    config
+        .type_names
+        .insert("init.opts".to_string(), "InitOptions".to_string());
+    let output = generate_with(config, INIT);
+    assert_contains(&output, &["pub fn init(opts: &InitOptions);"]);
+    assert!(!output.code.contains("InitOpts;"));
+}
+
+#[test]
+fn literals_pinned_to_one_name_share_it_if_they_match() {
+    let src = "declare function a(o: { x: number }): void;\n\
+               declare function b(o: { x: number }): void;\n\
+               declare function c(o: { y: number }): void;";
+    let mut config = Config::default();
+    for site in ["a.o", "b.o", "c.o"] {
+        config
+            .type_names
+            .insert(site.to_string(), "Point".to_string());
+    }
+    let output = generate_with(config, src);
+    assert_contains(
+        &output,
+        &[
+            "pub fn a(o: &Point);",
+            "pub fn b(o: &Point);",
+            "pub fn c(o: &Point2);",
+        ],
+    );
+    assert_eq!(output.code.matches("pub type Point;").count(), 1);
+}
+
+#[test]
+fn literals_that_are_not_named() {
+    let output = generate(
+        "declare function f(r: { [k: string]: number }, e: {}): void;\n\
+         declare function g<T>(o: { value: T }): void;",
+    );
+    assert_contains(
+        &output,
+        &[
+            "pub fn f(r: &JsPrimitiveRecord<f64>, e: &js_sys::Object) {",
+            "e: &js_sys::Object);",
+            "o: &js_sys::Object",
+        ],
+    );
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use common::{assert_contains, generate};
+
+const JQUERY: &str = "declare function jQuery(selector: string): JQuery;\n\
+                      declare namespace jQuery {\n\
+                          function ajax(url: string): void;\n\
+                          const fx: number;\n\
+                          interface Settings { url: string }\n\
+                      }\n\
+                      interface JQuery { length: number }\n";
+
+#[test]
+fn function_merged_with_namespace() {
+    let output = generate(JQUERY);
+    assert_contains(
+        &output,
+        &[
+            "#[wasm_bindgen(extends = js_sys::Function, extends = js_sys::Object, js_name = jQuery, is_type_of = JsValue::is_function)]",
+            "pub type JQueryStatic;",
+            "#[wasm_bindgen(static_method_of = JQueryStatic, js_class = \"jQuery\")]\n    pub fn ajax(url: &str);",
+            "#[wasm_bindgen(static_method_of = JQueryStatic, getter, js_class = \"jQuery\")]\n    pub fn fx() -> f64;",
+            "fn __call(this: &JQueryStatic, this_arg: &JsValue, selector: &str) -> JQuery;",
+            "impl JQueryStatic {\n    pub fn call(&self, selector: &str) -> JQuery {\n        self.__call(&JsValue::UNDEFINED, selector)\n    }\n}",
+            "#[wasm_bindgen(js_name = jQuery)]\n    pub fn j_query(selector: &str) -> JQuery;",
+            "pub mod j_query {",
+            "pub type Settings;",
+        ],
+    );
+    assert_eq!(output.names["call jQuery(string)"], "call");
+    assert_eq!(output.names["static jQuery.ajax(string)"], "ajax");
+}
+
+#[test]
+fn typeof_merged_function_is_its_type() {
+    let output = generate(&format!(
+        "{}declare const $: typeof jQuery;\n\
+         declare function wrap(f: typeof jQuery): void;\n\
+         declare function plain(): void;\n\
+         declare const p: typeof plain;",
+        JQUERY
+    ));
+    assert_contains(
+        &output,
+        &[
+            "pub static DOLLAR: JQueryStatic;",
+            "pub fn wrap(f: &JQueryStatic);",
+            "pub static P: JsValue;",
+        ],
+    );
+}
+
+#[test]
+fn overloaded_calls() {
+    let output = generate(
+        "declare function jQuery(selector: string): void;\n\
+         declare function jQuery(selector: string, context: Element): void;\n\
+         declare namespace jQuery { function ajax(url: string): void; }",
+    );
+    assert_contains(
+        &output,
+        &[
+            "pub fn call(&self, selector: &str) {",
+            "pub fn call_with_str_and_element(&self, selector: &str, context: &web_sys::Element) {",
+        ],
+    );
+}
@@ -0,0 +1,136 @@
+mod common;
+
+use common::{assert_contains, generate_files, messages};
+use dts2rs::Config;
+
+const UTIL: &str = "export declare function make(): Widget;\n\
+                    export declare function make(size: number): Widget;\n\
+                    export declare class Widget { size: number }\n";
+
+fn package() -> Config {
+    Config {
+        module: Some("my-pkg".to_string()),
+        ..Config::default()
+    }
+}
+
+#[test]
+fn renamed_imports_are_reexported() {
+    let output = generate_files(
+        Config::default(),
+        "renamed_imports_are_reexported",
+        &[
+            (
+                "index.d.ts",
+                "import { make as mk, Widget as W } from './util';\n\
+                 export { mk, W };\n\
+                 export { helper as assist } from './helpers';",
+            ),
+            ("util.d.ts", UTIL),
+            (
+                "helpers.d.ts",
+                "export declare function helper(a: string): void;",
+            ),
+        ],
+    );
+    assert_contains(
+        &output,
+        &[
+            "pub use self::util::Widget as W;",
+            "pub use self::util::make as mk;",
+            "pub use self::util::make_with_f64 as mk_with_f64;",
+            "pub use self::helpers::helper as assist;",
+        ],
+    );
+    assert!(messages(&output).is_empty(), "{:?}", messages(&output));
+}
+
+#[test]
+fn declarations_are_imported_by_their_exported_names() {
+    let output = generate_files(
+        package(),
+        "declarations_are_imported_by_their_exported_names",
+        &[
+            (
+                "index.d.ts",
+                "export { make as mk, Widget as W } from './util';\n\
+                 export * from './helpers';",
+            ),
+            ("util.d.ts", UTIL),
+            (
+                "helpers.d.ts",
+                "export declare function helper(a: string): void;",
+            ),
+        ],
+    );
+    assert_contains(
+        &output,
+        &[
+            "pub mod util {\n    use super::*;\n\n    #[wasm_bindgen(module = \"my-pkg\")]",
+            "#[wasm_bindgen(js_name = mk)]\n        pub fn make() -> Widget;",
+            "#[wasm_bindgen(extends = js_sys::Object, js_name = W)]",
+            "#[wasm_bindgen(constructor, js_class = \"W\")]",
+            "pub mod helpers {\n    use super::*;\n\n    #[wasm_bindgen(module = \"my-pkg\")]\n    extern \"C\" {\n        pub fn helper(a: &str);",
+        ],
+    );
+}
+
+#[test]
+fn files_the_entry_does_not_reexport_are_imported_from_their_path() {
+    let output = generate_files(
+        package(),
+        "files_the_entry_does_not_reexport_are_imported_from_their_path",
+        &[
+            ("index.d.ts", "export { make } from './lib/util';"),
+            (
+                "lib/util.d.ts",
+                "export declare function make(): void;\n\
+                 export declare function hidden(): void;",
+            ),
+        ],
+    );
+    assert_contains(
+        &output,
+        &[
+            "#[wasm_bindgen(module = \"my-pkg/lib/util\")]\n        extern \"C\" {\n            pub fn make();\n\n            pub fn hidden();",
+            "pub use self::lib::util::make;",
+        ],
+    );
+}
+
+#[test]
+fn default_exports_are_imported_as_default() {
+    let output = generate_files(
+        package(),
+        "default_exports_are_imported_as_default",
+        &[
+            (
+                "index.d.ts",
+                "export default function main(x: number): void;\n\
+                 export { default as Thing } from './thing';",
+            ),
+            ("thing.d.ts", "export default class Thing { run(): void }"),
+        ],
+    );
+    assert_contains(
+        &output,
+        &[
+            "#[wasm_bindgen(js_name = default)]\n    pub fn main(x: f64);",
+            "pub use self::thing::Thing;",
+            // The entry file exports the class by name.
+            "#[wasm_bindgen(method)]\n        pub fn run(this: &Thing);",
+        ],
+    );
+    let output = generate_files(
+        package(),
+        "default_exports_are_imported_as_default",
+        &[("index.d.ts", "export default class Thing { run(): void }")],
+    );
+    assert_contains(
+        &output,
+        &[
+            "#[wasm_bindgen(extends = js_sys::Object, js_name = default)]",
+            "#[wasm_bindgen(method, js_class = \"default\")]\n    pub fn run(this: &Thing);",
+        ],
+    );
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use common::{assert_contains, generate, generate_with};
+use dts2rs::Config;
+
+#[test]
+fn empty_namespaces_import_nothing() {
+    let output = generate(
+        "declare namespace NS { type X = string; }\n\
+         declare namespace C { namespace D { function f(): void } }",
+    );
+    assert_contains(
+        &output,
+        &[
+            "pub mod ns {}",
+            "pub mod c {\n    use super::*;\n\n    pub mod d {\n        use super::*;\n",
+        ],
+    );
+}
+
+#[test]
+fn namespaces_become_modules() {
+    let output = generate(
+        "declare namespace Outer {\n\
+           const version: string;\n\
+           function run(): void;\n\
+           namespace Inner { class Thing { constructor(); go(): void } }\n\
+         }",
+    );
+    assert_contains(
+        &output,
+        &[
+            "pub mod outer {\n    use super::*;\n\n    #[wasm_bindgen(js_namespace = Outer)]\n    extern \"C\" {",
+            "        #[wasm_bindgen(thread_local_v2, js_name = version)]\n        pub static VERSION: String;",
+            "        pub fn run();",
+            "    pub mod inner {\n        use super::*;\n\n        #[wasm_bindgen(js_namespace = [\"Outer\", \"Inner\"])]",
+            "            pub fn go(this: &Thing);",
+        ],
+    );
+}
+
+#[test]
+fn ambient_modules_and_merged_namespaces() {
+    let config = Config {
+        names: [("my-pkg.Client.connect()", "open"), ("A.B.f()", "eff")]
+            .into_iter()
+            .map(|(key, name)| (key.to_string(), name.to_string()))
+            .collect(),
+        ..Config::default()
+    };
+    let output = generate_with(
+        config,
+        "declare module \"my-pkg\" {\n\
+           export class Client { connect(): void }\n\
+         }\n\
+         declare namespace A.B { function f(): void }\n\
+         declare namespace A { function g(): B.T }\n\
+         declare namespace A.B { interface T { x: number } }",
+    );
+    assert_contains(
+        &output,
+        &[
+            "pub mod my_pkg {\n    use super::*;\n\n    #[wasm_bindgen(module = \"my-pkg\")]",
+            "        #[wasm_bindgen(method, js_name = connect)]\n        pub fn open(this: &Client);",
+            "        pub fn g() -> b::T;",
+            "        #[wasm_bindgen(js_name = f)]\n            pub fn eff();",
+            "            pub type T;",
+        ],
+    );
+    assert_eq!(
+        output.code.matches("pub mod b {").count(),
+        1,
+        "{}",
+        output.code
+    );
+}
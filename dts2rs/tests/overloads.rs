@@ -0,0 +1,138 @@
+mod common;
+
+use common::{assert_contains, generate, generate_with, messages};
+use dts2rs::{Config, PromiseMode};
+
+const OVERLOADS: &str = "declare function foo(a: string): void;\n\
+     declare function foo(a: number): void;\n\
+     declare function foo(a: string, options: Options): void;\n\
+     declare function bar(): void;\n\
+     declare function bar(x: number): void;\n\
+     declare function baz(x: number): void;\n\
+     interface Options { x: number }";
+
+#[test]
+fn overloads_are_named_after_their_parameters() {
+    let output = generate(OVERLOADS);
+    assert_contains(
+        &output,
+        &[
+            // Of the overloads with fewest parameters, the last keeps the name.
+            "pub fn foo_with_str(a: &str);",
+            "pub fn foo(a: f64);",
+            "pub fn foo_with_str_and_options(a: &str, options: &Options);",
+            "pub fn bar();",
+            "pub fn bar_with_f64(x: f64);",
+        ],
+    );
+    assert_eq!(output.names["foo(string)"], "foo_with_str");
+    assert_eq!(output.names["foo(number)"], "foo");
+}
+
+#[test]
+fn names_are_pinned_by_key() {
+    let config = Config {
+        names: [
+            ("foo(string)", "foo_str"),
+            // Bare keys only name functions that aren't overloaded.
+            ("bar", "ignored"),
+            ("baz", "qux"),
+        ]
+        .into_iter()
+        .map(|(key, name)| (key.to_string(), name.to_string()))
+        .collect(),
+        ..Config::default()
+    };
+    let output = generate_with(config, OVERLOADS);
+    assert_contains(
+        &output,
+        &[
+            "pub fn foo_str(a: &str);",
+            "pub fn foo(a: f64);",
+            "pub fn bar();",
+            "pub fn bar_with_f64(x: f64);",
+            "#[wasm_bindgen(js_name = baz)]\n    pub fn qux(x: f64);",
+        ],
+    );
+    assert!(!output.code.contains("ignored"), "{}", output.code);
+}
+
+#[test]
+fn pinned_names_are_not_taken_by_generated_ones() {
+    let config = Config {
+        names: [("foo(number)".to_string(), "foo_with_str".to_string())].into(),
+        ..Config::default()
+    };
+    let output = generate_with(config, OVERLOADS);
+    assert_contains(
+        &output,
+        &[
+            "pub fn foo_with_str2(a: &str);",
+            "pub fn foo_with_str(a: f64);",
+        ],
+    );
+}
+
+#[test]
+fn async_wrappers_take_the_name() {
+    let config = Config {
+        promises: PromiseMode::Async,
+        ..Config::default()
+    };
+    let output = generate_with(
+        config,
+        "declare function fetch(url: string): Promise<string>;",
+    );
+    assert_contains(
+        &output,
+        &[
+            "#[wasm_bindgen(js_name = fetch)]\n    pub fn fetch_promise(url: &str) -> js_sys::Promise;",
+            "pub async fn fetch(url: &str) -> Result<String, JsValue> {",
+        ],
+    );
+    assert_eq!(output.names["fetch(string)"], "fetch");
+}
+
+#[test]
+fn index_accessors_that_are_renumbered_are_reported() {
+    let output = generate(
+        "interface Lookup {\n\
+           get(key: string): number;\n\
+           [key: string]: any;\n\
+         }",
+    );
+    assert_contains(
+        &output,
+        &[
+            "pub fn get(this: &Lookup, key: &str) -> f64;",
+            "pub fn get2(this: &Lookup, key: &str) -> JsValue;",
+            "pub fn set(this: &Lookup, key: &str, value: &JsValue);",
+        ],
+    );
+    assert_eq!(
+        messages(&output),
+        ["`get Lookup[string]` is bound as `get2`, since a member takes `get`; its name can be set in `[names]`"]
+    );
+}
+
+#[test]
+fn dumped_names_reproduce_the_bindings() {
+    let first = generate(OVERLOADS);
+    // What `--dump-names` writes.
+    let dumped = Config {
+        names: first.names.clone(),
+        type_names: first.type_names.clone(),
+        ..Config::default()
+    }
+    .to_toml();
+    assert!(
+        dumped.contains("\"foo(string)\" = \"foo_with_str\""),
+        "{}",
+        dumped
+    );
+    let config = Config::from_toml(&dumped).unwrap();
+    assert_eq!(config.names, first.names);
+    let second = generate_with(config, OVERLOADS);
+    assert_eq!(second.code, first.code);
+    assert_eq!(second.names, first.names);
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::{assert_contains, generate, generate_with};
+use dts2rs::{Config, PromiseMode};
+
+const API: &str = "interface User { name: string }\n\
+     declare function fetchUser(id: string): Promise<User>;\n\
+     declare function count(): Promise<number>;\n\
+     declare function done(): Promise<void>;\n\
+     declare class Api { load(): Promise<string>; readonly ready: Promise<boolean> }";
+
+fn async_promises() -> Config {
+    Config {
+        promises: PromiseMode::Async,
+        ..Config::default()
+    }
+}
+
+#[test]
+fn raw_promises() {
+    let output = generate(API);
+    assert_contains(
+        &output,
+        &[
+            "#[wasm_bindgen(js_name = fetchUser)]\n    pub fn fetch_user(id: &str) -> js_sys::Promise;",
+            "pub fn load(this: &Api) -> js_sys::Promise;",
+        ],
+    );
+    assert!(!output.code.contains("async fn"), "{}", output.code);
+    assert!(!output.cargo_toml("app").contains("wasm-bindgen-futures"));
+}
+
+#[test]
+fn async_wrappers() {
+    let output = generate_with(async_promises(), API);
+    assert_contains(
+        &output,
+        &[
+            "#[wasm_bindgen(js_name = fetchUser)]\n    pub fn fetch_user_promise(id: &str) -> js_sys::Promise;",
+            "pub async fn fetch_user(id: &str) -> Result<User, JsValue> {\n    \
+             let value = wasm_bindgen_futures::JsFuture::from(fetch_user_promise(id)).await?;\n    \
+             Ok(value.unchecked_into())\n}",
+            "pub async fn count() -> Result<f64, JsValue> {",
+            "    value.as_f64().ok_or(value)",
+            "pub async fn done() -> Result<(), JsValue> {\n    \
+             wasm_bindgen_futures::JsFuture::from(done_promise()).await?;\n    Ok(())\n}",
+            "    pub async fn load(&self) -> Result<String, JsValue> {\n        \
+             let value = wasm_bindgen_futures::JsFuture::from(self.load_promise()).await?;",
+            // Accessors only return the promise.
+            "#[wasm_bindgen(method, getter)]\n    pub fn ready(this: &Api) -> js_sys::Promise;",
+        ],
+    );
+    assert!(output
+        .cargo_toml("app")
+        .contains("wasm-bindgen-futures = \"0.4\"\n"));
+}
+
+#[test]
+fn futures_are_only_a_dependency_when_used() {
+    let output = generate_with(async_promises(), "declare function f(): void;");
+    assert!(!output.cargo_toml("app").contains("wasm-bindgen-futures"));
+}
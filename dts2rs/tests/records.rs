@@ -0,0 +1,58 @@
+mod common;
+
+use common::{assert_contains, generate, messages};
+
+#[test]
+fn records_of_primitives() {
+    let output = generate(
+        "declare function scores(): Record<string, number>;\n\
+         declare function flags(f: Record<string, boolean>): void;\n\
+         declare function labels(): { [key: string]: string };",
+    );
+    assert_contains(
+        &output,
+        &[
+            "pub trait JsPrimitive: Sized {",
+            "impl JsPrimitive for f64 {",
+            "pub struct JsPrimitiveRecord<T: JsPrimitive> {",
+            "pub fn get(&self, key: &str) -> Option<T> {\n        T::from_js(",
+            "pub fn scores() -> JsPrimitiveRecord<f64> {",
+            "pub fn flags(f: &JsPrimitiveRecord<bool>) {",
+            "pub fn labels() -> JsPrimitiveRecord<String> {",
+        ],
+    );
+    assert!(!output.code.contains("pub struct JsRecord"));
+}
+
+#[test]
+fn records_of_objects() {
+    let output = generate(
+        "declare function dates(): Record<string, Date>;\n\
+         declare function first<T>(r: Record<string, T>): T;",
+    );
+    assert_contains(
+        &output,
+        &[
+            "pub struct JsRecord<T: JsCast = JsValue> {",
+            "pub fn dates() -> JsRecord<js_sys::Date> {",
+            "pub fn first<T: JsCast>(r: &JsRecord<T>) -> T {",
+        ],
+    );
+    assert!(!output.code.contains("JsPrimitiveRecord"));
+}
+
+#[test]
+fn index_accessors_yield_to_members() {
+    let output = generate("interface Dict { [key: string]: number; get(key: string): number }");
+    assert_contains(
+        &output,
+        &[
+            "#[wasm_bindgen(method, structural)]\n    pub fn get(this: &Dict, key: &str) -> f64;",
+            "#[wasm_bindgen(method, structural, indexing_getter)]\n    pub fn get2(this: &Dict, key: &str) -> Option<f64>;",
+        ],
+    );
+    assert_eq!(
+        messages(&output),
+        ["`get Dict[string]` is bound as `get2`, since a member takes `get`; its name can be set in `[names]`"]
+    );
+}
@@ -0,0 +1,27 @@
+mod common;
+
+use common::{assert_contains, generate};
+
+#[test]
+fn types_named_like_prelude_types_are_prefixed() {
+    let output = generate(
+        "declare class Box<T> { constructor(value: T); get(): T }\n\
+         interface Option { value?: number }\n\
+         type Result = 'ok' | 'err';\n\
+         declare function check(o: Option, r?: Result): Box<string> | undefined;",
+    );
+    assert_contains(
+        &output,
+        &[
+            "#[wasm_bindgen(extends = js_sys::Object, js_name = Box)]",
+            "pub type JsBoxErased;",
+            "pub struct JsBox<T: JsCast = JsValue> {",
+            "#[wasm_bindgen(extends = js_sys::Object, js_name = Option, is_type_of = JsValue::is_object)]",
+            "pub type JsOption;",
+            "pub fn value(this: &JsOption) -> Option<f64>;",
+            "pub enum JsResult {",
+            "fn try_from(value: JsValue) -> Result<JsResult, JsValue> {",
+            "fn __check(o: &JsOption, r: Option<JsResult>) -> Option<JsBoxErased>;",
+        ],
+    );
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use common::{assert_contains, generate, generate_with};
+use dts2rs::{Config, TypedArrayMode};
+
+const GL: &str = "declare class Gl {\n\
+       bufferData(target: number, data: BufferSource): void;\n\
+       getBufferSubData(target: number, offset: number, dst: ArrayBufferView): void;\n\
+       readonly vertices: Float32Array;\n\
+     }\n\
+     declare function hash(bytes: Uint8Array, out: Uint32Array): ArrayBuffer;\n\
+     declare function clamp(c: Uint8ClampedArray): void;";
+
+#[test]
+fn js_sys_types() {
+    let output = generate(GL);
+    assert_contains(
+        &output,
+        &[
+            "pub fn vertices(this: &Gl) -> js_sys::Float32Array;",
+            "pub fn hash(bytes: &js_sys::Uint8Array, out: &js_sys::Uint32Array) -> js_sys::ArrayBuffer;",
+        ],
+    );
+}
+
+#[test]
+fn slices() {
+    let config = Config {
+        typed_arrays: TypedArrayMode::Slice,
+        mutable_slices: [
+            "Gl.getBufferSubData.dst".to_string(),
+            "hash.out".to_string(),
+        ]
+        .into(),
+        ..Config::default()
+    };
+    let output = generate_with(config, GL);
+    assert_contains(
+        &output,
+        &[
+            "    pub fn buffer_data(&self, target: f64, data: &[u8]) {\n        \
+             self.__buffer_data(target, &js_sys::Uint8Array::from(data))\n    }",
+            "pub fn get_buffer_sub_data(this: &Gl, target: f64, offset: f64, dst: &mut [u8]);",
+            "pub fn vertices(this: &Gl) -> Vec<f32>;",
+            "fn __hash(bytes: &[u8], out: &mut [u32]) -> js_sys::ArrayBuffer;",
+            "pub fn hash(bytes: &[u8], out: &mut [u32]) -> Vec<u8> {\n    \
+             js_sys::Uint8Array::new(&__hash(bytes, out)).to_vec()\n}",
+            // Clamped arrays have no slice type.
+            "pub fn clamp(c: &js_sys::Uint8ClampedArray);",
+        ],
+    );
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use common::{assert_contains, generate, generate_with};
+use dts2rs::{Config, UnionStrategy};
+
+const SHAPES: &str = "type Shape = Circle | Square;\n\
+     interface Circle { r: number }\n\
+     interface Square { s: number }\n\
+     declare function draw(mode: 'fill' | 'stroke', shape: Shape, value: string | number): void;\n\
+     declare function pick(options: string | number): void;";
+
+#[test]
+fn auto_strategy() {
+    let output = generate(SHAPES);
+    assert_contains(
+        &output,
+        &[
+            "pub fn draw(mode: DrawMode, shape: Shape, value: &JsValue);",
+            "pub fn pick(options: &JsValue);",
+            "pub enum Shape {\n    Circle(Circle),\n    Square(Square),\n}",
+            "impl From<Circle> for Shape {",
+            "impl TryFrom<JsValue> for Shape {",
+            "/// `'fill' | 'stroke'`\n#[wasm_bindgen]\n#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]\npub enum DrawMode {\n    Fill = \"fill\",\n    Stroke = \"stroke\",\n}",
+        ],
+    );
+}
+
+#[test]
+fn strategies_by_declaration() {
+    let config = Config {
+        union_strategy: UnionStrategy::StringEnum,
+        unions: [
+            ("Shape".to_string(), UnionStrategy::JsValue),
+            ("pick.options".to_string(), UnionStrategy::Enum),
+        ]
+        .into(),
+        ..Config::default()
+    };
+    let output = generate_with(config, SHAPES);
+    assert_contains(
+        &output,
+        &[
+            "pub fn draw(mode: DrawMode, shape: &JsValue, value: &JsValue);",
+            "pub fn pick(options: PickOptions);",
+            "pub enum PickOptions {\n    String(String),\n    Number(f64),\n}",
+            "impl From<f64> for PickOptions {",
+        ],
+    );
+    assert!(!output.code.contains("pub enum Shape"), "{}", output.code);
+}
+
+#[test]
+fn keys_cover_nested_and_namespaced_unions() {
+    let config = Config {
+        unions: [
+            ("Widget".to_string(), UnionStrategy::Enum),
+            ("NS.draw.options".to_string(), UnionStrategy::Enum),
+        ]
+        .into(),
+        ..Config::default()
+    };
+    let output = generate_with(
+        config,
+        "interface Widget { value: string | number; render(size: boolean | string): void }\n\
+         declare namespace NS { function draw(options: string | boolean): void }",
+    );
+    assert_contains(
+        &output,
+        &[
+            "pub fn value(this: &Widget) -> WidgetValue;",
+            "pub fn set_value(this: &Widget, value: WidgetValue);",
+            "pub fn render(this: &Widget, size: WidgetRenderSize);",
+            "pub enum WidgetRenderSize {",
+            "        pub fn draw(options: DrawOptions);",
+            "    pub enum DrawOptions {",
+        ],
+    );
+}
+
+#[test]
+fn js_value_strategy() {
+    let config = Config {
+        union_strategy: UnionStrategy::JsValue,
+        ..Config::default()
+    };
+    let output = generate_with(config, SHAPES);
+    assert_contains(
+        &output,
+        &["pub fn draw(mode: &JsValue, shape: &JsValue, value: &JsValue);"],
+    );
+    assert!(!output.code.contains("pub enum"), "{}", output.code);
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use common::{assert_contains, generate, messages};
+
+const CONFIG: &str =
+    "interface Config { a: string; b?: number; readonly c: boolean; run(x: number): string }\n";
+
+#[test]
+fn partial_of_pick() {
+    let output = generate(&format!(
+        "{}type Opts = Partial<Pick<Config, 'a' | 'b'>>;",
+        CONFIG
+    ));
+    assert_contains(
+        &output,
+        &[
+            "pub type Opts;",
+            "#[wasm_bindgen(method, structural, getter)]\n    pub fn a(this: &Opts) -> Option<String>;",
+            "pub fn set_a(this: &Opts, value: Option<&str>);",
+            "pub fn b(this: &Opts) -> Option<f64>;",
+        ],
+    );
+    assert!(!output.code.contains("pub fn c(this: &Opts)"));
+    assert!(!output.code.contains("pub fn run(this: &Opts"));
+}
+
+#[test]
+fn required_and_readonly_omit() {
+    let output = generate(&format!(
+        "{}type Strict = Required<Config>;\n\
+         type Frozen = Readonly<Omit<Config, 'run'>>;",
+        CONFIG
+    ));
+    assert_contains(
+        &output,
+        &[
+            "pub fn b(this: &Strict) -> f64;",
+            "pub fn set_b(this: &Strict, value: f64);",
+            "pub fn run(this: &Strict, x: f64) -> String;",
+            "pub fn a(this: &Frozen) -> String;",
+            "pub fn b(this: &Frozen) -> Option<f64>;",
+        ],
+    );
+    assert!(!output.code.contains("pub fn set_a(this: &Frozen"));
+    assert!(!output.code.contains("pub fn run(this: &Frozen"));
+}
+
+#[test]
+fn return_type_and_parameters() {
+    let output = generate(
+        "declare function f(x: number, y?: string): boolean;\n\
+         declare function make(): Config;\n\
+         interface Config { a: string }\n\
+         declare function take(args: Parameters<typeof f>, r: ReturnType<typeof f>, c: ReturnType<typeof make>): void;",
+    );
+    assert_contains(
+        &output,
+        &["pub fn take(args: &js_sys::Array, r: bool, c: &Config);"],
+    );
+}
+
+#[test]
+fn inline_utilities_are_bound_like_literals() {
+    let output = generate(&format!(
+        "{}declare function p(p: Partial<Config>): void;\n\
+         declare function q(p: Pick<Config, 'a' | 'c'>): Omit<Config, 'run'>;\n\
+         declare function r(p: Required<Config>): void;",
+        CONFIG
+    ));
+    assert_contains(
+        &output,
+        &[
+            "pub fn p(p: &PP);",
+            "pub fn q(p: &QP) -> QReturn;",
+            "pub fn r(p: &RP);",
+            "pub fn a(this: &PP) -> Option<String>;",
+            "pub fn c(this: &QP) -> bool;",
+            "pub fn b(this: &QReturn) -> Option<f64>;",
+            "pub fn b(this: &RP) -> f64;",
+        ],
+    );
+    assert!(!output.code.contains("pub fn b(this: &QP)"));
+    assert!(!output.code.contains("pub fn run(this: &QReturn"));
+    assert!(messages(&output).is_empty(), "{:?}", messages(&output));
+}
+
+#[test]
+fn return_type_of_lookups() {
+    let output = generate(
+        "interface Api { m: (x: number) => boolean; n(y: string): number }\n\
+         declare function s(): ReturnType<Api['m']>;\n\
+         declare function t(): ReturnType<Api['n']>;",
+    );
+    assert_contains(&output, &["pub fn s() -> bool;", "pub fn t() -> f64;"]);
+}
+
+#[test]
+fn utilities_that_cannot_be_evaluated_are_reported() {
+    let output = generate(
+        "declare function g<T>(p: Partial<T>): void;\n\
+         declare function h(p: ReturnType<Missing>): void;",
+    );
+    assert_contains(
+        &output,
+        &["pub fn g(p: &JsValue);", "pub fn h(p: &JsValue);"],
+    );
+    assert_eq!(
+        messages(&output),
+        [
+            "can't evaluate this utility type for every `T`; it is bound as `JsValue`",
+            "can't evaluate this utility type; it is bound as `JsValue`",
+        ]
+    );
+}